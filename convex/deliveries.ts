@@ -0,0 +1,56 @@
+import { mutation } from "./_generated/server";
+import { v } from "convex/values";
+
+// Expired deliveries removed per call, to keep each mutation small
+const PRUNE_BATCH = 100;
+
+// Remember a delivery; returns false if it is already remembered
+export const record = mutation({
+    args: { delivery_id: v.string(), ttl_ms: v.number() },
+    handler: async (ctx, args): Promise<boolean> => {
+        const now = Date.now();
+
+        const expired = await ctx.db
+            .query("deliveries")
+            .withIndex("by_expires_at", (q) => q.lte("expires_at", now))
+            .take(PRUNE_BATCH);
+
+        for (const delivery of expired) {
+            await ctx.db.delete(delivery._id);
+        }
+
+        const existing = await ctx.db
+            .query("deliveries")
+            .withIndex("by_delivery_id", (q) => q.eq("delivery_id", args.delivery_id))
+            .first();
+
+        if (existing && existing.expires_at > now) {
+            return false;
+        }
+
+        if (existing) {
+            await ctx.db.patch(existing._id, { expires_at: now + args.ttl_ms });
+        } else {
+            await ctx.db.insert("deliveries", {
+                delivery_id: args.delivery_id,
+                expires_at: now + args.ttl_ms,
+            });
+        }
+        return true;
+    },
+});
+
+// Forget a delivery so a redelivery is processed again (no-op if unknown)
+export const forget = mutation({
+    args: { delivery_id: v.string() },
+    handler: async (ctx, args) => {
+        const existing = await ctx.db
+            .query("deliveries")
+            .withIndex("by_delivery_id", (q) => q.eq("delivery_id", args.delivery_id))
+            .first();
+
+        if (existing) {
+            await ctx.db.delete(existing._id);
+        }
+    },
+});
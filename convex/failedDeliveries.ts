@@ -0,0 +1,71 @@
+import { query, mutation } from "./_generated/server";
+import { v } from "convex/values";
+
+const retryFields = {
+    // Destinations still to post to; omitted to retry the whole event
+    destinations: v.optional(v.any()),
+    error: v.string(),
+    attempts: v.number(),
+    next_attempt_at: v.number(),
+    dead_lettered: v.boolean(),
+};
+
+// Store a delivery whose dispatch failed
+export const insert = mutation({
+    args: {
+        delivery_id: v.optional(v.string()),
+        event_type: v.string(),
+        repo: v.string(),
+        payload: v.string(),
+        created_at: v.number(),
+        ...retryFields,
+    },
+    handler: async (ctx, args) => {
+        return await ctx.db.insert("failed_deliveries", args);
+    },
+});
+
+// Record the outcome of a retry
+export const update = mutation({
+    args: { id: v.string(), ...retryFields },
+    handler: async (ctx, { id, ...fields }) => {
+        const deliveryId = ctx.db.normalizeId("failed_deliveries", id);
+        if (!deliveryId || !(await ctx.db.get(deliveryId))) {
+            return { success: false, error: "Failed delivery not found" };
+        }
+        // Patching with `undefined` removes a field, so an omitted
+        // `destinations` clears the subset of an earlier partial failure
+        await ctx.db.patch(deliveryId, { destinations: undefined, ...fields });
+        return { success: true };
+    },
+});
+
+// Remove a delivery once it went through
+export const remove = mutation({
+    args: { id: v.string() },
+    handler: async (ctx, args) => {
+        const deliveryId = ctx.db.normalizeId("failed_deliveries", args.id);
+        if (!deliveryId || !(await ctx.db.get(deliveryId))) {
+            return { success: false, error: "Failed delivery not found" };
+        }
+        await ctx.db.delete(deliveryId);
+        return { success: true };
+    },
+});
+
+// Get a failed delivery (null for unknown or malformed ids)
+export const get = query({
+    args: { id: v.string() },
+    handler: async (ctx, args) => {
+        const deliveryId = ctx.db.normalizeId("failed_deliveries", args.id);
+        return deliveryId ? await ctx.db.get(deliveryId) : null;
+    },
+});
+
+// All failed deliveries, oldest first
+export const list = query({
+    args: {},
+    handler: async (ctx) => {
+        return await ctx.db.query("failed_deliveries").order("asc").collect();
+    },
+});
@@ -0,0 +1,60 @@
+import { query, mutation } from "./_generated/server";
+import { v } from "convex/values";
+
+// Get the message last posted for a GitHub item
+export const get = query({
+    args: { item_key: v.string() },
+    handler: async (ctx, args) => {
+        return await ctx.db
+            .query("posted_messages")
+            .withIndex("by_item_key", (q) => q.eq("item_key", args.item_key))
+            .first();
+    },
+});
+
+// Record (or replace) the message posted for a GitHub item
+export const save = mutation({
+    args: { item_key: v.string(), channel_id: v.string(), message_id: v.string() },
+    handler: async (ctx, args) => {
+        const existing = await ctx.db
+            .query("posted_messages")
+            .withIndex("by_item_key", (q) => q.eq("item_key", args.item_key))
+            .first();
+
+        if (existing) {
+            await ctx.db.patch(existing._id, {
+                channel_id: args.channel_id,
+                message_id: args.message_id,
+            });
+            return existing._id;
+        }
+
+        return await ctx.db.insert("posted_messages", args);
+    },
+});
+
+// Move the messages of a repo to its new name after a GitHub rename or transfer
+export const renameRepo = mutation({
+    args: { from: v.string(), to: v.string() },
+    handler: async (ctx, args) => {
+        const from = args.from + "/";
+        const messages = await ctx.db
+            .query("posted_messages")
+            .withIndex("by_item_key", (q) =>
+                q.gte("item_key", from).lt("item_key", from + "\uffff")
+            )
+            .collect();
+
+        for (const message of messages) {
+            const item_key = args.to + "/" + message.item_key.slice(from.length);
+            const existing = await ctx.db
+                .query("posted_messages")
+                .withIndex("by_item_key", (q) => q.eq("item_key", item_key))
+                .first();
+            if (existing) {
+                await ctx.db.delete(existing._id);
+            }
+            await ctx.db.patch(message._id, { item_key });
+        }
+    },
+});
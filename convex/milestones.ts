@@ -0,0 +1,61 @@
+import { query, mutation } from "./_generated/server";
+import { v } from "convex/values";
+
+const milestoneFields = {
+    number: v.number(),
+    title: v.string(),
+    description: v.optional(v.string()),
+    html_url: v.string(),
+    state: v.string(),
+    open_issues: v.number(),
+    closed_issues: v.number(),
+    due_on: v.optional(v.string()),
+};
+
+// Record (or replace) the latest snapshot of a project's milestone
+export const save = mutation({
+    args: { project_id: v.id("projects"), ...milestoneFields },
+    handler: async (ctx, args) => {
+        const existing = await ctx.db
+            .query("milestones")
+            .withIndex("by_project_number", (q) =>
+                q.eq("project_id", args.project_id).eq("number", args.number)
+            )
+            .first();
+
+        if (existing) {
+            await ctx.db.replace(existing._id, args);
+            return existing._id;
+        }
+
+        return await ctx.db.insert("milestones", args);
+    },
+});
+
+// Forget a deleted milestone (no-op if unknown)
+export const remove = mutation({
+    args: { project_id: v.id("projects"), number: v.number() },
+    handler: async (ctx, args) => {
+        const existing = await ctx.db
+            .query("milestones")
+            .withIndex("by_project_number", (q) =>
+                q.eq("project_id", args.project_id).eq("number", args.number)
+            )
+            .first();
+
+        if (existing) {
+            await ctx.db.delete(existing._id);
+        }
+    },
+});
+
+// All stored milestones of a project, by number
+export const listByProject = query({
+    args: { project_id: v.id("projects") },
+    handler: async (ctx, args) => {
+        return await ctx.db
+            .query("milestones")
+            .withIndex("by_project_number", (q) => q.eq("project_id", args.project_id))
+            .collect();
+    },
+});
@@ -78,6 +78,56 @@ export const approveWithForum = mutation({
                 conditions: { event_type: "issues.opened" },
                 actions: { post_forum: true, post_announce: false },
             },
+            {
+                conditions: { event_type: "deployment.created" },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: { event_type: "deployment_status.created" },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: { event_type: "check_suite.completed" },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: { event_type: "milestone.created" },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: { event_type: "star.created" },
+                actions: { post_forum: true, post_announce: true },
+            },
+            {
+                conditions: { event_type: "issue_comment.created" },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: { event_type: "pull_request_review_comment.created" },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: {
+                    event_type: "pull_request_review.submitted",
+                    review_state: "approved",
+                },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: {
+                    event_type: "pull_request_review.submitted",
+                    review_state: "changes_requested",
+                },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: { event_type: "discussion.created" },
+                actions: { post_forum: true, post_announce: false },
+            },
+            {
+                conditions: { event_type: "discussion.answered" },
+                actions: { post_forum: true, post_announce: false },
+            },
         ];
 
         for (let i = 0; i < defaultRules.length; i++) {
@@ -138,6 +188,15 @@ export const deny = mutation({
             await ctx.db.delete(rule._id);
         }
 
+        const milestones = await ctx.db
+            .query("milestones")
+            .withIndex("by_project_number", (q) => q.eq("project_id", project._id))
+            .collect();
+
+        for (const milestone of milestones) {
+            await ctx.db.delete(milestone._id);
+        }
+
         await ctx.db.delete(project._id);
         return { success: true };
     },
@@ -222,3 +281,35 @@ export const updateThreadId = mutation({
         return { success: false };
     },
 });
+
+// Move a project to a new repo key after a GitHub rename or transfer
+export const rename = mutation({
+    args: { github_repo: v.string(), new_github_repo: v.string() },
+    handler: async (ctx, args): Promise<MutationResult> => {
+        const new_github_repo = args.new_github_repo.toLowerCase();
+        const name = new_github_repo.split("/").pop() || new_github_repo;
+
+        const taken = await ctx.db
+            .query("projects")
+            .withIndex("by_github_repo", (q) => q.eq("github_repo", new_github_repo))
+            .first();
+
+        if (taken) {
+            return { success: false, error: "Project already exists" };
+        }
+
+        const project = await ctx.db
+            .query("projects")
+            .withIndex("by_github_repo", (q) =>
+                q.eq("github_repo", args.github_repo.toLowerCase())
+            )
+            .first();
+
+        if (!project) {
+            return { success: false, error: "Project not found" };
+        }
+
+        await ctx.db.patch(project._id, { github_repo: new_github_repo, name });
+        return { success: true, id: project._id };
+    },
+});
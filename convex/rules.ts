@@ -13,50 +13,6 @@ export const getByProject = query({
     },
 });
 
-// Evaluate rules and find matching actions
-export const evaluateForProject = query({
-    args: {
-        project_id: v.id("projects"),
-        event_key: v.optional(v.string()),
-        is_merged: v.boolean(),
-    },
-    handler: async (ctx, args) => {
-        const rules = await ctx.db
-            .query("rules")
-            .withIndex("by_project", (q) => q.eq("project_id", args.project_id))
-            .order("desc")
-            .collect();
-
-        // Sort by priority descending
-        rules.sort((a, b) => b.priority - a.priority);
-
-        for (const rule of rules) {
-            const conditions = rule.conditions as {
-                event_type?: string;
-                merged?: boolean;
-            };
-
-            // Check event_type condition
-            if (conditions.event_type && conditions.event_type !== args.event_key) {
-                continue;
-            }
-
-            // Check merged condition
-            if (conditions.merged !== undefined && conditions.merged !== args.is_merged) {
-                continue;
-            }
-
-            // All conditions matched
-            return {
-                rule_id: rule._id,
-                actions: rule.actions,
-            };
-        }
-
-        return null;
-    },
-});
-
 // Create a rule
 export const create = mutation({
     args: {
@@ -74,3 +30,29 @@ export const create = mutation({
         });
     },
 });
+
+// Delete a rule
+export const remove = mutation({
+    args: { rule_id: v.id("rules") },
+    handler: async (ctx, args) => {
+        const rule = await ctx.db.get(args.rule_id);
+        if (!rule) {
+            return { success: false, error: "Rule not found" };
+        }
+        await ctx.db.delete(args.rule_id);
+        return { success: true };
+    },
+});
+
+// Change a rule's priority (higher runs first)
+export const updatePriority = mutation({
+    args: { rule_id: v.id("rules"), priority: v.number() },
+    handler: async (ctx, args) => {
+        const rule = await ctx.db.get(args.rule_id);
+        if (!rule) {
+            return { success: false, error: "Rule not found" };
+        }
+        await ctx.db.patch(args.rule_id, { priority: args.priority });
+        return { success: true };
+    },
+});
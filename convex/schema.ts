@@ -14,10 +14,12 @@ export default defineSchema({
         .index("by_github_repo", ["github_repo"])
         .index("by_guild", ["guild_id"]),
 
-    // Whitelisted GitHub usernames (future work)
+    // Whitelisted GitHub usernames per guild (stored lowercase).
+    // guild_id is optional only so legacy unscoped entries still validate.
     whitelist: defineTable({
+        guild_id: v.optional(v.string()),
         github_username: v.string(),
-    }).index("by_username", ["github_username"]),
+    }).index("by_guild_username", ["guild_id", "github_username"]),
 
     // Discord moderator IDs
     moderators: defineTable({
@@ -37,5 +39,50 @@ export default defineSchema({
         guild_id: v.string(),
         announcements_id: v.string(),
         github_forum_id: v.string(),
+        // Private channel for security alerts (servers set up before it existed lack it)
+        moderator_channel_id: v.optional(v.string()),
     }).index("by_guild", ["guild_id"]),
+
+    // Discord messages edited in place when their GitHub item changes
+    posted_messages: defineTable({
+        item_key: v.string(),
+        channel_id: v.string(),
+        message_id: v.string(),
+    }).index("by_item_key", ["item_key"]),
+
+    // Latest milestone snapshots from webhook payloads, for /milestone
+    milestones: defineTable({
+        project_id: v.id("projects"),
+        number: v.number(),
+        title: v.string(),
+        description: v.optional(v.string()),
+        html_url: v.string(),
+        state: v.string(),
+        open_issues: v.number(),
+        closed_issues: v.number(),
+        due_on: v.optional(v.string()),
+    }).index("by_project_number", ["project_id", "number"]),
+
+    // Webhook deliveries seen recently (X-GitHub-Delivery), for de-duplication
+    deliveries: defineTable({
+        delivery_id: v.string(),
+        expires_at: v.number(),
+    })
+        .index("by_delivery_id", ["delivery_id"])
+        .index("by_expires_at", ["expires_at"]),
+
+    // Deliveries that failed to dispatch, retried with backoff and then
+    // kept as dead letters until replayed
+    failed_deliveries: defineTable({
+        delivery_id: v.optional(v.string()),
+        event_type: v.string(),
+        repo: v.string(),
+        payload: v.string(),
+        destinations: v.optional(v.any()),
+        error: v.string(),
+        attempts: v.number(),
+        next_attempt_at: v.number(),
+        dead_lettered: v.boolean(),
+        created_at: v.number(),
+    }),
 });
@@ -54,3 +54,24 @@ export const save = mutation({
         });
     },
 });
+
+// Record the private moderator channel used for security alerts
+export const updateModeratorChannel = mutation({
+    args: { guild_id: v.string(), moderator_channel_id: v.string() },
+    handler: async (ctx, args): Promise<{ success: boolean }> => {
+        const existing = await ctx.db
+            .query("server_config")
+            .withIndex("by_guild", (q) => q.eq("guild_id", args.guild_id))
+            .first();
+
+        if (!existing) {
+            return { success: false };
+        }
+        if (existing.moderator_channel_id !== args.moderator_channel_id) {
+            await ctx.db.patch(existing._id, {
+                moderator_channel_id: args.moderator_channel_id,
+            });
+        }
+        return { success: true };
+    },
+});
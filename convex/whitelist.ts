@@ -0,0 +1,79 @@
+import { query, mutation } from "./_generated/server";
+import { v } from "convex/values";
+
+// Entries are scoped per guild. Legacy entries without a guild_id are ignored.
+
+// Add a GitHub user to a guild's whitelist (idempotent)
+export const add = mutation({
+    args: { guild_id: v.string(), github_username: v.string() },
+    handler: async (ctx, args) => {
+        const github_username = args.github_username.toLowerCase();
+        const existing = await ctx.db
+            .query("whitelist")
+            .withIndex("by_guild_username", (q) =>
+                q.eq("guild_id", args.guild_id).eq("github_username", github_username)
+            )
+            .first();
+
+        if (existing) {
+            return existing._id;
+        }
+
+        return await ctx.db.insert("whitelist", {
+            guild_id: args.guild_id,
+            github_username,
+        });
+    },
+});
+
+// Remove a GitHub user from a guild's whitelist
+export const remove = mutation({
+    args: { guild_id: v.string(), github_username: v.string() },
+    handler: async (ctx, args) => {
+        const entry = await ctx.db
+            .query("whitelist")
+            .withIndex("by_guild_username", (q) =>
+                q
+                    .eq("guild_id", args.guild_id)
+                    .eq("github_username", args.github_username.toLowerCase())
+            )
+            .first();
+
+        if (!entry) {
+            return { success: false, error: "User is not whitelisted" };
+        }
+
+        await ctx.db.delete(entry._id);
+        return { success: true };
+    },
+});
+
+// List the whitelisted usernames of a guild
+export const listByGuild = query({
+    args: { guild_id: v.string() },
+    handler: async (ctx, args) => {
+        const entries = await ctx.db
+            .query("whitelist")
+            .withIndex("by_guild_username", (q) => q.eq("guild_id", args.guild_id))
+            .collect();
+
+        return entries.map((e) => e.github_username);
+    },
+});
+
+// Check whether a GitHub user is whitelisted in a guild
+export const isWhitelisted = query({
+    args: { guild_id: v.string(), github_username: v.string() },
+    handler: async (ctx, args) => {
+        const entry = await ctx.db
+            .query("whitelist")
+            .withIndex("by_guild_username", (q) =>
+                q
+                    .eq("guild_id", args.guild_id)
+                    .eq("github_username", args.github_username.toLowerCase())
+            )
+            .first();
+
+        return entry !== null;
+    },
+});
@@ -54,15 +54,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
         {
             "name": "whitelist-user",
-            "description": "Add a GitHub user to the whitelist (mod only)",
+            "description": "Manage trusted GitHub users of this server (mod only)",
             "dm_permission": false,
             "contexts": [0],
             "options": [
                 {
-                    "name": "username",
-                    "description": "GitHub username",
-                    "type": 3,
-                    "required": true
+                    "name": "add",
+                    "description": "Whitelist a GitHub user",
+                    "type": 1,
+                    "options": [
+                        { "name": "username", "description": "GitHub username", "type": 3, "required": true }
+                    ]
+                },
+                {
+                    "name": "remove",
+                    "description": "Remove a GitHub user from the whitelist",
+                    "type": 1,
+                    "options": [
+                        { "name": "username", "description": "GitHub username", "type": 3, "required": true }
+                    ]
+                },
+                {
+                    "name": "list",
+                    "description": "List whitelisted GitHub users",
+                    "type": 1
                 }
             ]
         },
@@ -72,6 +87,124 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "dm_permission": false,
             "contexts": [0]
         },
+        {
+            "name": "rules",
+            "description": "Manage a project's routing rules (mod only)",
+            "dm_permission": false,
+            "contexts": [0],
+            "options": [
+                {
+                    "name": "list",
+                    "description": "List rules, highest priority first",
+                    "type": 1,
+                    "options": [
+                        { "name": "repo", "description": "GitHub repo", "type": 3, "required": true }
+                    ]
+                },
+                {
+                    "name": "add",
+                    "description": "Add a routing rule",
+                    "type": 1,
+                    "options": [
+                        { "name": "repo", "description": "GitHub repo", "type": 3, "required": true },
+                        { "name": "event", "description": "Event type (e.g. pull_request.closed)", "type": 3 },
+                        { "name": "labels", "description": "Match any of these labels (comma-separated)", "type": 3 },
+                        { "name": "labels_all", "description": "Match all of these labels (comma-separated)", "type": 3 },
+                        { "name": "labels_none", "description": "Match none of these labels (comma-separated)", "type": 3 },
+                        { "name": "merged", "description": "Only merged (true) or unmerged (false) pull requests", "type": 5 },
+                        { "name": "actor_whitelisted", "description": "Only whitelisted (true) or other (false) actors", "type": 5 },
+                        { "name": "branches", "description": "Match any of these branches, `*` as suffix (comma-separated)", "type": 3 },
+                        { "name": "review_state", "description": "Pull request review state", "type": 3, "choices": [
+                            { "name": "approved", "value": "approved" },
+                            { "name": "changes requested", "value": "changes_requested" },
+                            { "name": "commented", "value": "commented" }
+                        ] },
+                        { "name": "category", "description": "Discussion category (e.g. Q&A)", "type": 3 },
+                        { "name": "activity", "description": "Post to the activity thread (default: true)", "type": 5 },
+                        { "name": "forum", "description": "Post to the sidebar thread (default: false)", "type": 5 },
+                        { "name": "announce", "description": "Post to announcements (default: false)", "type": 5 },
+                        { "name": "continue", "description": "Keep evaluating lower-priority rules (default: false)", "type": 5 },
+                        { "name": "priority", "description": "Higher runs first (default: above all existing rules)", "type": 4 },
+                        { "name": "template", "description": "Message template, e.g. {repo}: #{number} {title}\\n{url}", "type": 3 }
+                    ]
+                },
+                {
+                    "name": "remove",
+                    "description": "Remove a rule",
+                    "type": 1,
+                    "options": [
+                        { "name": "repo", "description": "GitHub repo", "type": 3, "required": true },
+                        { "name": "index", "description": "Rule number from /rules list", "type": 4, "required": true, "min_value": 1 }
+                    ]
+                },
+                {
+                    "name": "test",
+                    "description": "Dry-run a sample event through the rules without posting",
+                    "type": 1,
+                    "options": [
+                        { "name": "repo", "description": "GitHub repo", "type": 3, "required": true },
+                        { "name": "event", "description": "Event type (e.g. pull_request.closed)", "type": 3, "required": true },
+                        { "name": "labels", "description": "Labels on the sample (comma-separated)", "type": 3 },
+                        { "name": "merged", "description": "Sample pull request is merged", "type": 5 },
+                        { "name": "branch", "description": "Branch of the sample push or workflow run (default: main)", "type": 3 },
+                        { "name": "review_state", "description": "State of the sample review (default: approved)", "type": 3, "choices": [
+                            { "name": "approved", "value": "approved" },
+                            { "name": "changes requested", "value": "changes_requested" },
+                            { "name": "commented", "value": "commented" }
+                        ] },
+                        { "name": "actor", "description": "GitHub username of the sample sender", "type": 3 }
+                    ]
+                },
+                {
+                    "name": "priority",
+                    "description": "Change a rule's priority",
+                    "type": 1,
+                    "options": [
+                        { "name": "repo", "description": "GitHub repo", "type": 3, "required": true },
+                        { "name": "index", "description": "Rule number from /rules list", "type": 4, "required": true, "min_value": 1 },
+                        { "name": "priority", "description": "New priority (higher runs first)", "type": 4, "required": true }
+                    ]
+                }
+            ]
+        },
+        {
+            "name": "milestone",
+            "description": "Show the completion of a project's open milestones",
+            "dm_permission": false,
+            "contexts": [0],
+            "options": [
+                {
+                    "name": "repo",
+                    "description": "GitHub repo (e.g. owner/repo-name)",
+                    "type": 3,
+                    "required": true
+                }
+            ]
+        },
+        {
+            "name": "deliveries",
+            "description": "Inspect and replay webhook deliveries that could not be posted (mod only)",
+            "dm_permission": false,
+            "contexts": [0],
+            "options": [
+                {
+                    "name": "failed",
+                    "description": "List failed and dead-lettered deliveries, oldest first",
+                    "type": 1,
+                    "options": [
+                        { "name": "repo", "description": "Only this GitHub repo", "type": 3 }
+                    ]
+                },
+                {
+                    "name": "replay",
+                    "description": "Retry a failed delivery now",
+                    "type": 1,
+                    "options": [
+                        { "name": "id", "description": "Id from /deliveries failed", "type": 3, "required": true }
+                    ]
+                }
+            ]
+        },
         {
             "name": "setup-server",
             "description": "Set up ByteHub channels in this server (mod only)",
@@ -1,21 +1,82 @@
 use std::env;
 
+/// Which storage backend ByteHub persists to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// Convex cloud deployment (requires `CONVEX_URL`)
+    Convex,
+    /// Embedded SQLite database at `DATABASE_PATH`
+    Sqlite,
+    /// Volatile in-memory storage (tests and `--dev` mode)
+    Memory,
+}
+
+impl StorageBackend {
+    /// Read `STORAGE_BACKEND` (defaults to Convex)
+    pub fn from_env() -> Self {
+        match env::var("STORAGE_BACKEND")
+            .unwrap_or_else(|_| "convex".into())
+            .to_lowercase()
+            .as_str()
+        {
+            "convex" => StorageBackend::Convex,
+            "sqlite" => StorageBackend::Sqlite,
+            "memory" => StorageBackend::Memory,
+            other => panic!(
+                "STORAGE_BACKEND must be 'convex', 'sqlite' or 'memory', got '{}'",
+                other
+            ),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageBackend::Convex => "Convex",
+            StorageBackend::Sqlite => "SQLite",
+            StorageBackend::Memory => "In-memory (dev)",
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Config {
+    pub storage_backend: StorageBackend,
     pub convex_url: String,
+    pub database_path: String,
     pub github_webhook_secret: String,
     pub discord_public_key: String,
     pub discord_bot_token: String,
     pub discord_application_id: u64,
     pub discord_invite: Option<String>,
+    /// Bearer token for the `/admin/*` endpoints; they are disabled when unset
+    pub admin_token: Option<String>,
+    /// Token for GitHub's REST API, used to backfill missed webhook
+    /// deliveries; backfill is disabled when unset
+    pub github_token: Option<String>,
     pub host: String,
     pub port: u16,
+    /// Background workers dispatching webhook events
+    pub dispatch_workers: usize,
+    /// Events waiting per worker before new deliveries are rejected
+    pub dispatch_queue_size: usize,
 }
 
 impl Config {
     pub fn from_env() -> Self {
+        Self::from_env_with_backend(StorageBackend::from_env())
+    }
+
+    /// Read the config from the environment, forcing a storage backend
+    pub fn from_env_with_backend(storage_backend: StorageBackend) -> Self {
+        let convex_url = match storage_backend {
+            StorageBackend::Convex => env::var("CONVEX_URL").expect("CONVEX_URL required"),
+            _ => env::var("CONVEX_URL").unwrap_or_default(),
+        };
+
         Self {
-            convex_url: env::var("CONVEX_URL").expect("CONVEX_URL required"),
+            storage_backend,
+            convex_url,
+            database_path: env::var("DATABASE_PATH").unwrap_or_else(|_| "bytehub.db".into()),
             github_webhook_secret: env::var("GITHUB_WEBHOOK_SECRET")
                 .expect("GITHUB_WEBHOOK_SECRET required"),
             discord_public_key: env::var("DISCORD_PUBLIC_KEY")
@@ -26,11 +87,23 @@ impl Config {
                 .parse()
                 .expect("DISCORD_APPLICATION_ID must be a valid u64"),
             discord_invite: env::var("DISCORD_INVITE").ok(),
+            admin_token: env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty()),
+            github_token: env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()),
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".into()),
             port: env::var("PORT")
                 .unwrap_or_else(|_| "3000".into())
                 .parse()
                 .unwrap_or(3000),
+            dispatch_workers: env::var("DISPATCH_WORKERS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(4),
+            dispatch_queue_size: env::var("DISPATCH_QUEUE_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(100),
         }
     }
 }
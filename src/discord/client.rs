@@ -5,16 +5,25 @@ use twilight_http::Client;
 use twilight_model::channel::message::embed::{Embed, EmbedFooter};
 use twilight_model::channel::ChannelType;
 use twilight_model::id::{
-    marker::{ApplicationMarker, ChannelMarker, GuildMarker},
+    marker::{ApplicationMarker, ChannelMarker, GuildMarker, MessageMarker},
     Id,
 };
 
+/// Name of the private channel security alerts are posted to
+pub const MODERATOR_CHANNEL_NAME: &str = "security-alerts";
+
 #[async_trait]
 pub trait DiscordInterface: Send + Sync {
     async fn create_announcements_channel(
         &self,
         guild_id: Id<GuildMarker>,
     ) -> Result<Id<ChannelMarker>>;
+    /// Create the private channel for security alerts (hidden from @everyone,
+    /// visible to roles with Manage Server)
+    async fn create_moderator_channel(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Id<ChannelMarker>>;
     async fn create_github_category(&self, guild_id: Id<GuildMarker>) -> Result<Id<ChannelMarker>>;
     async fn create_project_forum(
         &self,
@@ -85,9 +94,21 @@ pub trait DiscordInterface: Send + Sync {
         description: &str,
         color: u32,
         footer: Option<&str>,
+    ) -> Result<Id<MessageMarker>>;
+    /// Replace the embed of a message posted by the bot
+    async fn edit_message_embed(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        message_id: Id<MessageMarker>,
+        title: &str,
+        description: &str,
+        color: u32,
+        footer: Option<&str>,
     ) -> Result<()>;
     async fn lock_thread(&self, thread_id: Id<ChannelMarker>) -> Result<()>;
     async fn pin_and_lock_thread(&self, thread_id: Id<ChannelMarker>) -> Result<()>;
+    /// Rename a channel or thread
+    async fn rename_channel(&self, channel_id: Id<ChannelMarker>, name: &str) -> Result<()>;
 }
 
 #[derive(Clone)]
@@ -151,6 +172,77 @@ impl DiscordInterface for DiscordClient {
         Ok(channel.id)
     }
 
+    /// Create the security alerts channel. Only administrators, roles with
+    /// Manage Server (the moderators of `check_moderator`) and the bot itself
+    /// have access.
+    async fn create_moderator_channel(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Id<ChannelMarker>> {
+        use twilight_model::channel::permission_overwrite::{
+            PermissionOverwrite, PermissionOverwriteType,
+        };
+        use twilight_model::guild::Permissions;
+        use twilight_model::id::marker::RoleMarker;
+
+        // @everyone role ID is the same as guild ID
+        let everyone_role_id: Id<RoleMarker> = Id::new(guild_id.get());
+
+        let guild_roles = self
+            .http
+            .roles(guild_id)
+            .await
+            .map_err(|e| Error::Discord(e.to_string()))?
+            .model()
+            .await
+            .map_err(|e| Error::Discord(e.to_string()))?;
+
+        // A bot's user ID is its application ID
+        let mut overwrites = vec![
+            PermissionOverwrite {
+                id: everyone_role_id.cast(),
+                kind: PermissionOverwriteType::Role,
+                allow: Permissions::empty(),
+                deny: Permissions::VIEW_CHANNEL,
+            },
+            PermissionOverwrite {
+                id: self.application_id.cast(),
+                kind: PermissionOverwriteType::Member,
+                allow: Permissions::VIEW_CHANNEL
+                    | Permissions::SEND_MESSAGES
+                    | Permissions::EMBED_LINKS,
+                deny: Permissions::empty(),
+            },
+        ];
+        // Administrators see every channel anyway, Manage Server does not
+        overwrites.extend(
+            guild_roles
+                .iter()
+                .filter(|r| {
+                    r.id != everyone_role_id && r.permissions.contains(Permissions::MANAGE_GUILD)
+                })
+                .map(|r| PermissionOverwrite {
+                    id: r.id.cast(),
+                    kind: PermissionOverwriteType::Role,
+                    allow: Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY,
+                    deny: Permissions::empty(),
+                }),
+        );
+
+        let channel = self
+            .http
+            .create_guild_channel(guild_id, MODERATOR_CHANNEL_NAME)
+            .kind(ChannelType::GuildText)
+            .permission_overwrites(&overwrites)
+            .await
+            .map_err(|e| Error::Discord(e.to_string()))?
+            .model()
+            .await
+            .map_err(|e| Error::Discord(e.to_string()))?;
+
+        Ok(channel.id)
+    }
+
     /// Create GitHub category (container for project forums)
     async fn create_github_category(&self, guild_id: Id<GuildMarker>) -> Result<Id<ChannelMarker>> {
         let channel = self
@@ -427,25 +519,7 @@ impl DiscordInterface for DiscordClient {
         color: u32,
         footer: Option<&str>,
     ) -> Result<Id<ChannelMarker>> {
-        let embed = Embed {
-            author: None,
-            color: Some(color),
-            description: Some(description.to_string()),
-            fields: vec![],
-            footer: footer.map(|f| EmbedFooter {
-                icon_url: None,
-                proxy_icon_url: None,
-                text: f.to_string(),
-            }),
-            image: None,
-            kind: "rich".to_string(),
-            provider: None,
-            thumbnail: None,
-            timestamp: None,
-            title: Some(title.to_string()),
-            url: None,
-            video: None,
-        };
+        let embed = rich_embed(title, description, color, footer);
 
         let thread = self
             .http
@@ -476,31 +550,36 @@ impl DiscordInterface for DiscordClient {
         description: &str,
         color: u32,
         footer: Option<&str>,
-    ) -> Result<()> {
-        let embed = Embed {
-            author: None,
-            color: Some(color),
-            description: Some(description.to_string()),
-            fields: vec![],
-            footer: footer.map(|f| EmbedFooter {
-                icon_url: None,
-                proxy_icon_url: None,
-                text: f.to_string(),
-            }),
-            image: None,
-            kind: "rich".to_string(),
-            provider: None,
-            thumbnail: None,
-            timestamp: None,
-            title: Some(title.to_string()),
-            url: None,
-            video: None,
-        };
+    ) -> Result<Id<MessageMarker>> {
+        let embed = rich_embed(title, description, color, footer);
 
-        self.http
+        let message = self
+            .http
             .create_message(thread_id)
             .embeds(&[embed])
             .await
+            .map_err(|e| Error::Discord(e.to_string()))?
+            .model()
+            .await
+            .map_err(|e| Error::Discord(e.to_string()))?;
+        Ok(message.id)
+    }
+
+    async fn edit_message_embed(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        message_id: Id<MessageMarker>,
+        title: &str,
+        description: &str,
+        color: u32,
+        footer: Option<&str>,
+    ) -> Result<()> {
+        let embed = rich_embed(title, description, color, footer);
+
+        self.http
+            .update_message(channel_id, message_id)
+            .embeds(Some(&[embed]))
+            .await
             .map_err(|e| Error::Discord(e.to_string()))?;
         Ok(())
     }
@@ -539,4 +618,36 @@ impl DiscordInterface for DiscordClient {
 
         Ok(())
     }
+
+    async fn rename_channel(&self, channel_id: Id<ChannelMarker>, name: &str) -> Result<()> {
+        self.http
+            .update_channel(channel_id)
+            .name(name)
+            .await
+            .map_err(|e| Error::Discord(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+fn rich_embed(title: &str, description: &str, color: u32, footer: Option<&str>) -> Embed {
+    Embed {
+        author: None,
+        color: Some(color),
+        description: Some(description.to_string()),
+        fields: vec![],
+        footer: footer.map(|f| EmbedFooter {
+            icon_url: None,
+            proxy_icon_url: None,
+            text: f.to_string(),
+        }),
+        image: None,
+        kind: "rich".to_string(),
+        provider: None,
+        thumbnail: None,
+        timestamp: None,
+        title: Some(title.to_string()),
+        url: None,
+        video: None,
+    }
 }
@@ -3,11 +3,16 @@ use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 use tracing::warn;
 
+use crate::discord::formatters::{format_milestone_progress, truncate};
 use crate::discord::rate_limit::RateLimiter;
 use crate::discord::verify::verify_discord_signature;
 use crate::error::{Error, Result};
-use crate::governance::{projects, server_config, whitelist};
-use crate::storage::convex::ConvexDb;
+use crate::governance::rules::{self, Rule, RuleActions, RuleConditions};
+use crate::governance::{milestones, projects, server_config, whitelist};
+use crate::router::dispatch::{Destination, DispatchPlan, Dispatcher};
+use crate::router::retries::{self, FailedDelivery};
+use crate::router::samples::{self, SampleOptions};
+use crate::storage::Storage;
 use crate::AppState;
 
 use twilight_model::guild::Permissions;
@@ -44,7 +49,11 @@ pub struct InteractionData {
 #[derive(Debug, Clone, Deserialize)]
 pub struct CommandOption {
     pub name: String,
+    /// Absent for subcommands, which carry their arguments in `options`
+    #[serde(default)]
     pub value: serde_json::Value,
+    #[serde(default)]
+    pub options: Option<Vec<CommandOption>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -185,10 +194,15 @@ pub async fn handle_interaction(
         }
 
         let response = match data.name.as_str() {
-            "submit-project" => handle_submit_project(&state.db, data).await?,
-            "deny" => handle_deny(&state.db, member, data).await?,
-            "whitelist-user" => handle_whitelist(&state.db, member, data).await?,
-            "list" => handle_list(&state.db, member, &interaction.guild_id).await?,
+            "submit-project" => handle_submit_project(state.db.as_ref(), data).await?,
+            "deny" => handle_deny(state.db.as_ref(), member, data).await?,
+            "whitelist-user" => {
+                handle_whitelist(state.db.as_ref(), member, data, &interaction.guild_id).await?
+            }
+            "list" => handle_list(state.db.as_ref(), member, &interaction.guild_id).await?,
+            "rules" => handle_rules(&state, member, data, &interaction.guild_id).await?,
+            "milestone" => handle_milestone(state.db.as_ref(), data, &interaction.guild_id).await?,
+            "deliveries" => handle_deliveries(&state, member, data, &interaction.guild_id).await?,
             _ => "Unknown command".to_string(),
         };
 
@@ -207,7 +221,7 @@ pub async fn handle_interaction(
     }))
 }
 
-async fn handle_submit_project(db: &ConvexDb, data: &InteractionData) -> Result<String> {
+async fn handle_submit_project(db: &dyn Storage, data: &InteractionData) -> Result<String> {
     let opts = data
         .options
         .as_ref()
@@ -270,7 +284,7 @@ pub async fn do_approve(
     }
 
     // Get server config to find the GitHub category
-    let config = server_config::get_config(&state.db, guild_id_str)
+    let config = server_config::get_config(state.db.as_ref(), guild_id_str)
         .await?
         .ok_or(Error::InvalidPayload(
             "Server not set up. Run /setup-server first.".into(),
@@ -283,7 +297,7 @@ pub async fn do_approve(
             // Category exists - sync config if ID changed
             if id.get().to_string() != config.github_forum_id {
                 server_config::save_config(
-                    &state.db,
+                    state.db.as_ref(),
                     guild_id_str,
                     &config.announcements_id,
                     &id.get().to_string(),
@@ -296,7 +310,7 @@ pub async fn do_approve(
             // Category missing - create and update config
             let id = state.discord.create_github_category(gid).await?;
             server_config::save_config(
-                &state.db,
+                state.db.as_ref(),
                 guild_id_str,
                 &config.announcements_id,
                 &id.get().to_string(),
@@ -312,7 +326,7 @@ pub async fn do_approve(
     // Check if project already has a forum channel and if it still exists in Discord
     let channels = state.discord.guild_channels(gid).await?;
 
-    let existing_project = projects::get_project(&state.db, repo).await?;
+    let existing_project = projects::get_project(state.db.as_ref(), repo).await?;
 
     if let Some(p) = &existing_project {
         if p.is_approved {
@@ -355,7 +369,7 @@ pub async fn do_approve(
 
     // Update project with the forum channel ID and approve
     projects::approve_project_with_forum(
-        &state.db,
+        state.db.as_ref(),
         repo,
         &project_forum_id.get().to_string(),
         guild_id_str,
@@ -372,7 +386,7 @@ pub async fn do_approve(
 }
 
 async fn handle_deny(
-    db: &ConvexDb,
+    db: &dyn Storage,
     member: Option<&Member>,
     data: &InteractionData,
 ) -> Result<String> {
@@ -391,24 +405,64 @@ async fn handle_deny(
     Ok(format!("Project `{}` denied and removed.", repo))
 }
 
-async fn handle_whitelist(
-    db: &ConvexDb,
+/// `/whitelist-user add|remove|list` - manage the server's trusted GitHub users
+pub async fn handle_whitelist(
+    db: &dyn Storage,
     member: Option<&Member>,
     data: &InteractionData,
+    guild_id: &Option<String>,
 ) -> Result<String> {
     check_moderator(member)?;
-    let opts = data
+
+    let guild_id_str = guild_id
+        .as_ref()
+        .ok_or(Error::InvalidPayload("missing guild_id".into()))?;
+
+    let subcommand = data
         .options
         .as_ref()
-        .ok_or(Error::InvalidPayload("missing options".into()))?;
-    let username = opts
-        .iter()
-        .find(|o| o.name == "username")
-        .and_then(|o| o.value.as_str())
-        .ok_or(Error::InvalidPayload("missing username".into()))?;
+        .and_then(|opts| opts.first())
+        .ok_or(Error::InvalidPayload("missing subcommand".into()))?;
+    let opts = subcommand.options.as_deref().unwrap_or_default();
+
+    let result = match subcommand.name.as_str() {
+        "list" => {
+            let users = whitelist::list_users(db, guild_id_str).await?;
+            if users.is_empty() {
+                Ok("No whitelisted users in this server.".to_string())
+            } else {
+                let lines: Vec<String> = users.iter().map(|u| format!("• `{}`", u)).collect();
+                Ok(format!("**Whitelisted users:**\n{}", lines.join("\n")))
+            }
+        }
+        "add" | "remove" => {
+            let username = string_option(opts, "username")
+                .ok_or(Error::InvalidPayload("missing username".into()))?;
+            if subcommand.name == "add" {
+                whitelist::add_user(db, guild_id_str, username)
+                    .await
+                    .map(|_| format!("User `{}` added to whitelist.", username))
+            } else {
+                whitelist::remove_user(db, guild_id_str, username)
+                    .await
+                    .map(|_| format!("User `{}` removed from whitelist.", username))
+            }
+        }
+        other => Err(Error::InvalidPayload(format!(
+            "Unknown subcommand `{}`",
+            other
+        ))),
+    };
 
-    whitelist::add_user(db, username).await?;
-    Ok(format!("User `{}` added to whitelist.", username))
+    show_user_errors(result)
+}
+
+/// Show validation errors to the moderator instead of failing the interaction
+fn show_user_errors(result: Result<String>) -> Result<String> {
+    match result {
+        Err(Error::InvalidPayload(msg)) | Err(Error::NotFound(msg)) => Ok(format!("❌ {}", msg)),
+        result => result,
+    }
 }
 
 // Check if member has ADMINISTRATOR (0x8) or MANAGE_GUILD (0x20) permission
@@ -433,7 +487,7 @@ fn check_moderator(member: Option<&Member>) -> Result<()> {
 }
 
 async fn handle_list(
-    db: &ConvexDb,
+    db: &dyn Storage,
     member: Option<&Member>,
     guild_id: &Option<String>,
 ) -> Result<String> {
@@ -479,6 +533,444 @@ async fn handle_list(
     Ok(response)
 }
 
+/// Open milestones listed by `/milestone`; more would overflow a Discord message
+const MAX_MILESTONES: usize = 10;
+
+/// `/milestone <repo>` - completion of a project's open milestones
+pub async fn handle_milestone(
+    db: &dyn Storage,
+    data: &InteractionData,
+    guild_id: &Option<String>,
+) -> Result<String> {
+    let guild_id_str = guild_id
+        .as_ref()
+        .ok_or(Error::InvalidPayload("missing guild_id".into()))?;
+    let opts = data.options.as_deref().unwrap_or_default();
+    let repo = string_option(opts, "repo").ok_or(Error::InvalidPayload("missing repo".into()))?;
+
+    let Some(project) = projects::get_approved_project(db, repo)
+        .await?
+        .filter(|p| p.guild_id == *guild_id_str)
+    else {
+        return Ok(format!("❌ Project `{}` not found in this server", repo));
+    };
+
+    let open = milestones::list_open_milestones(db, &project.id).await?;
+    if open.is_empty() {
+        return Ok(format!(
+            "No open milestones known for `{}`. They show up once GitHub sends a milestone or issue event.",
+            project.github_repo
+        ));
+    }
+
+    let mut sections: Vec<String> = open
+        .iter()
+        .take(MAX_MILESTONES)
+        .map(|m| {
+            format!(
+                "**[{}]({})**\n{}",
+                m.title,
+                m.html_url,
+                format_milestone_progress(m)
+            )
+        })
+        .collect();
+    if open.len() > MAX_MILESTONES {
+        sections.push(format!("…and {} more", open.len() - MAX_MILESTONES));
+    }
+
+    Ok(format!(
+        "**🎯 Open milestones of `{}`**\n\n{}",
+        project.github_repo,
+        sections.join("\n\n")
+    ))
+}
+
+const MAX_FAILED_DELIVERIES: usize = 10;
+
+/// `/deliveries failed|replay` - inspect and replay webhook deliveries of
+/// this server's projects that could not be posted
+pub async fn handle_deliveries(
+    state: &AppState,
+    member: Option<&Member>,
+    data: &InteractionData,
+    guild_id: &Option<String>,
+) -> Result<String> {
+    check_moderator(member)?;
+
+    let guild_id_str = guild_id
+        .as_ref()
+        .ok_or(Error::InvalidPayload("missing guild_id".into()))?;
+
+    let subcommand = data
+        .options
+        .as_ref()
+        .and_then(|opts| opts.first())
+        .ok_or(Error::InvalidPayload("missing subcommand".into()))?;
+    let opts = subcommand.options.as_deref().unwrap_or_default();
+
+    let db = state.db.as_ref();
+    let repos: Vec<String> = projects::list_projects_by_guild(db, guild_id_str)
+        .await?
+        .into_iter()
+        .filter(|p| p.is_approved)
+        .map(|p| p.github_repo)
+        .collect();
+    let failed = retries::list_failed(db, Some(&repos)).await?;
+
+    match subcommand.name.as_str() {
+        "failed" => {
+            let failed: Vec<&FailedDelivery> = match string_option(opts, "repo") {
+                Some(repo) => failed
+                    .iter()
+                    .filter(|f| f.repo.eq_ignore_ascii_case(repo.trim()))
+                    .collect(),
+                None => failed.iter().collect(),
+            };
+            if failed.is_empty() {
+                return Ok("✅ No failed deliveries.".to_string());
+            }
+
+            let mut lines: Vec<String> = failed
+                .iter()
+                .take(MAX_FAILED_DELIVERIES)
+                .map(|f| format_failed_delivery(f))
+                .collect();
+            if failed.len() > MAX_FAILED_DELIVERIES {
+                lines.push(format!(
+                    "…and {} more",
+                    failed.len() - MAX_FAILED_DELIVERIES
+                ));
+            }
+
+            Ok(format!(
+                "**📮 Failed deliveries** (oldest first, replay with `/deliveries replay`):\n{}",
+                lines.join("\n")
+            ))
+        }
+        "replay" => {
+            let id = string_option(opts, "id").ok_or(Error::InvalidPayload("missing id".into()))?;
+            // Deliveries of other servers' projects are reported as unknown
+            if !failed.iter().any(|f| f.id == id) {
+                return Ok(format!(
+                    "❌ Failed delivery `{}` not found in this server",
+                    id
+                ));
+            }
+
+            match retries::replay(db, &state.queue, id).await {
+                Ok(f) => Ok(format!(
+                    "🔁 Replaying `{}` for `{}`. It disappears from `/deliveries failed` once it goes through.",
+                    f.event_type, f.repo
+                )),
+                Err(Error::Unavailable(msg)) | Err(Error::NotFound(msg)) => {
+                    Ok(format!("❌ {}", msg))
+                }
+                Err(e) => Err(e),
+            }
+        }
+        _ => Ok("Unknown subcommand".to_string()),
+    }
+}
+
+fn format_failed_delivery(failed: &FailedDelivery) -> String {
+    let status = if failed.dead_lettered {
+        "☠️ dead letter".to_string()
+    } else {
+        format!("next retry <t:{}:R>", failed.next_attempt_at)
+    };
+    format!(
+        "• `{}` `{}` on `{}` · {} attempt(s) · {}\n  {}",
+        failed.id,
+        failed.event_type,
+        failed.repo,
+        failed.attempts,
+        status,
+        truncate(&failed.error, 150)
+    )
+}
+
+/// `/rules list|add|remove|priority|test` - manage a project's routing rules
+pub async fn handle_rules(
+    state: &AppState,
+    member: Option<&Member>,
+    data: &InteractionData,
+    guild_id: &Option<String>,
+) -> Result<String> {
+    check_moderator(member)?;
+
+    let guild_id_str = guild_id
+        .as_ref()
+        .ok_or(Error::InvalidPayload("missing guild_id".into()))?;
+
+    let subcommand = data
+        .options
+        .as_ref()
+        .and_then(|opts| opts.first())
+        .ok_or(Error::InvalidPayload("missing subcommand".into()))?;
+    let opts = subcommand.options.as_deref().unwrap_or_default();
+
+    show_user_errors(run_rules_subcommand(state, guild_id_str, &subcommand.name, opts).await)
+}
+
+async fn run_rules_subcommand(
+    state: &AppState,
+    guild_id: &str,
+    subcommand: &str,
+    opts: &[CommandOption],
+) -> Result<String> {
+    let db = state.db.as_ref();
+    let repo = string_option(opts, "repo").ok_or(Error::InvalidPayload("missing repo".into()))?;
+
+    // Only projects approved in this server can be configured from it
+    let project = projects::get_project(db, repo)
+        .await?
+        .filter(|p| p.guild_id == guild_id)
+        .ok_or_else(|| Error::NotFound(format!("Project `{}` not found in this server", repo)))?;
+
+    let project_rules = rules::list_rules(db, &project.id).await?;
+
+    match subcommand {
+        "list" => {
+            if project_rules.is_empty() {
+                return Ok(format!(
+                    "No rules for `{}`. Built-in routing applies.",
+                    project.github_repo
+                ));
+            }
+
+            let lines: Vec<String> = project_rules
+                .iter()
+                .enumerate()
+                .map(|(i, rule)| format_rule(i + 1, rule))
+                .collect();
+            Ok(format!(
+                "**Rules for `{}`** (highest priority first):\n{}",
+                project.github_repo,
+                lines.join("\n")
+            ))
+        }
+        "add" => {
+            let conditions = RuleConditions {
+                event_type: string_option(opts, "event").map(|e| e.trim().to_lowercase()),
+                labels: list_option(opts, "labels"),
+                labels_all: list_option(opts, "labels_all"),
+                labels_none: list_option(opts, "labels_none"),
+                actor_whitelisted: bool_option(opts, "actor_whitelisted"),
+                merged: bool_option(opts, "merged"),
+                branches: list_option(opts, "branches"),
+                review_state: string_option(opts, "review_state").map(|s| s.to_string()),
+                category: string_option(opts, "category").map(|c| c.trim().to_string()),
+            };
+            let actions = RuleActions {
+                post_activity: bool_option(opts, "activity").unwrap_or(true),
+                post_forum: bool_option(opts, "forum").unwrap_or(false),
+                post_announce: bool_option(opts, "announce").unwrap_or(false),
+                // Slash command inputs are single-line, so `\n` starts a new line
+                template: string_option(opts, "template").map(|t| t.replace("\\n", "\n")),
+                continue_matching: bool_option(opts, "continue").unwrap_or(false),
+            };
+
+            // New rules take precedence over existing ones unless told otherwise,
+            // within the accepted range
+            let priority = match int_option(opts, "priority") {
+                Some(p) => p,
+                None => project_rules
+                    .first()
+                    .map(|r| (r.priority + 1).min(rules::MAX_PRIORITY))
+                    .unwrap_or(0),
+            };
+
+            rules::create_rule(db, &project.id, priority, &conditions, &actions).await?;
+            Ok(format!(
+                "✅ Rule added to `{}` with priority {}: {} → {}",
+                project.github_repo,
+                priority,
+                conditions.describe(),
+                describe_actions(&actions)
+            ))
+        }
+        "remove" => {
+            let rule = rule_at(&project_rules, opts)?;
+            rules::delete_rule(db, &rule.id).await?;
+            Ok(format!(
+                "🗑️ Removed rule: {} → {}",
+                rule.conditions.describe(),
+                describe_actions(&rule.actions)
+            ))
+        }
+        "priority" => {
+            let rule = rule_at(&project_rules, opts)?;
+            let priority = int_option(opts, "priority")
+                .ok_or(Error::InvalidPayload("missing priority".into()))?;
+            rules::set_priority(db, &rule.id, priority).await?;
+            Ok(format!(
+                "✅ Priority of rule `{}` changed from {} to {}",
+                rule.conditions.describe(),
+                rule.priority,
+                priority
+            ))
+        }
+        "test" => {
+            let event_key = string_option(opts, "event")
+                .ok_or(Error::InvalidPayload("missing event".into()))?
+                .trim()
+                .to_lowercase();
+            let mut sample = SampleOptions::default();
+            if let Some(actor) = string_option(opts, "actor") {
+                sample.actor = actor.to_string();
+            }
+            sample.labels = list_option(opts, "labels").unwrap_or_default();
+            sample.merged = bool_option(opts, "merged").unwrap_or(false);
+            if let Some(branch) = string_option(opts, "branch") {
+                sample.branch = branch.trim().to_string();
+            }
+            if let Some(state) = string_option(opts, "review_state") {
+                sample.review_state = state.to_string();
+            }
+
+            let event = samples::sample_event(&project.github_repo, &event_key, &sample)?;
+            let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+            let plan = dispatcher.plan(&event).await?.ok_or_else(|| {
+                Error::InvalidPayload(format!(
+                    "Project `{}` is not approved yet",
+                    project.github_repo
+                ))
+            })?;
+
+            Ok(format_plan(&event_key, &plan))
+        }
+        other => Err(Error::InvalidPayload(format!(
+            "Unknown subcommand `{}`",
+            other
+        ))),
+    }
+}
+
+/// Summarise a dry-run for Discord (messages are capped at 2000 characters)
+fn format_plan(event_key: &str, plan: &DispatchPlan) -> String {
+    let mut out = format!("🧪 **Dry-run of `{}` for `{}`**\n", event_key, plan.repo);
+
+    if plan.rule_count == 0 {
+        out.push_str("No rules, built-in routing applies.\n");
+    } else if plan.matches.is_empty() {
+        out.push_str("No rule matched, the event is only logged to the activity thread.\n");
+    } else {
+        for m in &plan.matches {
+            out.push_str(&format!(
+                "• Matched rule (priority {}): {}\n",
+                m.priority, m.reason
+            ));
+        }
+    }
+
+    if plan.messages.is_empty() {
+        out.push_str("\nNothing would be posted.");
+    }
+    for message in &plan.messages {
+        let destination = match &message.destination {
+            Destination::ItemThread { .. } => {
+                format!("{} (if it exists)", message.destination.label())
+            }
+            other if message.edit_only => format!("{} (edits the posted message)", other.label()),
+            other => other.label(),
+        };
+        let preview: String = message.description.chars().take(200).collect();
+        out.push_str(&format!(
+            "\n**→ {}**\n> {}\n> {}\n",
+            destination,
+            message.title,
+            preview.replace('\n', "\n> ")
+        ));
+    }
+
+    if out.chars().count() > 1900 {
+        out = out.chars().take(1900).collect();
+        out.push_str("\n…");
+    }
+    out
+}
+
+/// Rules are addressed by their 1-based position in `/rules list`
+fn rule_at<'a>(project_rules: &'a [Rule], opts: &[CommandOption]) -> Result<&'a Rule> {
+    let index = int_option(opts, "index").ok_or(Error::InvalidPayload("missing index".into()))?;
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| i.checked_sub(1))
+        .and_then(|i| project_rules.get(i))
+        .ok_or_else(|| {
+            Error::InvalidPayload(format!(
+                "No rule #{} (use /rules list to see rule numbers)",
+                index
+            ))
+        })
+}
+
+fn format_rule(position: usize, rule: &Rule) -> String {
+    let mut line = format!(
+        "`#{}` (priority {}) {} → {}",
+        position,
+        rule.priority,
+        rule.conditions.describe(),
+        describe_actions(&rule.actions)
+    );
+    if rule.actions.continue_matching {
+        line.push_str(" (continues)");
+    }
+    if let Some(template) = &rule.actions.template {
+        let title = template.lines().next().unwrap_or_default();
+        line.push_str(&format!(" with template `{}`", title));
+    }
+    line
+}
+
+fn describe_actions(actions: &RuleActions) -> String {
+    let mut targets = Vec::new();
+    if actions.post_activity {
+        targets.push("activity");
+    }
+    if actions.post_forum {
+        targets.push("forum");
+    }
+    if actions.post_announce {
+        targets.push("announcements");
+    }
+
+    if targets.is_empty() {
+        "drop".to_string()
+    } else {
+        targets.join(", ")
+    }
+}
+
+fn string_option<'a>(opts: &'a [CommandOption], name: &str) -> Option<&'a str> {
+    opts.iter()
+        .find(|o| o.name == name)
+        .and_then(|o| o.value.as_str())
+}
+
+fn bool_option(opts: &[CommandOption], name: &str) -> Option<bool> {
+    opts.iter()
+        .find(|o| o.name == name)
+        .and_then(|o| o.value.as_bool())
+}
+
+fn int_option(opts: &[CommandOption], name: &str) -> Option<i64> {
+    opts.iter()
+        .find(|o| o.name == name)
+        .and_then(|o| o.value.as_i64())
+}
+
+/// Comma-separated list, e.g. "bug, security"
+fn list_option(opts: &[CommandOption], name: &str) -> Option<Vec<String>> {
+    string_option(opts, name).map(|value| {
+        value
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .collect()
+    })
+}
+
 pub async fn do_setup_server(state: &AppState, guild_id: &Option<String>) -> Result<String> {
     let guild_id_str = guild_id
         .as_ref()
@@ -523,18 +1015,37 @@ pub async fn do_setup_server(state: &AppState, guild_id: &Option<String>) -> Res
         None => state.discord.create_github_category(gid).await?,
     };
 
+    // Reuse the private moderator channel if it is still there. It is never
+    // looked up by name: a public channel with the same name must not receive alerts.
+    let existing_config = server_config::get_config(state.db.as_ref(), guild_id_str).await?;
+    let channels = state.discord.guild_channels(gid).await?;
+    let moderator_channel_id = match existing_config
+        .and_then(|c| c.moderator_channel_id)
+        .filter(|id| channel_exists(&channels, id))
+    {
+        Some(id) => id,
+        None => state
+            .discord
+            .create_moderator_channel(gid)
+            .await?
+            .get()
+            .to_string(),
+    };
+
     // Save config to database
     server_config::save_config(
-        &state.db,
+        state.db.as_ref(),
         guild_id_str,
         &announcements_id.get().to_string(),
         &github_category_id.get().to_string(),
     )
     .await?;
+    server_config::set_moderator_channel(state.db.as_ref(), guild_id_str, &moderator_channel_id)
+        .await?;
 
     Ok(format!(
-        "✅ **Server setup complete!**\n\n**Channels:**\n• <#{}> - Announcements\n• <#{}> - GitHub (Category)",
-        announcements_id, github_category_id
+        "✅ **Server setup complete!**\n\n**Channels:**\n• <#{}> - Announcements\n• <#{}> - GitHub (Category)\n• <#{}> - Security alerts (moderators only)",
+        announcements_id, github_category_id, moderator_channel_id
     ))
 }
 
@@ -555,7 +1066,7 @@ pub async fn do_repair(state: &AppState, guild_id: &Option<String>) -> Result<St
     let channels = state.discord.guild_channels(gid).await?;
 
     // Get server config from DB
-    let config = server_config::get_config(&state.db, guild_id_str)
+    let config = server_config::get_config(state.db.as_ref(), guild_id_str)
         .await?
         .ok_or(Error::InvalidPayload(
             "Server not set up. Run /setup-server first.".into(),
@@ -582,7 +1093,7 @@ pub async fn do_repair(state: &AppState, guild_id: &Option<String>) -> Result<St
     // Update config if any server channels were repaired
     if !repairs.is_empty() {
         server_config::save_config(
-            &state.db,
+            state.db.as_ref(),
             guild_id_str,
             &new_announcements_id,
             &new_github_forum_id,
@@ -590,6 +1101,22 @@ pub async fn do_repair(state: &AppState, guild_id: &Option<String>) -> Result<St
         .await?;
     }
 
+    // Check the private moderator channel
+    let moderator_channel_ok = config
+        .moderator_channel_id
+        .as_deref()
+        .is_some_and(|id| channel_exists(&channels, id));
+    if !moderator_channel_ok {
+        let new_id = state.discord.create_moderator_channel(gid).await?;
+        server_config::set_moderator_channel(
+            state.db.as_ref(),
+            guild_id_str,
+            &new_id.get().to_string(),
+        )
+        .await?;
+        repairs.push(format!("✅ Recreated <#{}> (Security alerts)", new_id));
+    }
+
     // Check project forums
     let github_cat = match new_github_forum_id.parse::<u64>() {
         Ok(id) => Some(Id::new(id)),
@@ -598,7 +1125,7 @@ pub async fn do_repair(state: &AppState, guild_id: &Option<String>) -> Result<St
             None
         }
     };
-    let project_list = projects::list_projects_by_guild(&state.db, guild_id_str).await?;
+    let project_list = projects::list_projects_by_guild(state.db.as_ref(), guild_id_str).await?;
 
     const MAX_FORUM_REPAIRS: usize = 10; // Prevent excessive API calls
 
@@ -623,7 +1150,7 @@ pub async fn do_repair(state: &AppState, guild_id: &Option<String>) -> Result<St
                     .create_project_forum(gid, cat_id, name)
                     .await?;
                 projects::update_forum_id(
-                    &state.db,
+                    state.db.as_ref(),
                     &project.github_repo,
                     &new_id.get().to_string(),
                 )
@@ -5,6 +5,29 @@ pub const COLOR_SKIPPED: u32 = 0x95A5A6; // Grey
 pub const COLOR_PR: u32 = 0x9B59B6; // Purple - PR merged
 pub const COLOR_BOUNTY: u32 = 0xF1C40F; // Gold - Bounty events
 pub const COLOR_ISSUE: u32 = 0x3498DB; // Blue - Other issues
+pub const COLOR_PUSH: u32 = 0x1ABC9C; // Teal - Direct pushes
+pub const COLOR_COMMENT: u32 = 0x7F8C8D; // Slate - Comments
+pub const COLOR_REVIEW_APPROVED: u32 = 0x27AE60; // Dark green - PR approved
+pub const COLOR_REVIEW_CHANGES: u32 = 0xE67E22; // Orange - Changes requested
+pub const COLOR_REVIEW_COMMENTED: u32 = 0x8E44AD; // Dark purple - Review comments
+pub const COLOR_DISCUSSION: u32 = 0xE91E63; // Pink - Discussions
+pub const COLOR_STAR: u32 = 0xFFD700; // Bright gold - Star milestones
+pub const COLOR_REF: u32 = 0x34495E; // Dark blue - Forks, branches and tags
+pub const COLOR_DEPLOYMENT: u32 = 0x5865F2; // Blurple - Deployments in progress
+pub const COLOR_MILESTONE: u32 = 0x00BCD4; // Cyan - Open milestones
+
+/// Colors for security alert severities
+pub const COLOR_SEVERITY_CRITICAL: u32 = 0x8B0000; // Dark red
+pub const COLOR_SEVERITY_HIGH: u32 = 0xE74C3C; // Red
+pub const COLOR_SEVERITY_MEDIUM: u32 = 0xE67E22; // Orange
+pub const COLOR_SEVERITY_LOW: u32 = 0xF1C40F; // Yellow
+
+/// Commits listed in a push embed before the rest are summarised
+pub const MAX_PUSH_COMMITS: usize = 10;
+/// Commit subjects longer than this are cut off (git's conventional limit)
+const MAX_COMMIT_SUBJECT_LEN: usize = 72;
+/// Length of the comment excerpt shown in embeds
+pub const MAX_COMMENT_EXCERPT_LEN: usize = 300;
 
 pub fn format_release(event: &crate::github::events::ReleaseEvent) -> (String, String) {
     let title = format!("🚀 Release {}", event.release.tag_name);
@@ -77,3 +100,128 @@ pub fn format_workflow(event: &crate::github::events::WorkflowRunEvent) -> (Stri
     );
     (title, body)
 }
+
+pub fn format_push(event: &crate::github::events::PushEvent) -> (String, String) {
+    let branch = event.branch().unwrap_or(&event.ref_name);
+    let count = event.commits.len();
+    let noun = if count == 1 { "commit" } else { "commits" };
+    let forced = if event.forced { " (force-push)" } else { "" };
+    let title = format!("⬆️ {} {} pushed to `{}`{}", count, noun, branch, forced);
+    let body = format!(
+        "{}\n\nby @{} · [Compare changes]({})",
+        format_commit_list(event),
+        event.sender.login,
+        event.compare
+    );
+    (title, body.trim_start().to_string())
+}
+
+/// One line per commit, capped at `MAX_PUSH_COMMITS`
+pub fn format_commit_list(event: &crate::github::events::PushEvent) -> String {
+    let mut lines: Vec<String> = event
+        .commits
+        .iter()
+        .take(MAX_PUSH_COMMITS)
+        .map(|commit| {
+            let sha: String = commit.id.chars().take(7).collect();
+            let author = commit
+                .author
+                .username
+                .as_deref()
+                .map(|u| format!("@{}", u))
+                .unwrap_or_else(|| commit.author.name.clone());
+            format!(
+                "[`{}`]({}) {} - {}",
+                sha,
+                commit.url,
+                truncate(commit.summary(), MAX_COMMIT_SUBJECT_LEN),
+                author
+            )
+        })
+        .collect();
+
+    if event.commits.len() > MAX_PUSH_COMMITS {
+        lines.push(format!(
+            "… and {} more",
+            event.commits.len() - MAX_PUSH_COMMITS
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Shortened comment body, quoted line by line
+pub fn format_comment_excerpt(body: &str) -> String {
+    truncate(body.trim(), MAX_COMMENT_EXCERPT_LEN)
+        .lines()
+        .map(|line| format!("> {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Cut `text` to at most `max_chars` characters, ending in `…` when shortened
+pub(crate) fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// (emoji, verb, color) for a review state
+pub fn review_style(state: &str) -> (&'static str, &'static str, u32) {
+    match state {
+        "approved" => ("✅", "approved", COLOR_REVIEW_APPROVED),
+        "changes_requested" => ("🔁", "changes requested", COLOR_REVIEW_CHANGES),
+        _ => ("👀", "reviewed", COLOR_REVIEW_COMMENTED),
+    }
+}
+
+/// Checks page of a commit, where check suites have no page of their own
+pub fn checks_url(repo: &str, sha: &str) -> String {
+    format!("https://github.com/{}/commit/{}/checks", repo, sha)
+}
+
+/// (emoji, label, color) for a deployment state
+pub fn deployment_style(state: &str) -> (&'static str, &'static str, u32) {
+    match state {
+        "success" => ("✅", "succeeded", COLOR_SUCCESS),
+        "failure" => ("❌", "failed", COLOR_FAILURE),
+        "error" => ("⚠️", "errored", COLOR_FAILURE),
+        "in_progress" => ("🚀", "in progress", COLOR_DEPLOYMENT),
+        "inactive" => ("💤", "inactive", COLOR_SKIPPED),
+        "queued" => ("⏳", "queued", COLOR_DEPLOYMENT),
+        _ => ("⏳", "pending", COLOR_DEPLOYMENT),
+    }
+}
+
+/// Ten-segment bar for a percentage, e.g. `▰▰▰▰▰▰▱▱▱▱`
+pub fn progress_bar(percent: u64) -> String {
+    let filled = (percent.min(100) / 10) as usize;
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(10 - filled))
+}
+
+/// Progress and due date of a milestone, as shown in the forum and by `/milestone`
+pub fn format_milestone_progress(milestone: &crate::github::events::Milestone) -> String {
+    let mut text = format!(
+        "`{}` **{}%** · {} of {} issues closed",
+        progress_bar(milestone.progress()),
+        milestone.progress(),
+        milestone.closed_issues,
+        milestone.open_issues + milestone.closed_issues
+    );
+    if let Some(due) = milestone.due_date() {
+        text.push_str(&format!("\nDue {}", due));
+    }
+    text
+}
+
+pub fn severity_color(severity: &str) -> u32 {
+    match severity {
+        "critical" => COLOR_SEVERITY_CRITICAL,
+        "high" => COLOR_SEVERITY_HIGH,
+        "medium" | "moderate" => COLOR_SEVERITY_MEDIUM,
+        "low" => COLOR_SEVERITY_LOW,
+        _ => COLOR_SKIPPED,
+    }
+}
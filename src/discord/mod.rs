@@ -2,4 +2,5 @@ pub mod client;
 pub mod commands;
 pub mod formatters;
 pub mod rate_limit;
+pub mod templates;
 pub mod verify;
@@ -0,0 +1,420 @@
+//! Custom message templates (`RuleActions.template`)
+//!
+//! A template is plain text with `{placeholder}` fields, e.g.
+//!
+//! ```text
+//! 🔒 {repo}: #{number} {title}
+//! Labels: {labels}
+//! {url}
+//! ```
+//!
+//! The first line becomes the embed title and the remaining lines the
+//! description. `{{` and `}}` produce literal braces. Placeholders that the
+//! event does not provide (e.g. `{branch}` on an issue) make rendering fail,
+//! in which case the dispatcher falls back to the built-in layout.
+
+use std::collections::BTreeMap;
+
+use crate::discord::formatters;
+use crate::error::{Error, Result};
+use crate::github::events::ParsedEvent;
+
+/// Every placeholder a template may use
+pub const PLACEHOLDERS: &[&str] = &[
+    "repo",
+    "event",
+    "action",
+    "actor",
+    "number",
+    "title",
+    "labels",
+    "branch",
+    "conclusion",
+    "tag",
+    "body",
+    "url",
+    "commits",
+    "state",
+    "category",
+    "severity",
+    "stars",
+    "environment",
+    "sha",
+    "progress",
+    "due",
+];
+
+/// Discord embed limits
+const MAX_TITLE_LEN: usize = 256;
+const MAX_DESCRIPTION_LEN: usize = 4096;
+
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into literal text and placeholders
+fn parse(template: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        if pos > 0 {
+            segments.push(Segment::Text(&rest[..pos]));
+        }
+        let tail = &rest[pos..];
+
+        if let Some(after) = tail.strip_prefix("{{") {
+            segments.push(Segment::Text("{"));
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("}}") {
+            segments.push(Segment::Text("}"));
+            rest = after;
+        } else if tail.starts_with('}') {
+            return Err(Error::InvalidPayload(
+                "Unmatched `}` in template (use `}}` for a literal brace)".into(),
+            ));
+        } else {
+            let end = tail.find('}').ok_or_else(|| {
+                Error::InvalidPayload(
+                    "Unclosed `{` in template (use `{{` for a literal brace)".into(),
+                )
+            })?;
+            segments.push(Segment::Placeholder(tail[1..end].trim()));
+            rest = &tail[end + 1..];
+        }
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+/// Check a template before it is saved: braces must balance, every
+/// placeholder must be known and the title line must not be empty.
+pub fn validate(template: &str) -> Result<()> {
+    for segment in parse(template)? {
+        if let Segment::Placeholder(name) = segment {
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(Error::InvalidPayload(format!(
+                    "Unknown placeholder `{{{}}}`. Available: {}",
+                    name,
+                    PLACEHOLDERS.join(", ")
+                )));
+            }
+        }
+    }
+
+    if template.lines().next().unwrap_or("").trim().is_empty() {
+        return Err(Error::InvalidPayload(
+            "The first line of a template (the title) cannot be empty".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// The placeholder values an event provides
+pub fn fields(event: &ParsedEvent) -> BTreeMap<&'static str, String> {
+    let mut fields = BTreeMap::new();
+
+    if let Some(repo) = event.repo_full_name() {
+        fields.insert("repo", repo.to_string());
+    }
+    if let Some(key) = event.event_key() {
+        fields.insert("event", key);
+    }
+    if let Some(actor) = event.actor() {
+        fields.insert("actor", actor.to_string());
+    }
+    if let Some(severity) = event.security_severity() {
+        fields.insert("severity", severity.to_string());
+    }
+
+    match event {
+        ParsedEvent::PullRequest(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("number", e.pull_request.number.to_string());
+            fields.insert("title", e.pull_request.title.clone());
+            fields.insert("labels", event.labels().join(", "));
+            fields.insert("url", e.pull_request.html_url.clone());
+        }
+        ParsedEvent::Issue(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("number", e.issue.number.to_string());
+            fields.insert("title", e.issue.title.clone());
+            fields.insert("labels", event.labels().join(", "));
+            fields.insert("url", e.issue.html_url.clone());
+        }
+        ParsedEvent::Release(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert(
+                "title",
+                e.release
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| e.release.tag_name.clone()),
+            );
+            fields.insert("tag", e.release.tag_name.clone());
+            fields.insert("body", e.release.body.clone().unwrap_or_default());
+            fields.insert("url", e.release.html_url.clone());
+        }
+        ParsedEvent::WorkflowRun(e) => {
+            let run = &e.workflow_run;
+            fields.insert("action", e.action.clone());
+            fields.insert("title", run.name.clone().unwrap_or_else(|| "CI".into()));
+            if let Some(branch) = &run.head_branch {
+                fields.insert("branch", branch.clone());
+            }
+            if let Some(conclusion) = &run.conclusion {
+                fields.insert("conclusion", conclusion.clone());
+            }
+            fields.insert("url", run.html_url.clone());
+        }
+        ParsedEvent::Push(e) => {
+            fields.insert("action", "pushed".into());
+            if let Some(branch) = e.branch() {
+                fields.insert("branch", branch.to_string());
+            }
+            if let Some(head) = e.commits.last() {
+                fields.insert("title", head.summary().to_string());
+            }
+            fields.insert("commits", e.commits.len().to_string());
+            fields.insert("body", formatters::format_commit_list(e));
+            fields.insert("url", e.compare.clone());
+        }
+        ParsedEvent::IssueComment(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("number", e.issue.number.to_string());
+            fields.insert("title", e.issue.title.clone());
+            fields.insert("labels", event.labels().join(", "));
+            fields.insert("body", e.comment.body.clone());
+            fields.insert("url", e.comment.html_url.clone());
+        }
+        ParsedEvent::PullRequestReviewComment(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("number", e.pull_request.number.to_string());
+            fields.insert("title", e.pull_request.title.clone());
+            fields.insert("labels", event.labels().join(", "));
+            fields.insert("body", e.comment.body.clone());
+            fields.insert("url", e.comment.html_url.clone());
+        }
+        ParsedEvent::PullRequestReview(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("state", e.review.state.clone());
+            fields.insert("number", e.pull_request.number.to_string());
+            fields.insert("title", e.pull_request.title.clone());
+            fields.insert("labels", event.labels().join(", "));
+            fields.insert("body", e.review.body.clone().unwrap_or_default());
+            fields.insert("url", e.review.html_url.clone());
+        }
+        ParsedEvent::Discussion(e) => {
+            let d = &e.discussion;
+            fields.insert("action", e.action.clone());
+            fields.insert("number", d.number.to_string());
+            fields.insert("title", d.title.clone());
+            fields.insert("labels", event.labels().join(", "));
+            fields.insert("category", d.category.name.clone());
+            match &e.answer {
+                Some(answer) => {
+                    fields.insert("body", answer.body.clone());
+                    fields.insert("url", answer.html_url.clone());
+                }
+                None => {
+                    fields.insert("url", d.html_url.clone());
+                }
+            }
+        }
+        ParsedEvent::DiscussionComment(e) => {
+            let d = &e.discussion;
+            fields.insert("action", e.action.clone());
+            fields.insert("number", d.number.to_string());
+            fields.insert("title", d.title.clone());
+            fields.insert("labels", event.labels().join(", "));
+            fields.insert("category", d.category.name.clone());
+            fields.insert("body", e.comment.body.clone());
+            fields.insert("url", e.comment.html_url.clone());
+        }
+        ParsedEvent::SecurityAdvisory(e) => {
+            let advisory = &e.security_advisory;
+            fields.insert("action", e.action.clone());
+            fields.insert("title", advisory.summary.clone());
+            if let Some(url) = &advisory.html_url {
+                fields.insert("url", url.clone());
+            }
+        }
+        ParsedEvent::DependabotAlert(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("number", e.alert.number.to_string());
+            fields.insert("title", e.alert.security_advisory.summary.clone());
+            fields.insert("url", e.alert.html_url.clone());
+        }
+        ParsedEvent::CodeScanningAlert(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("number", e.alert.number.to_string());
+            fields.insert("title", e.alert.rule.description.clone());
+            fields.insert("url", e.alert.html_url.clone());
+        }
+        ParsedEvent::SecretScanningAlert(e) => {
+            let alert = &e.alert;
+            fields.insert("action", e.action.clone());
+            fields.insert("number", alert.number.to_string());
+            fields.insert(
+                "title",
+                alert
+                    .secret_type_display_name
+                    .clone()
+                    .unwrap_or_else(|| alert.secret_type.clone()),
+            );
+            fields.insert("url", alert.html_url.clone());
+        }
+        ParsedEvent::Star(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("stars", e.repository.stargazers_count.to_string());
+            fields.insert("url", format!("{}/stargazers", e.repository.html_url));
+        }
+        ParsedEvent::Fork(e) => {
+            fields.insert("action", "forked".into());
+            fields.insert("stars", e.repository.stargazers_count.to_string());
+            fields.insert("title", e.forkee.full_name.clone());
+            fields.insert("url", e.forkee.html_url.clone());
+        }
+        ParsedEvent::Create(e) | ParsedEvent::Delete(e) => {
+            let action = if matches!(event, ParsedEvent::Create(_)) {
+                "created"
+            } else {
+                "deleted"
+            };
+            fields.insert("action", action.into());
+            fields.insert("title", e.ref_name.clone());
+            if e.is_tag() {
+                fields.insert("tag", e.ref_name.clone());
+            }
+        }
+        ParsedEvent::Ping(e) => {
+            fields.insert("action", "connected".into());
+            fields.insert("body", e.zen.clone());
+        }
+        ParsedEvent::Repository(e) => {
+            fields.insert("action", e.action.clone());
+            fields.insert("title", e.repository.full_name.clone());
+        }
+        ParsedEvent::Deployment(_) | ParsedEvent::DeploymentStatus(_) => {
+            let Some(deployment) = event.deployment() else {
+                return fields;
+            };
+            fields.insert("branch", deployment.ref_name.clone());
+            fields.insert("environment", deployment.environment.clone());
+            fields.insert("title", deployment.environment.clone());
+            fields.insert("sha", deployment.sha.chars().take(7).collect());
+            match event {
+                ParsedEvent::DeploymentStatus(e) => {
+                    let status = &e.deployment_status;
+                    fields.insert("action", e.action.clone());
+                    fields.insert("state", status.state.clone());
+                    if let Some(description) = &status.description {
+                        fields.insert("body", description.clone());
+                    }
+                    if let Some(url) = status
+                        .environment_url
+                        .as_ref()
+                        .or(status.target_url.as_ref())
+                    {
+                        fields.insert("url", url.clone());
+                    }
+                }
+                _ => {
+                    fields.insert("action", "created".into());
+                    fields.insert("state", "pending".into());
+                    if let Some(description) = &deployment.description {
+                        fields.insert("body", description.clone());
+                    }
+                }
+            }
+        }
+        ParsedEvent::CheckSuite(e) => {
+            let suite = &e.check_suite;
+            fields.insert("action", e.action.clone());
+            fields.insert("title", suite.app.name.clone());
+            if let Some(branch) = &suite.head_branch {
+                fields.insert("branch", branch.clone());
+            }
+            if let Some(conclusion) = &suite.conclusion {
+                fields.insert("conclusion", conclusion.clone());
+            }
+            fields.insert("sha", suite.head_sha.chars().take(7).collect());
+            fields.insert(
+                "url",
+                formatters::checks_url(&e.repository.full_name, &suite.head_sha),
+            );
+        }
+        ParsedEvent::CheckRun(e) => {
+            let run = &e.check_run;
+            fields.insert("action", e.action.clone());
+            fields.insert("title", run.name.clone());
+            if let Some(branch) = &run.check_suite.head_branch {
+                fields.insert("branch", branch.clone());
+            }
+            if let Some(conclusion) = &run.conclusion {
+                fields.insert("conclusion", conclusion.clone());
+            }
+            fields.insert("state", run.status.clone());
+            fields.insert("sha", run.head_sha.chars().take(7).collect());
+            fields.insert("url", run.html_url.clone());
+        }
+        ParsedEvent::Milestone(e) => {
+            let milestone = &e.milestone;
+            fields.insert("action", e.action.clone());
+            fields.insert("number", milestone.number.to_string());
+            fields.insert("title", milestone.title.clone());
+            fields.insert("state", milestone.state.clone());
+            fields.insert("url", milestone.html_url.clone());
+            fields.insert("progress", format!("{}%", milestone.progress()));
+            if let Some(description) = &milestone.description {
+                fields.insert("body", description.clone());
+            }
+            if let Some(due) = milestone.due_date() {
+                fields.insert("due", due.to_string());
+            }
+        }
+        ParsedEvent::Unknown => {}
+    }
+
+    fields
+}
+
+/// Render a template for an event into an embed `(title, description)`
+pub fn render(template: &str, event: &ParsedEvent) -> Result<(String, String)> {
+    let fields = fields(event);
+    let mut output = String::new();
+
+    for segment in parse(template)? {
+        match segment {
+            Segment::Text(text) => output.push_str(text),
+            Segment::Placeholder(name) => {
+                let value = fields.get(name).ok_or_else(|| {
+                    Error::InvalidPayload(format!(
+                        "`{{{}}}` is not available for {}",
+                        name,
+                        event.event_key().unwrap_or_else(|| "this event".into())
+                    ))
+                })?;
+                output.push_str(value);
+            }
+        }
+    }
+
+    let (title, description) = match output.split_once('\n') {
+        Some((title, description)) => (title.trim(), description.trim()),
+        None => (output.trim(), ""),
+    };
+    if title.is_empty() {
+        return Err(Error::InvalidPayload("rendered title is empty".into()));
+    }
+
+    Ok((
+        formatters::truncate(title, MAX_TITLE_LEN),
+        formatters::truncate(description, MAX_DESCRIPTION_LEN),
+    ))
+}
@@ -17,8 +17,12 @@ pub enum Error {
     Unauthorized,
     #[error("discord api error: {0}")]
     Discord(String),
+    #[error("github api error: {0}")]
+    GitHub(String),
     #[error("internal error: {0}")]
     Internal(String),
+    #[error("service unavailable: {0}")]
+    Unavailable(String),
 }
 
 impl IntoResponse for Error {
@@ -28,6 +32,7 @@ impl IntoResponse for Error {
             Error::InvalidPayload(_) => StatusCode::BAD_REQUEST,
             Error::ProjectAlreadyExists(_) => StatusCode::CONFLICT,
             Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
         (status, self.to_string()).into_response()
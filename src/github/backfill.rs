@@ -0,0 +1,193 @@
+//! Recover webhook events missed during an outage
+//!
+//! GitHub keeps the deliveries of a webhook for three days. A backfill lists
+//! the deliveries of a repository's webhook in a time window, fetches their
+//! payloads and queues them like fresh deliveries. Deliveries are claimed
+//! by GUID first, so anything ByteHub already processed is skipped.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::github::client::{GitHubInterface, HookDelivery};
+use crate::github::deliveries::{self, DELIVERY_TTL_SECS};
+use crate::github::events::ParsedEvent;
+use crate::governance::projects;
+use crate::router::queue::{Delivery, DispatchQueue};
+use crate::storage::Storage;
+
+/// Most deliveries replayed by one backfill
+pub const MAX_BACKFILL_DELIVERIES: usize = 500;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillRequest {
+    /// `owner/name` of an approved project
+    pub repo: String,
+    pub since: DateTime<Utc>,
+    /// Defaults to now
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    /// Needed only if several webhooks of the repository point to ByteHub
+    #[serde(default)]
+    pub hook_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BackfillReport {
+    pub hook_id: u64,
+    /// Deliveries in the window, counting redeliveries once
+    pub found: usize,
+    pub queued: usize,
+    /// Already processed by ByteHub
+    pub duplicates: usize,
+    /// Pings and events ByteHub does not handle
+    pub skipped: usize,
+    /// GUIDs whose payload could not be fetched or queued
+    pub failed: Vec<String>,
+}
+
+/// Queue the deliveries of `request.repo` that ByteHub has not processed yet
+pub async fn backfill(
+    db: &dyn Storage,
+    github: &dyn GitHubInterface,
+    queue: &DispatchQueue,
+    request: &BackfillRequest,
+) -> Result<BackfillReport> {
+    let until = request.until.unwrap_or_else(Utc::now);
+    if until <= request.since {
+        return Err(Error::InvalidPayload(
+            "`until` must be after `since`".into(),
+        ));
+    }
+
+    let repo = request.repo.trim().to_lowercase();
+    if projects::get_approved_project(db, &repo).await?.is_none() {
+        return Err(Error::NotFound(format!(
+            "{} is not an approved ByteHub project",
+            repo
+        )));
+    }
+
+    let hook_id = match request.hook_id {
+        Some(id) => id,
+        None => find_webhook(github, &repo).await?,
+    };
+
+    let mut listed = github
+        .list_deliveries(&repo, hook_id, request.since)
+        .await?;
+    listed.retain(|d| d.delivered_at >= request.since && d.delivered_at < until);
+
+    // Oldest first, so events are dispatched in their original order
+    listed.sort_by_key(|d| d.delivered_at);
+    let mut guids = HashSet::new();
+    listed.retain(|d| guids.insert(d.guid.clone()));
+
+    if listed.len() > MAX_BACKFILL_DELIVERIES {
+        return Err(Error::InvalidPayload(format!(
+            "{} deliveries in the window, at most {} can be backfilled at once",
+            listed.len(),
+            MAX_BACKFILL_DELIVERIES
+        )));
+    }
+
+    let mut report = BackfillReport {
+        hook_id,
+        found: listed.len(),
+        ..Default::default()
+    };
+
+    for delivery in &listed {
+        if delivery.event == "ping" {
+            report.skipped += 1;
+            continue;
+        }
+        // Claimed directly, so backfilled duplicates are not counted by `/health`
+        if !db
+            .record_delivery(&delivery.guid, DELIVERY_TTL_SECS)
+            .await?
+        {
+            report.duplicates += 1;
+            continue;
+        }
+
+        match replay(github, queue, &repo, hook_id, delivery).await {
+            Ok(true) => report.queued += 1,
+            Ok(false) => {
+                report.skipped += 1;
+                deliveries::release_delivery(db, &delivery.guid).await;
+            }
+            Err(e) => {
+                warn!(repo, guid = delivery.guid, error = %e, "failed to backfill delivery");
+                report.failed.push(delivery.guid.clone());
+                deliveries::release_delivery(db, &delivery.guid).await;
+            }
+        }
+    }
+
+    info!(
+        repo,
+        hook_id,
+        found = report.found,
+        queued = report.queued,
+        duplicates = report.duplicates,
+        "backfilled webhook deliveries"
+    );
+    Ok(report)
+}
+
+/// The repository's webhook pointing to ByteHub's GitHub endpoint
+async fn find_webhook(github: &dyn GitHubInterface, repo: &str) -> Result<u64> {
+    let hooks: Vec<u64> = github
+        .list_webhooks(repo)
+        .await?
+        .into_iter()
+        .filter(|h| h.url.trim_end_matches('/').ends_with("/webhooks/github"))
+        .map(|h| h.id)
+        .collect();
+
+    match hooks.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(Error::NotFound(format!(
+            "no webhook of {} points to /webhooks/github",
+            repo
+        ))),
+        _ => Err(Error::InvalidPayload(format!(
+            "several webhooks of {} point to /webhooks/github, pass `hook_id`",
+            repo
+        ))),
+    }
+}
+
+/// Fetch a delivery and queue it. Returns `false` for events ByteHub does
+/// not handle.
+async fn replay(
+    github: &dyn GitHubInterface,
+    queue: &DispatchQueue,
+    repo: &str,
+    hook_id: u64,
+    delivery: &HookDelivery,
+) -> Result<bool> {
+    let payload = github
+        .get_delivery_payload(repo, hook_id, delivery.id)
+        .await?;
+    let event = ParsedEvent::from_payload(&delivery.event, payload.as_bytes())
+        .map_err(|e| Error::InvalidPayload(e.to_string()))?;
+    if matches!(event, ParsedEvent::Unknown) {
+        return Ok(false);
+    }
+
+    queue
+        .enqueue_waiting(
+            event,
+            Delivery {
+                delivery_id: Some(delivery.guid.clone()),
+                event_type: delivery.event.clone(),
+                payload,
+            },
+        )
+        .await?;
+    Ok(true)
+}
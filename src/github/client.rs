@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use octocrab::{Octocrab, Page};
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+
+/// Most delivery pages (100 deliveries each) fetched for one backfill
+const MAX_DELIVERY_PAGES: usize = 20;
+
+/// A repository webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: u64,
+    /// Where GitHub sends the deliveries
+    pub url: String,
+}
+
+/// A webhook delivery as listed by GitHub, without its payload
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HookDelivery {
+    /// Id for the deliveries API
+    pub id: u64,
+    /// `X-GitHub-Delivery` GUID, shared by redeliveries
+    pub guid: String,
+    pub delivered_at: DateTime<Utc>,
+    /// `X-GitHub-Event`
+    pub event: String,
+    /// Our answer, e.g. 202, or 0 if GitHub could not reach us
+    pub status_code: u16,
+}
+
+#[async_trait]
+pub trait GitHubInterface: Send + Sync {
+    async fn list_webhooks(&self, repo: &str) -> Result<Vec<Webhook>>;
+    /// Deliveries of a webhook, newest first, going back at least to `since`.
+    /// Fails rather than return only part of them.
+    async fn list_deliveries(
+        &self,
+        repo: &str,
+        hook_id: u64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<HookDelivery>>;
+    /// Raw JSON body of a delivery
+    async fn get_delivery_payload(&self, repo: &str, hook_id: u64, id: u64) -> Result<String>;
+}
+
+/// GitHub REST API client, authenticated with a personal access token that
+/// can read the repository's webhooks
+pub struct GitHubClient {
+    crab: Octocrab,
+}
+
+impl GitHubClient {
+    pub fn new(token: &str) -> Result<Self> {
+        let crab = Octocrab::builder()
+            .personal_token(token.to_string())
+            .build()
+            .map_err(github_err)?;
+        Ok(Self { crab })
+    }
+}
+
+fn github_err(e: octocrab::Error) -> Error {
+    Error::GitHub(e.to_string())
+}
+
+#[derive(Deserialize)]
+struct HookResponse {
+    id: u64,
+    config: HookConfig,
+}
+
+#[derive(Deserialize)]
+struct HookConfig {
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct DeliveryDetail {
+    request: DeliveryRequest,
+}
+
+#[derive(Deserialize)]
+struct DeliveryRequest {
+    payload: serde_json::Value,
+}
+
+#[async_trait]
+impl GitHubInterface for GitHubClient {
+    async fn list_webhooks(&self, repo: &str) -> Result<Vec<Webhook>> {
+        let hooks: Vec<HookResponse> = self
+            .crab
+            .get(format!("/repos/{}/hooks", repo), None::<&()>)
+            .await
+            .map_err(github_err)?;
+
+        Ok(hooks
+            .into_iter()
+            .map(|h| Webhook {
+                id: h.id,
+                url: h.config.url,
+            })
+            .collect())
+    }
+
+    async fn list_deliveries(
+        &self,
+        repo: &str,
+        hook_id: u64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<HookDelivery>> {
+        let mut page: Page<HookDelivery> = self
+            .crab
+            .get(
+                format!("/repos/{}/hooks/{}/deliveries", repo, hook_id),
+                Some(&[("per_page", "100")]),
+            )
+            .await
+            .map_err(github_err)?;
+
+        // Deliveries come newest first; the cursor in `next` pages back in time
+        let mut deliveries = page.take_items();
+        for pages in 1.. {
+            if deliveries.last().is_none_or(|d| d.delivered_at < since) {
+                break;
+            }
+            // Stopping here would silently leave out the oldest deliveries
+            if pages == MAX_DELIVERY_PAGES && page.next.is_some() {
+                return Err(Error::GitHub(format!(
+                    "{} has more than {} webhook deliveries since {}, too many to list",
+                    repo,
+                    MAX_DELIVERY_PAGES * 100,
+                    since
+                )));
+            }
+            match self
+                .crab
+                .get_page::<HookDelivery>(&page.next)
+                .await
+                .map_err(github_err)?
+            {
+                Some(mut next) => {
+                    deliveries.append(&mut next.take_items());
+                    page = next;
+                }
+                None => break,
+            }
+        }
+
+        Ok(deliveries)
+    }
+
+    async fn get_delivery_payload(&self, repo: &str, hook_id: u64, id: u64) -> Result<String> {
+        let detail: DeliveryDetail = self
+            .crab
+            .get(
+                format!("/repos/{}/hooks/{}/deliveries/{}", repo, hook_id, id),
+                None::<&()>,
+            )
+            .await
+            .map_err(github_err)?;
+
+        serde_json::to_string(&detail.request.payload).map_err(|e| Error::Internal(e.to_string()))
+    }
+}
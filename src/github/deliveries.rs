@@ -0,0 +1,50 @@
+//! Webhook delivery de-duplication
+//!
+//! GitHub gives every delivery a GUID in `X-GitHub-Delivery` and keeps it
+//! when a delivery is redelivered or retried after a timeout. A delivery is
+//! claimed right before it is dispatched; a second delivery with the same
+//! GUID is acknowledged without posting anything again.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{info, warn};
+
+use crate::error::Result;
+use crate::storage::Storage;
+
+/// How long a delivery is remembered. GitHub only redelivers deliveries
+/// from the past three days.
+pub const DELIVERY_TTL_SECS: u64 = 3 * 24 * 60 * 60;
+
+/// Duplicates dropped since the process started, reported by `/health`
+static DUPLICATES_DROPPED: AtomicU64 = AtomicU64::new(0);
+
+pub fn duplicates_dropped() -> u64 {
+    DUPLICATES_DROPPED.load(Ordering::Relaxed)
+}
+
+/// Claim a delivery for dispatch. Returns `false` (and counts the drop) if
+/// the same delivery was already claimed within the TTL.
+pub async fn claim_delivery(db: &dyn Storage, delivery_id: &str) -> Result<bool> {
+    if db.record_delivery(delivery_id, DELIVERY_TTL_SECS).await? {
+        return Ok(true);
+    }
+
+    let dropped = DUPLICATES_DROPPED.fetch_add(1, Ordering::Relaxed) + 1;
+    info!(delivery_id, dropped, "dropping duplicate webhook delivery");
+    Ok(false)
+}
+
+/// Give up a claim after a failed dispatch, so a redelivery is processed
+pub async fn release_delivery(db: &dyn Storage, delivery_id: &str) {
+    if let Err(e) = db.forget_delivery(delivery_id).await {
+        warn!(delivery_id, error = %e, "failed to release webhook delivery");
+    }
+}
+
+/// Claim a delivery again after it was posted without a claim, e.g. when a
+/// dead letter is replayed, so a later redelivery is still dropped
+pub async fn reclaim_delivery(db: &dyn Storage, delivery_id: &str) {
+    if let Err(e) = db.record_delivery(delivery_id, DELIVERY_TTL_SECS).await {
+        warn!(delivery_id, error = %e, "failed to reclaim webhook delivery");
+    }
+}
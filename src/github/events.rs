@@ -52,6 +52,14 @@ pub struct PullRequest {
     pub html_url: String,
     pub merged: Option<bool>,
     pub labels: Vec<Label>,
+    /// `open` or `closed`
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Author of the pull request
+    #[serde(default)]
+    pub user: Option<User>,
+    #[serde(default)]
+    pub merged_by: Option<User>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +71,10 @@ pub struct Label {
 pub struct IssueEvent {
     pub action: String,
     pub issue: Issue,
+    /// Sent with `milestoned` and `demilestoned`, when the issue itself may
+    /// no longer carry the milestone
+    #[serde(default)]
+    pub milestone: Option<Milestone>,
     pub repository: Repository,
     pub sender: User,
 }
@@ -73,6 +85,512 @@ pub struct Issue {
     pub title: String,
     pub html_url: String,
     pub labels: Vec<Label>,
+    /// `open` or `closed`
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Author of the issue
+    #[serde(default)]
+    pub user: Option<User>,
+    /// Current state of the issue's milestone, including its issue counts
+    #[serde(default)]
+    pub milestone: Option<Milestone>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Milestone {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub html_url: String,
+    /// `open` or `closed`
+    pub state: String,
+    pub open_issues: u64,
+    pub closed_issues: u64,
+    /// ISO 8601 timestamp
+    #[serde(default)]
+    pub due_on: Option<String>,
+}
+
+impl Milestone {
+    pub fn is_open(&self) -> bool {
+        self.state == "open"
+    }
+
+    /// Share of closed issues in percent, 0 for a milestone without issues
+    pub fn progress(&self) -> u64 {
+        let total = self.open_issues + self.closed_issues;
+        if total == 0 {
+            return 0;
+        }
+        self.closed_issues * 100 / total
+    }
+
+    /// Due date as `YYYY-MM-DD`
+    pub fn due_date(&self) -> Option<&str> {
+        self.due_on.as_deref().map(|d| d.get(..10).unwrap_or(d))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneEvent {
+    pub action: String,
+    pub milestone: Milestone,
+    #[serde(default)]
+    pub changes: Option<MilestoneChanges>,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+impl MilestoneEvent {
+    /// Whether an `edited` event moved the due date
+    pub fn due_date_changed(&self) -> bool {
+        self.changes.as_ref().is_some_and(|c| c.due_on.is_some())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneChanges {
+    #[serde(default)]
+    pub due_on: Option<DueOnChange>,
+}
+
+/// The previous due date, absent if none was set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DueOnChange {
+    pub from: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: u64,
+    pub body: String,
+    pub html_url: String,
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueCommentEvent {
+    pub action: String,
+    /// The issue or pull request the comment was left on
+    pub issue: Issue,
+    pub comment: Comment,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+impl IssueCommentEvent {
+    /// GitHub delivers comments on pull requests as issue comments too
+    pub fn is_pull_request(&self) -> bool {
+        self.issue.html_url.contains("/pull/")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestReviewCommentEvent {
+    pub action: String,
+    pub comment: Comment,
+    pub pull_request: PullRequest,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestReviewEvent {
+    pub action: String,
+    pub review: Review,
+    pub pull_request: PullRequest,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    pub id: u64,
+    /// `approved`, `changes_requested` or `commented`
+    pub state: String,
+    pub body: Option<String>,
+    pub html_url: String,
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscussionEvent {
+    pub action: String,
+    pub discussion: Discussion,
+    /// The comment marked as answer, on `answered` events
+    pub answer: Option<Comment>,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscussionCommentEvent {
+    pub action: String,
+    pub comment: Comment,
+    pub discussion: Discussion,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Discussion {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub category: DiscussionCategory,
+    pub answer_html_url: Option<String>,
+    pub user: User,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+impl Discussion {
+    pub fn is_answered(&self) -> bool {
+        self.answer_html_url.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscussionCategory {
+    pub name: String,
+}
+
+/// A GitHub advisory (`security_advisory`). Only delivered to GitHub Apps
+/// and not tied to a repository, so it is dropped unless one is attached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityAdvisoryEvent {
+    pub action: String,
+    pub security_advisory: SecurityAdvisory,
+    pub repository: Option<Repository>,
+    pub sender: Option<User>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityAdvisory {
+    pub ghsa_id: String,
+    pub summary: String,
+    /// `low`, `medium`, `high` or `critical`
+    pub severity: String,
+    pub html_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependabotAlertEvent {
+    pub action: String,
+    pub alert: DependabotAlert,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependabotAlert {
+    pub number: u64,
+    pub state: String,
+    pub html_url: String,
+    pub security_advisory: SecurityAdvisory,
+    pub dependency: Dependency,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    pub package: Package,
+    pub manifest_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub ecosystem: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeScanningAlertEvent {
+    pub action: String,
+    pub alert: CodeScanningAlert,
+    #[serde(rename = "ref")]
+    pub ref_name: Option<String>,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeScanningAlert {
+    pub number: u64,
+    pub state: Option<String>,
+    pub html_url: String,
+    pub rule: CodeScanningRule,
+    pub tool: CodeScanningTool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeScanningRule {
+    pub id: String,
+    pub description: String,
+    /// `none`, `note`, `warning` or `error`
+    pub severity: Option<String>,
+    /// `low`, `medium`, `high` or `critical` for security queries
+    pub security_severity_level: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeScanningTool {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretScanningAlertEvent {
+    pub action: String,
+    pub alert: SecretScanningAlert,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretScanningAlert {
+    pub number: u64,
+    pub html_url: String,
+    pub secret_type: String,
+    pub secret_type_display_name: Option<String>,
+    pub state: Option<String>,
+}
+
+/// Repository with the counters that star and fork events report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryStats {
+    pub full_name: String,
+    pub name: String,
+    pub html_url: String,
+    pub stargazers_count: u64,
+    pub forks_count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarEvent {
+    /// `created` or `deleted`
+    pub action: String,
+    pub repository: RepositoryStats,
+    pub sender: User,
+}
+
+impl StarEvent {
+    /// The star count if this star reached a milestone (100, 500, 1000, 5000, …)
+    pub fn milestone(&self) -> Option<u64> {
+        let count = self.repository.stargazers_count;
+        (self.action == "created" && is_star_milestone(count)).then_some(count)
+    }
+}
+
+/// 1× and 5× powers of ten from 100 up
+pub fn is_star_milestone(count: u64) -> bool {
+    let mut milestone = 100;
+    while milestone <= count {
+        if count == milestone || count == milestone * 5 {
+            return true;
+        }
+        milestone *= 10;
+    }
+    false
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkEvent {
+    /// The newly created fork
+    pub forkee: Forkee,
+    pub repository: RepositoryStats,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Forkee {
+    pub full_name: String,
+    pub html_url: String,
+    pub owner: User,
+}
+
+/// `create` and `delete` events for branches and tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefEvent {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// `branch` or `tag`
+    pub ref_type: String,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+impl RefEvent {
+    pub fn is_tag(&self) -> bool {
+        self.ref_type == "tag"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deployment {
+    pub id: u64,
+    pub sha: String,
+    /// Branch, tag or SHA that was deployed
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub environment: String,
+    pub description: Option<String>,
+    pub creator: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentEvent {
+    pub action: String,
+    pub deployment: Deployment,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentStatus {
+    pub id: u64,
+    /// `queued`, `pending`, `in_progress`, `success`, `failure`, `error` or `inactive`
+    pub state: String,
+    pub description: Option<String>,
+    /// The deployed site; `target_url` is its deprecated alias
+    pub environment_url: Option<String>,
+    pub target_url: Option<String>,
+    pub log_url: Option<String>,
+    pub creator: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentStatusEvent {
+    pub action: String,
+    pub deployment_status: DeploymentStatus,
+    pub deployment: Deployment,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+/// GitHub App that reported a check suite or run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct App {
+    pub id: u64,
+    pub slug: String,
+    pub name: String,
+}
+
+/// Slug of the app behind GitHub Actions' own checks, which `workflow_run` already covers
+pub const ACTIONS_APP_SLUG: &str = "github-actions";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckSuite {
+    pub id: u64,
+    pub head_branch: Option<String>,
+    pub head_sha: String,
+    pub status: Option<String>,
+    pub conclusion: Option<String>,
+    pub app: App,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckSuiteEvent {
+    pub action: String,
+    pub check_suite: CheckSuite,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+/// The suite a check run belongs to, as embedded in `check_run` payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckRunSuite {
+    pub id: u64,
+    pub head_branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckRun {
+    pub id: u64,
+    pub name: String,
+    pub head_sha: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub html_url: String,
+    pub app: App,
+    pub check_suite: CheckRunSuite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckRunEvent {
+    pub action: String,
+    pub check_run: CheckRun,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+/// Sent once when a webhook is created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingEvent {
+    pub zen: String,
+    pub hook_id: u64,
+    /// Missing for organization webhooks
+    pub repository: Option<Repository>,
+    pub sender: Option<User>,
+}
+
+/// Repository lifecycle: renamed, transferred, archived, deleted, …
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryEvent {
+    pub action: String,
+    pub changes: Option<RepositoryChanges>,
+    /// The repository as it is now, i.e. already under its new name
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryChanges {
+    /// Set on `renamed`
+    pub repository: Option<RepositoryNameChange>,
+    /// Set on `transferred`
+    pub owner: Option<OwnerChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryNameChange {
+    pub name: ChangedFrom,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFrom {
+    pub from: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerChange {
+    pub from: PreviousOwner,
+}
+
+/// Exactly one of the two is set, depending on the account type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviousOwner {
+    pub user: Option<User>,
+    pub organization: Option<User>,
+}
+
+impl RepositoryEvent {
+    /// `owner/name` before a rename or transfer
+    pub fn previous_full_name(&self) -> Option<String> {
+        let changes = self.changes.as_ref()?;
+        match self.action.as_str() {
+            "renamed" => {
+                let owner = self.repository.full_name.split('/').next()?;
+                let name = &changes.repository.as_ref()?.name.from;
+                Some(format!("{}/{}", owner, name))
+            }
+            "transferred" => {
+                let from = &changes.owner.as_ref()?.from;
+                let owner = from.user.as_ref().or(from.organization.as_ref())?;
+                Some(format!("{}/{}", owner.login, self.repository.name))
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,17 +605,84 @@ pub struct WorkflowRunEvent {
 pub struct WorkflowRun {
     pub id: u64,
     pub name: Option<String>,
+    /// `queued`, `in_progress` or `completed`
+    #[serde(default)]
+    pub status: Option<String>,
     pub conclusion: Option<String>,
     pub html_url: String,
     pub head_branch: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushEvent {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub before: String,
+    pub after: String,
+    pub compare: String,
+    #[serde(default)]
+    pub forced: bool,
+    #[serde(default)]
+    pub deleted: bool,
+    pub commits: Vec<Commit>,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+impl PushEvent {
+    /// Branch name for branch pushes (`refs/heads/<branch>`), `None` for tags
+    pub fn branch(&self) -> Option<&str> {
+        self.ref_name.strip_prefix("refs/heads/")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Commit {
+    pub id: String,
+    pub message: String,
+    pub url: String,
+    pub author: CommitAuthor,
+}
+
+impl Commit {
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or("")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitAuthor {
+    pub name: String,
+    pub username: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ParsedEvent {
     Release(ReleaseEvent),
     PullRequest(PullRequestEvent),
     Issue(IssueEvent),
     WorkflowRun(WorkflowRunEvent),
+    Push(PushEvent),
+    IssueComment(IssueCommentEvent),
+    PullRequestReviewComment(PullRequestReviewCommentEvent),
+    PullRequestReview(PullRequestReviewEvent),
+    Discussion(DiscussionEvent),
+    DiscussionComment(DiscussionCommentEvent),
+    SecurityAdvisory(SecurityAdvisoryEvent),
+    DependabotAlert(DependabotAlertEvent),
+    CodeScanningAlert(CodeScanningAlertEvent),
+    SecretScanningAlert(SecretScanningAlertEvent),
+    Star(StarEvent),
+    Fork(ForkEvent),
+    Create(RefEvent),
+    Delete(RefEvent),
+    Ping(PingEvent),
+    Repository(RepositoryEvent),
+    Deployment(DeploymentEvent),
+    DeploymentStatus(DeploymentStatusEvent),
+    CheckSuite(CheckSuiteEvent),
+    CheckRun(CheckRunEvent),
+    Milestone(MilestoneEvent),
     Unknown,
 }
 
@@ -108,6 +693,31 @@ impl ParsedEvent {
             "pull_request" => Ok(Self::PullRequest(serde_json::from_slice(payload)?)),
             "issues" => Ok(Self::Issue(serde_json::from_slice(payload)?)),
             "workflow_run" => Ok(Self::WorkflowRun(serde_json::from_slice(payload)?)),
+            "push" => Ok(Self::Push(serde_json::from_slice(payload)?)),
+            "issue_comment" => Ok(Self::IssueComment(serde_json::from_slice(payload)?)),
+            "pull_request_review_comment" => Ok(Self::PullRequestReviewComment(
+                serde_json::from_slice(payload)?,
+            )),
+            "pull_request_review" => Ok(Self::PullRequestReview(serde_json::from_slice(payload)?)),
+            "discussion" => Ok(Self::Discussion(serde_json::from_slice(payload)?)),
+            "discussion_comment" => Ok(Self::DiscussionComment(serde_json::from_slice(payload)?)),
+            "security_advisory" => Ok(Self::SecurityAdvisory(serde_json::from_slice(payload)?)),
+            "dependabot_alert" => Ok(Self::DependabotAlert(serde_json::from_slice(payload)?)),
+            "code_scanning_alert" => Ok(Self::CodeScanningAlert(serde_json::from_slice(payload)?)),
+            "secret_scanning_alert" => {
+                Ok(Self::SecretScanningAlert(serde_json::from_slice(payload)?))
+            }
+            "star" => Ok(Self::Star(serde_json::from_slice(payload)?)),
+            "fork" => Ok(Self::Fork(serde_json::from_slice(payload)?)),
+            "create" => Ok(Self::Create(serde_json::from_slice(payload)?)),
+            "delete" => Ok(Self::Delete(serde_json::from_slice(payload)?)),
+            "ping" => Ok(Self::Ping(serde_json::from_slice(payload)?)),
+            "repository" => Ok(Self::Repository(serde_json::from_slice(payload)?)),
+            "milestone" => Ok(Self::Milestone(serde_json::from_slice(payload)?)),
+            "deployment" => Ok(Self::Deployment(serde_json::from_slice(payload)?)),
+            "deployment_status" => Ok(Self::DeploymentStatus(serde_json::from_slice(payload)?)),
+            "check_suite" => Ok(Self::CheckSuite(serde_json::from_slice(payload)?)),
+            "check_run" => Ok(Self::CheckRun(serde_json::from_slice(payload)?)),
             _ => Ok(Self::Unknown),
         }
     }
@@ -118,6 +728,31 @@ impl ParsedEvent {
             Self::PullRequest(e) => Some(format!("pull_request.{}", e.action)),
             Self::Issue(e) => Some(format!("issues.{}", e.action)),
             Self::WorkflowRun(e) => Some(format!("workflow_run.{}", e.action)),
+            // Pushes have no action
+            Self::Push(_) => Some("push".to_string()),
+            Self::IssueComment(e) => Some(format!("issue_comment.{}", e.action)),
+            Self::PullRequestReviewComment(e) => {
+                Some(format!("pull_request_review_comment.{}", e.action))
+            }
+            Self::PullRequestReview(e) => Some(format!("pull_request_review.{}", e.action)),
+            Self::Discussion(e) => Some(format!("discussion.{}", e.action)),
+            Self::DiscussionComment(e) => Some(format!("discussion_comment.{}", e.action)),
+            Self::SecurityAdvisory(e) => Some(format!("security_advisory.{}", e.action)),
+            Self::DependabotAlert(e) => Some(format!("dependabot_alert.{}", e.action)),
+            Self::CodeScanningAlert(e) => Some(format!("code_scanning_alert.{}", e.action)),
+            Self::SecretScanningAlert(e) => Some(format!("secret_scanning_alert.{}", e.action)),
+            Self::Star(e) => Some(format!("star.{}", e.action)),
+            Self::Fork(_) => Some("fork".to_string()),
+            // The ref type stands in for the action, e.g. `create.tag`
+            Self::Create(e) => Some(format!("create.{}", e.ref_type)),
+            Self::Delete(e) => Some(format!("delete.{}", e.ref_type)),
+            Self::Ping(_) => Some("ping".to_string()),
+            Self::Repository(e) => Some(format!("repository.{}", e.action)),
+            Self::Deployment(e) => Some(format!("deployment.{}", e.action)),
+            Self::DeploymentStatus(e) => Some(format!("deployment_status.{}", e.action)),
+            Self::CheckSuite(e) => Some(format!("check_suite.{}", e.action)),
+            Self::CheckRun(e) => Some(format!("check_run.{}", e.action)),
+            Self::Milestone(e) => Some(format!("milestone.{}", e.action)),
             Self::Unknown => None,
         }
     }
@@ -128,6 +763,26 @@ impl ParsedEvent {
             Self::PullRequest(e) => Some(&e.repository.full_name),
             Self::Issue(e) => Some(&e.repository.full_name),
             Self::WorkflowRun(e) => Some(&e.repository.full_name),
+            Self::Push(e) => Some(&e.repository.full_name),
+            Self::IssueComment(e) => Some(&e.repository.full_name),
+            Self::PullRequestReviewComment(e) => Some(&e.repository.full_name),
+            Self::PullRequestReview(e) => Some(&e.repository.full_name),
+            Self::Discussion(e) => Some(&e.repository.full_name),
+            Self::DiscussionComment(e) => Some(&e.repository.full_name),
+            Self::SecurityAdvisory(e) => e.repository.as_ref().map(|r| r.full_name.as_str()),
+            Self::DependabotAlert(e) => Some(&e.repository.full_name),
+            Self::CodeScanningAlert(e) => Some(&e.repository.full_name),
+            Self::SecretScanningAlert(e) => Some(&e.repository.full_name),
+            Self::Star(e) => Some(&e.repository.full_name),
+            Self::Fork(e) => Some(&e.repository.full_name),
+            Self::Create(e) | Self::Delete(e) => Some(&e.repository.full_name),
+            Self::Ping(e) => e.repository.as_ref().map(|r| r.full_name.as_str()),
+            Self::Repository(e) => Some(&e.repository.full_name),
+            Self::Deployment(e) => Some(&e.repository.full_name),
+            Self::DeploymentStatus(e) => Some(&e.repository.full_name),
+            Self::CheckSuite(e) => Some(&e.repository.full_name),
+            Self::CheckRun(e) => Some(&e.repository.full_name),
+            Self::Milestone(e) => Some(&e.repository.full_name),
             Self::Unknown => None,
         }
     }
@@ -138,6 +793,26 @@ impl ParsedEvent {
             Self::PullRequest(e) => Some(&e.sender.login),
             Self::Issue(e) => Some(&e.sender.login),
             Self::WorkflowRun(e) => Some(&e.sender.login),
+            Self::Push(e) => Some(&e.sender.login),
+            Self::IssueComment(e) => Some(&e.sender.login),
+            Self::PullRequestReviewComment(e) => Some(&e.sender.login),
+            Self::PullRequestReview(e) => Some(&e.sender.login),
+            Self::Discussion(e) => Some(&e.sender.login),
+            Self::DiscussionComment(e) => Some(&e.sender.login),
+            Self::SecurityAdvisory(e) => e.sender.as_ref().map(|s| s.login.as_str()),
+            Self::DependabotAlert(e) => Some(&e.sender.login),
+            Self::CodeScanningAlert(e) => Some(&e.sender.login),
+            Self::SecretScanningAlert(e) => Some(&e.sender.login),
+            Self::Star(e) => Some(&e.sender.login),
+            Self::Fork(e) => Some(&e.sender.login),
+            Self::Create(e) | Self::Delete(e) => Some(&e.sender.login),
+            Self::Ping(e) => e.sender.as_ref().map(|s| s.login.as_str()),
+            Self::Repository(e) => Some(&e.sender.login),
+            Self::Deployment(e) => Some(&e.sender.login),
+            Self::DeploymentStatus(e) => Some(&e.sender.login),
+            Self::CheckSuite(e) => Some(&e.sender.login),
+            Self::CheckRun(e) => Some(&e.sender.login),
+            Self::Milestone(e) => Some(&e.sender.login),
             Self::Unknown => None,
         }
     }
@@ -151,14 +826,166 @@ impl ParsedEvent {
                 .map(|l| l.name.clone())
                 .collect(),
             Self::Issue(e) => e.issue.labels.iter().map(|l| l.name.clone()).collect(),
+            Self::IssueComment(e) => e.issue.labels.iter().map(|l| l.name.clone()).collect(),
+            Self::PullRequestReviewComment(e) => e
+                .pull_request
+                .labels
+                .iter()
+                .map(|l| l.name.clone())
+                .collect(),
+            Self::PullRequestReview(e) => e
+                .pull_request
+                .labels
+                .iter()
+                .map(|l| l.name.clone())
+                .collect(),
+            Self::Discussion(e) => e.discussion.labels.iter().map(|l| l.name.clone()).collect(),
+            Self::DiscussionComment(e) => {
+                e.discussion.labels.iter().map(|l| l.name.clone()).collect()
+            }
             _ => vec![],
         }
     }
 
+    /// Branch the event happened on, where that applies
+    pub fn branch(&self) -> Option<&str> {
+        match self {
+            Self::WorkflowRun(e) => e.workflow_run.head_branch.as_deref(),
+            Self::Push(e) => e.branch(),
+            Self::Create(e) | Self::Delete(e) if !e.is_tag() => Some(&e.ref_name),
+            Self::Deployment(e) => Some(&e.deployment.ref_name),
+            Self::DeploymentStatus(e) => Some(&e.deployment.ref_name),
+            Self::CheckSuite(e) => e.check_suite.head_branch.as_deref(),
+            Self::CheckRun(e) => e.check_run.check_suite.head_branch.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The app that reported a check suite or run
+    pub fn check_app(&self) -> Option<&App> {
+        match self {
+            Self::CheckSuite(e) => Some(&e.check_suite.app),
+            Self::CheckRun(e) => Some(&e.check_run.app),
+            _ => None,
+        }
+    }
+
+    /// Checks created by GitHub Actions, which also sends `workflow_run`
+    pub fn is_actions_check(&self) -> bool {
+        self.check_app()
+            .is_some_and(|app| app.slug == ACTIONS_APP_SLUG)
+    }
+
+    /// The deployment a deployment event or status belongs to
+    pub fn deployment(&self) -> Option<&Deployment> {
+        match self {
+            Self::Deployment(e) => Some(&e.deployment),
+            Self::DeploymentStatus(e) => Some(&e.deployment),
+            _ => None,
+        }
+    }
+
+    /// The milestone an event reports on: the milestone itself for milestone
+    /// events, the issue's milestone for issue events
+    pub fn milestone(&self) -> Option<&Milestone> {
+        match self {
+            Self::Milestone(e) => Some(&e.milestone),
+            Self::Issue(e) => e.milestone.as_ref().or(e.issue.milestone.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Stable key of the GitHub item an event updates, e.g.
+    /// `owner/repo/deployments/42`. Messages posted for the item are edited
+    /// by later events with the same key.
+    pub fn item_key(&self) -> Option<String> {
+        let repo = self.repo_full_name()?.to_lowercase();
+        match self {
+            Self::Deployment(_) | Self::DeploymentStatus(_) => {
+                Some(format!("{}/deployments/{}", repo, self.deployment()?.id))
+            }
+            Self::Milestone(e) => Some(milestone_key(&repo, e.milestone.number)),
+            Self::Issue(e) => Some(format!("{}/issues/{}", repo, e.issue.number)),
+            Self::PullRequest(e) => Some(format!("{}/pulls/{}", repo, e.pull_request.number)),
+            Self::WorkflowRun(e) => Some(format!("{}/runs/{}", repo, e.workflow_run.id)),
+            _ => None,
+        }
+    }
+
+    /// Discussion category name for discussions and their comments
+    pub fn category(&self) -> Option<&str> {
+        match self {
+            Self::Discussion(e) => Some(&e.discussion.category.name),
+            Self::DiscussionComment(e) => Some(&e.discussion.category.name),
+            _ => None,
+        }
+    }
+
+    /// Security alerts only ever go to the private moderator channel
+    pub fn is_security_alert(&self) -> bool {
+        matches!(
+            self,
+            Self::SecurityAdvisory(_)
+                | Self::DependabotAlert(_)
+                | Self::CodeScanningAlert(_)
+                | Self::SecretScanningAlert(_)
+        )
+    }
+
+    /// Normalised severity of a security alert: `low`, `medium`, `high` or
+    /// `critical`. Leaked secrets are always `critical`.
+    pub fn security_severity(&self) -> Option<&str> {
+        match self {
+            Self::SecurityAdvisory(e) => Some(&e.security_advisory.severity),
+            Self::DependabotAlert(e) => Some(&e.alert.security_advisory.severity),
+            Self::CodeScanningAlert(e) => {
+                let rule = &e.alert.rule;
+                match rule.security_severity_level.as_deref() {
+                    Some(level) => Some(level),
+                    None => match rule.severity.as_deref() {
+                        Some("error") => Some("high"),
+                        Some("warning") => Some("medium"),
+                        _ => Some("low"),
+                    },
+                }
+            }
+            Self::SecretScanningAlert(_) => Some("critical"),
+            _ => None,
+        }
+    }
+
+    /// Review state (lowercase) for pull request reviews
+    pub fn review_state(&self) -> Option<&str> {
+        match self {
+            Self::PullRequestReview(e) => Some(&e.review.state),
+            _ => None,
+        }
+    }
+
+    /// Comments and reviews are posted into the thread of the item they belong to
+    pub fn targets_item_thread(&self) -> bool {
+        matches!(
+            self,
+            Self::IssueComment(_) | Self::PullRequestReviewComment(_) | Self::PullRequestReview(_)
+        )
+    }
+
     pub fn is_merged(&self) -> bool {
         match self {
             Self::PullRequest(e) => e.pull_request.merged.unwrap_or(false),
+            Self::PullRequestReviewComment(e) => e.pull_request.merged.unwrap_or(false),
+            Self::PullRequestReview(e) => e.pull_request.merged.unwrap_or(false),
             _ => false,
         }
     }
 }
+
+/// Item key of a milestone, shared by milestone events and the issues in it
+pub fn milestone_key(repo: &str, number: u64) -> String {
+    format!("{}/milestones/{}", repo.to_lowercase(), number)
+}
+
+/// Key recorded once a star milestone was announced, so it is announced once
+pub fn star_milestone_key(repo: &str, count: u64) -> String {
+    format!("{}/stars/{}", repo.to_lowercase(), count)
+}
@@ -1,3 +1,6 @@
+pub mod backfill;
+pub mod client;
+pub mod deliveries;
 pub mod events;
 pub mod verify;
 pub mod webhook;
@@ -7,8 +7,9 @@ use axum::{
 use tracing::{info, warn};
 
 use crate::error::{Error, Result};
-use crate::github::{events::ParsedEvent, verify::verify_github_signature};
-use crate::router::dispatch::Dispatcher;
+use crate::github::{deliveries, events::ParsedEvent, verify::verify_github_signature};
+use crate::governance::projects;
+use crate::router::queue::Delivery;
 use crate::AppState;
 
 pub async fn handle_webhook(
@@ -39,8 +40,51 @@ pub async fn handle_webhook(
         return Ok(StatusCode::OK);
     }
 
-    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
-    dispatcher.dispatch(event).await?;
+    // Answering a ping with an error shows a failed delivery in GitHub's
+    // webhook settings, the only feedback the person setting it up gets
+    if let ParsedEvent::Ping(ping) = &event {
+        if let Some(repository) = &ping.repository {
+            let repo = repository.full_name.to_lowercase();
+            if projects::get_approved_project(state.db.as_ref(), &repo)
+                .await?
+                .is_none()
+            {
+                warn!(
+                    repo,
+                    "ping for a repository that is not an approved project"
+                );
+                return Err(Error::NotFound(format!(
+                    "{} is not an approved ByteHub project",
+                    repo
+                )));
+            }
+        }
+    }
+
+    // Redeliveries and retries keep their delivery GUID. Claiming only now
+    // means a rejected ping is processed again once the project is approved.
+    let delivery_id = headers
+        .get("x-github-delivery")
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| !id.is_empty());
+    if let Some(id) = delivery_id {
+        if !deliveries::claim_delivery(state.db.as_ref(), id).await? {
+            return Ok(StatusCode::OK);
+        }
+    }
+
+    // Dispatching can outlast GitHub's 10 second timeout, so it runs in the background
+    let delivery = Delivery {
+        delivery_id: delivery_id.map(String::from),
+        event_type: event_type.to_string(),
+        payload: String::from_utf8_lossy(&body).into_owned(),
+    };
+    if let Err(e) = state.queue.enqueue(event, delivery) {
+        if let Some(id) = delivery_id {
+            deliveries::release_delivery(state.db.as_ref(), id).await;
+        }
+        return Err(e);
+    }
 
-    Ok(StatusCode::OK)
+    Ok(StatusCode::ACCEPTED)
 }
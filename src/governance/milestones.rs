@@ -0,0 +1,35 @@
+//! Milestone snapshots for `/milestone`
+//!
+//! GitHub sends a milestone's issue counts with every milestone event and
+//! with every issue event for an issue in it. The latest snapshot is stored
+//! per project, so progress can be shown without calling the GitHub API.
+
+use crate::error::Result;
+use crate::github::events::Milestone;
+use crate::storage::Storage;
+
+pub async fn save_milestone(
+    db: &dyn Storage,
+    project_id: &str,
+    milestone: &Milestone,
+) -> Result<()> {
+    db.save_milestone(project_id, milestone).await
+}
+
+pub async fn delete_milestone(db: &dyn Storage, project_id: &str, number: u64) -> Result<()> {
+    db.delete_milestone(project_id, number).await
+}
+
+/// Open milestones of a project, the next due first and undated ones last
+pub async fn list_open_milestones(db: &dyn Storage, project_id: &str) -> Result<Vec<Milestone>> {
+    let mut milestones: Vec<Milestone> = db
+        .list_milestones(project_id)
+        .await?
+        .into_iter()
+        .filter(|m| m.is_open())
+        .collect();
+    milestones.sort_by(|a, b| {
+        (a.due_on.is_none(), &a.due_on, a.number).cmp(&(b.due_on.is_none(), &b.due_on, b.number))
+    });
+    Ok(milestones)
+}
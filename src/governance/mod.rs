@@ -1,3 +1,4 @@
+pub mod milestones;
 pub mod projects;
 pub mod rules;
 pub mod server_config;
@@ -1,73 +1,505 @@
-use convex::Value as ConvexValue;
-use maplit::btreemap;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
+use crate::discord::templates;
 use crate::error::{Error, Result};
 use crate::github::events::ParsedEvent;
-use crate::storage::convex::ConvexDb;
+use crate::governance::projects::Project;
+use crate::governance::whitelist;
+use crate::storage::Storage;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RuleConditions {
     pub event_type: Option<String>,
+    /// Matches if the event has *any* of these labels
     pub labels: Option<Vec<String>>,
+    /// Matches if the event has *all* of these labels
+    pub labels_all: Option<Vec<String>>,
+    /// Matches if the event has *none* of these labels
+    pub labels_none: Option<Vec<String>>,
     pub actor_whitelisted: Option<bool>,
     pub merged: Option<bool>,
+    /// Matches if the event's branch matches any of these patterns; a
+    /// trailing `*` matches a prefix, e.g. `release/*`
+    #[serde(default)]
+    pub branches: Option<Vec<String>>,
+    /// Pull request review state: `approved`, `changes_requested` or `commented`
+    #[serde(default)]
+    pub review_state: Option<String>,
+    /// Discussion category name (case-insensitive), e.g. `Q&A`
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// The facts about an event that rule conditions are evaluated against
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RuleContext {
+    pub event_key: Option<String>,
+    pub is_merged: bool,
+    pub labels: Vec<String>,
+    pub actor_whitelisted: bool,
+    /// Branch for pushes and workflow runs
+    pub branch: Option<String>,
+    pub review_state: Option<String>,
+    /// Category for discussions and their comments
+    pub category: Option<String>,
+}
+
+impl RuleContext {
+    /// Build the context for an event, looking up whether its actor is
+    /// whitelisted in the project's guild
+    pub async fn for_event(db: &dyn Storage, guild_id: &str, event: &ParsedEvent) -> Result<Self> {
+        let actor_whitelisted = match event.actor() {
+            Some(actor) => whitelist::is_whitelisted(db, guild_id, actor).await?,
+            None => false,
+        };
+
+        Ok(Self {
+            event_key: event.event_key(),
+            is_merged: event.is_merged(),
+            labels: event.labels(),
+            actor_whitelisted,
+            branch: event.branch().map(str::to_string),
+            review_state: event.review_state().map(str::to_string),
+            category: event.category().map(str::to_string),
+        })
+    }
+
+    /// GitHub label names are case-insensitive
+    fn has_label(&self, label: &str) -> bool {
+        self.labels.iter().any(|l| l.eq_ignore_ascii_case(label))
+    }
+
+    fn on_branch(&self, pattern: &str) -> bool {
+        let Some(branch) = self.branch.as_deref() else {
+            return false;
+        };
+        match pattern.strip_suffix('*') {
+            Some(prefix) => branch.starts_with(prefix),
+            None => branch == pattern,
+        }
+    }
+}
+
+impl RuleConditions {
+    /// Check the conditions against an event (mirrors `rules:evaluateForProject`)
+    pub fn matches(&self, ctx: &RuleContext) -> bool {
+        if let Some(event_type) = &self.event_type {
+            if Some(event_type) != ctx.event_key.as_ref() {
+                return false;
+            }
+        }
+
+        if let Some(merged) = self.merged {
+            if merged != ctx.is_merged {
+                return false;
+            }
+        }
+
+        if let Some(any) = &self.labels {
+            if !any.is_empty() && !any.iter().any(|l| ctx.has_label(l)) {
+                return false;
+            }
+        }
+
+        if let Some(all) = &self.labels_all {
+            if !all.iter().all(|l| ctx.has_label(l)) {
+                return false;
+            }
+        }
+
+        if let Some(none) = &self.labels_none {
+            if none.iter().any(|l| ctx.has_label(l)) {
+                return false;
+            }
+        }
+
+        if let Some(whitelisted) = self.actor_whitelisted {
+            if whitelisted != ctx.actor_whitelisted {
+                return false;
+            }
+        }
+
+        if let Some(branches) = &self.branches {
+            if !branches.is_empty() && !branches.iter().any(|b| ctx.on_branch(b)) {
+                return false;
+            }
+        }
+
+        if let Some(state) = &self.review_state {
+            if Some(state) != ctx.review_state.as_ref() {
+                return false;
+            }
+        }
+
+        if let Some(category) = &self.category {
+            let same = ctx
+                .category
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(category));
+            if !same {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Human-readable summary of the conditions, used as the match reason in logs
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(event_type) = &self.event_type {
+            parts.push(format!("event is {}", event_type));
+        }
+        if let Some(merged) = self.merged {
+            parts.push(format!("merged = {}", merged));
+        }
+        if let Some(any) = self.labels.as_ref().filter(|l| !l.is_empty()) {
+            parts.push(format!("has any of [{}]", any.join(", ")));
+        }
+        if let Some(all) = &self.labels_all {
+            parts.push(format!("has all of [{}]", all.join(", ")));
+        }
+        if let Some(none) = &self.labels_none {
+            parts.push(format!("has none of [{}]", none.join(", ")));
+        }
+        if let Some(whitelisted) = self.actor_whitelisted {
+            parts.push(format!("actor whitelisted = {}", whitelisted));
+        }
+        if let Some(branches) = self.branches.as_ref().filter(|b| !b.is_empty()) {
+            parts.push(format!("branch is any of [{}]", branches.join(", ")));
+        }
+        if let Some(state) = &self.review_state {
+            parts.push(format!("review is {}", state));
+        }
+        if let Some(category) = &self.category {
+            parts.push(format!("category is {}", category));
+        }
+
+        if parts.is_empty() {
+            "matches every event".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleActions {
+    /// Log to the project's pinned activity thread (defaults to true for
+    /// rules created before this flag existed)
+    #[serde(default = "default_true")]
+    pub post_activity: bool,
+    /// Post to the dedicated sidebar thread in the project forum
     pub post_forum: bool,
     pub post_announce: bool,
+    /// Custom message layout, see `discord::templates`
     pub template: Option<String>,
+    /// Keep evaluating lower-priority rules after this one matches.
+    /// By default the first matching rule stops evaluation.
+    #[serde(default)]
+    pub continue_matching: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Convex stores numbers as floats, so accept any JSON number for priorities
+fn deserialize_priority<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<i64, D::Error> {
+    let value = f64::deserialize(deserializer)?;
+    Ok(value as i64)
+}
+
+/// A routing rule as stored for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub project_id: String,
+    #[serde(deserialize_with = "deserialize_priority")]
+    pub priority: i64,
+    pub conditions: RuleConditions,
+    pub actions: RuleActions,
 }
 
+#[derive(Debug, Clone, Serialize)]
 pub struct RuleMatch {
     pub actions: RuleActions,
     pub rule_id: String,
+    pub priority: i64,
+    /// Why the rule matched (its conditions), for logging
+    pub reason: String,
 }
 
-pub async fn evaluate_rules(
-    db: &ConvexDb,
-    project_id: &str,
-    event: &ParsedEvent,
-) -> Result<Option<RuleMatch>> {
-    let event_key = event.event_key();
-    let is_merged = event.is_merged();
-
-    let result = db
-        .query(
-            "rules:evaluateForProject",
-            btreemap! {
-                "project_id".into() => ConvexValue::String(project_id.to_string()),
-                "event_key".into() => match &event_key {
-                    Some(k) => ConvexValue::String(k.clone()),
-                    None => ConvexValue::Null,
-                },
-                "is_merged".into() => ConvexValue::Boolean(is_merged),
-            },
-        )
-        .await?;
-
-    if result.is_null() {
-        return Ok(None);
+/// Outcome of running a project's rules against one event
+#[derive(Debug, Clone, Default)]
+pub struct RuleEvaluation {
+    /// Number of rules the project has; zero means built-in routing applies
+    pub rule_count: usize,
+    /// Matching rules in evaluation order (highest priority first)
+    pub matches: Vec<RuleMatch>,
+    /// Whether the event's actor is on the guild whitelist
+    pub actor_whitelisted: bool,
+}
+
+impl RuleEvaluation {
+    pub fn has_rules(&self) -> bool {
+        self.rule_count > 0
+    }
+}
+
+/// Evaluates a project's rules locally, highest priority first
+pub struct RuleEvaluator {
+    rules: Vec<Rule>,
+}
+
+impl RuleEvaluator {
+    pub fn new(mut rules: Vec<Rule>) -> Self {
+        // Stable sort keeps insertion order for rules with equal priority
+        rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        Self { rules }
     }
 
-    let obj = result
-        .as_object()
-        .ok_or_else(|| Error::InvalidPayload("Expected object from evaluate".into()))?;
+    /// Collect every matching rule until one without `continue_matching` is
+    /// hit. When nothing matches, a default rule from `added_default_rules`
+    /// applies unless the project's rules mention the event type.
+    pub fn evaluate(&self, ctx: &RuleContext) -> RuleEvaluation {
+        let mut matches = Vec::new();
 
-    let rule_id = obj
-        .get("rule_id")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| Error::InvalidPayload("Missing rule_id".into()))?
-        .to_string();
+        for rule in &self.rules {
+            if !rule.conditions.matches(ctx) {
+                continue;
+            }
 
-    let actions_value = obj
-        .get("actions")
-        .ok_or_else(|| Error::InvalidPayload("Missing actions".into()))?;
+            matches.push(RuleMatch {
+                actions: rule.actions.clone(),
+                rule_id: rule.id.clone(),
+                priority: rule.priority,
+                reason: rule.conditions.describe(),
+            });
 
-    let actions: RuleActions = serde_json::from_value(actions_value.clone())
-        .map_err(|e| Error::InvalidPayload(format!("Failed to parse actions: {}", e)))?;
+            if !rule.actions.continue_matching {
+                break;
+            }
+        }
+
+        if matches.is_empty() && !self.rules.is_empty() && !self.mentions_event(ctx) {
+            if let Some((conditions, actions)) = added_default_rules()
+                .into_iter()
+                .find(|(conditions, _)| conditions.matches(ctx))
+            {
+                matches.push(RuleMatch {
+                    actions,
+                    rule_id: DEFAULT_RULE_ID.to_string(),
+                    priority: 0,
+                    reason: format!("built-in default, {}", conditions.describe()),
+                });
+            }
+        }
+
+        RuleEvaluation {
+            rule_count: self.rules.len(),
+            matches,
+            actor_whitelisted: ctx.actor_whitelisted,
+        }
+    }
+
+    /// Whether any rule names the event's type, with or without an action
+    fn mentions_event(&self, ctx: &RuleContext) -> bool {
+        let Some(event) = ctx.event_key.as_deref() else {
+            return false;
+        };
+        let event = event.split('.').next().unwrap_or(event);
+        self.rules.iter().any(|rule| {
+            rule.conditions
+                .event_type
+                .as_deref()
+                .is_some_and(|t| t.split('.').next() == Some(event))
+        })
+    }
+}
+
+/// Largest absolute priority accepted from users. Convex stores numbers as
+/// floats, so priorities are kept well inside the exactly representable range.
+pub const MAX_PRIORITY: i64 = 1_000_000;
+
+fn check_priority(priority: i64) -> Result<()> {
+    if priority.abs() > MAX_PRIORITY {
+        return Err(Error::InvalidPayload(format!(
+            "Priority must be between -{0} and {0}",
+            MAX_PRIORITY
+        )));
+    }
+    Ok(())
+}
+
+/// Review states GitHub reports on `pull_request_review.submitted`
+pub const REVIEW_STATES: &[&str] = &["approved", "changes_requested", "commented"];
+
+/// Reject rules that could never match or would not round-trip through storage
+pub fn validate_rule(
+    priority: i64,
+    conditions: &RuleConditions,
+    actions: &RuleActions,
+) -> Result<()> {
+    check_priority(priority)?;
+
+    if let Some(event_type) = &conditions.event_type {
+        // "<event>" or "<event>.<action>", e.g. "pull_request.closed"
+        let valid = !event_type.is_empty()
+            && event_type.split('.').count() <= 2
+            && event_type.split('.').all(|part| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            });
+        if !valid {
+            return Err(Error::InvalidPayload(format!(
+                "Invalid event type `{}` (expected e.g. `pull_request.closed`)",
+                event_type
+            )));
+        }
+    }
+
+    for labels in [
+        &conditions.labels,
+        &conditions.labels_all,
+        &conditions.labels_none,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if labels.iter().any(|l| l.trim().is_empty()) {
+            return Err(Error::InvalidPayload("Label names cannot be empty".into()));
+        }
+    }
+
+    if let Some(branches) = &conditions.branches {
+        if branches.iter().any(|b| b.trim().is_empty()) {
+            return Err(Error::InvalidPayload("Branch names cannot be empty".into()));
+        }
+    }
+
+    if let Some(state) = &conditions.review_state {
+        if !REVIEW_STATES.contains(&state.as_str()) {
+            return Err(Error::InvalidPayload(format!(
+                "Invalid review state `{}` (expected one of {})",
+                state,
+                REVIEW_STATES.join(", ")
+            )));
+        }
+    }
+
+    if let Some(template) = &actions.template {
+        templates::validate(template)?;
+    }
+
+    Ok(())
+}
+
+/// Rule ID reported for a default rule applied without being stored
+pub const DEFAULT_RULE_ID: &str = "default";
+
+fn default_rule(
+    event_type: &str,
+    merged: Option<bool>,
+    post_announce: bool,
+) -> (RuleConditions, RuleActions) {
+    (
+        RuleConditions {
+            event_type: Some(event_type.to_string()),
+            merged,
+            ..Default::default()
+        },
+        RuleActions {
+            post_activity: true,
+            post_forum: true,
+            post_announce,
+            template: None,
+            continue_matching: false,
+        },
+    )
+}
+
+/// Plain comment reviews repeat their review comments, so only approvals
+/// and change requests are posted by default
+fn review_default_rule(state: &str) -> (RuleConditions, RuleActions) {
+    let (conditions, actions) = default_rule("pull_request_review.submitted", None, false);
+    let conditions = RuleConditions {
+        review_state: Some(state.to_string()),
+        ..conditions
+    };
+    (conditions, actions)
+}
+
+/// Rules created for every newly approved project (mirrors `projects:approveWithForum`)
+pub fn default_rules() -> Vec<(RuleConditions, RuleActions)> {
+    let mut rules = vec![
+        default_rule("workflow_run.completed", None, false),
+        default_rule("release.published", None, true),
+        default_rule("pull_request.closed", Some(true), false),
+        default_rule("issues.opened", None, false),
+    ];
+    rules.extend(added_default_rules());
+    rules
+}
+
+/// Default rules added after the first four. Projects approved earlier do
+/// not have them stored, so they also apply to event types that none of a
+/// project's rules mention.
+pub fn added_default_rules() -> Vec<(RuleConditions, RuleActions)> {
+    vec![
+        default_rule("deployment.created", None, false),
+        default_rule("deployment_status.created", None, false),
+        default_rule("check_suite.completed", None, false),
+        default_rule("milestone.created", None, false),
+        default_rule("star.created", None, true),
+        default_rule("issue_comment.created", None, false),
+        default_rule("pull_request_review_comment.created", None, false),
+        review_default_rule("approved"),
+        review_default_rule("changes_requested"),
+        default_rule("discussion.created", None, false),
+        default_rule("discussion.answered", None, false),
+    ]
+}
+
+/// All rules configured for a project, highest priority first
+pub async fn list_rules(db: &dyn Storage, project_id: &str) -> Result<Vec<Rule>> {
+    db.list_rules(project_id).await
+}
+
+/// Load a project's rules once and evaluate them against an event
+pub async fn evaluate_rules(
+    db: &dyn Storage,
+    project: &Project,
+    event: &ParsedEvent,
+) -> Result<RuleEvaluation> {
+    let project_rules = list_rules(db, &project.id).await?;
+    let ctx = RuleContext::for_event(db, &project.guild_id, event).await?;
+    Ok(RuleEvaluator::new(project_rules).evaluate(&ctx))
+}
+
+/// Validate and store a new rule for a project
+pub async fn create_rule(
+    db: &dyn Storage,
+    project_id: &str,
+    priority: i64,
+    conditions: &RuleConditions,
+    actions: &RuleActions,
+) -> Result<String> {
+    validate_rule(priority, conditions, actions)?;
+    db.create_rule(project_id, priority, conditions, actions)
+        .await
+}
+
+pub async fn delete_rule(db: &dyn Storage, rule_id: &str) -> Result<()> {
+    db.delete_rule(rule_id).await
+}
 
-    Ok(Some(RuleMatch { actions, rule_id }))
+pub async fn set_priority(db: &dyn Storage, rule_id: &str, priority: i64) -> Result<()> {
+    check_priority(priority)?;
+    db.update_rule_priority(rule_id, priority).await
 }
@@ -1,9 +1,7 @@
-use convex::Value as ConvexValue;
-use maplit::btreemap;
 use serde::{Deserialize, Serialize};
 
-use crate::error::{Error, Result};
-use crate::storage::convex::ConvexDb;
+use crate::error::Result;
+use crate::storage::Storage;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
@@ -13,45 +11,35 @@ pub struct ServerConfig {
     /// not a forum ID. The category contains project-specific forum channels.
     /// Named for historical reasons; renaming would require a database migration.
     pub github_forum_id: String,
+    /// Private moderator-only channel for security alerts. Missing for
+    /// servers set up before security alerts existed until `/setup-server`
+    /// or `/repair` runs again.
+    #[serde(default)]
+    pub moderator_channel_id: Option<String>,
 }
 
 /// Get server config by guild ID
-pub async fn get_config(db: &ConvexDb, guild_id: &str) -> Result<Option<ServerConfig>> {
-    let result = db
-        .query(
-            "serverConfig:get",
-            btreemap! {
-                "guild_id".into() => ConvexValue::String(guild_id.to_string()),
-            },
-        )
-        .await?;
-
-    if result.is_null() {
-        return Ok(None);
-    }
-
-    let config: ServerConfig = serde_json::from_value(result)
-        .map_err(|e| Error::InvalidPayload(format!("Failed to parse config: {}", e)))?;
-
-    Ok(Some(config))
+pub async fn get_config(db: &dyn Storage, guild_id: &str) -> Result<Option<ServerConfig>> {
+    db.get_server_config(guild_id).await
 }
 
 /// Save server config
 pub async fn save_config(
-    db: &ConvexDb,
+    db: &dyn Storage,
     guild_id: &str,
     announcements_id: &str,
     github_forum_id: &str,
 ) -> Result<()> {
-    db.mutation(
-        "serverConfig:save",
-        btreemap! {
-            "guild_id".into() => ConvexValue::String(guild_id.to_string()),
-            "announcements_id".into() => ConvexValue::String(announcements_id.to_string()),
-            "github_forum_id".into() => ConvexValue::String(github_forum_id.to_string()),
-        },
-    )
-    .await?;
+    db.save_server_config(guild_id, announcements_id, github_forum_id)
+        .await
+}
 
-    Ok(())
+/// Record the private moderator channel of a server.
+/// Returns `NotFound` if the server is not set up.
+pub async fn set_moderator_channel(
+    db: &dyn Storage,
+    guild_id: &str,
+    channel_id: &str,
+) -> Result<()> {
+    db.update_moderator_channel_id(guild_id, channel_id).await
 }
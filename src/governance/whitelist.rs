@@ -1,18 +1,51 @@
-// Whitelist module - future work
-// The schema has the whitelist table defined but functionality is not yet implemented
+use crate::error::{Error, Result};
+use crate::storage::Storage;
 
-use crate::error::Result;
-use crate::storage::convex::ConvexDb;
+/// GitHub usernames are 1-39 alphanumerics or single hyphens, not at either end.
+/// Bot accounts carry a `[bot]` suffix (e.g. `dependabot[bot]`).
+fn validate_username(github_username: &str) -> Result<()> {
+    let name = github_username
+        .strip_suffix("[bot]")
+        .unwrap_or(github_username);
+    let valid = !name.is_empty()
+        && name.len() <= 39
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--");
 
-#[allow(dead_code)]
-pub async fn add_user(_db: &ConvexDb, _github_username: &str) -> Result<()> {
-    // TODO: Implement when whitelist feature is needed
+    if !valid {
+        return Err(Error::InvalidPayload(format!(
+            "`{}` is not a valid GitHub username",
+            github_username
+        )));
+    }
     Ok(())
 }
 
-#[allow(dead_code)]
-pub async fn is_whitelisted(_db: &ConvexDb, _github_username: &str) -> Result<bool> {
-    // TODO: Implement when whitelist feature is needed
-    // For now, return false (no one is whitelisted)
-    Ok(false)
+/// Add a GitHub user to a guild's whitelist (usernames are case-insensitive)
+pub async fn add_user(db: &dyn Storage, guild_id: &str, github_username: &str) -> Result<()> {
+    let github_username = github_username.trim();
+    validate_username(github_username)?;
+    db.add_whitelisted_user(guild_id, github_username).await
+}
+
+/// Remove a GitHub user from a guild's whitelist
+pub async fn remove_user(db: &dyn Storage, guild_id: &str, github_username: &str) -> Result<()> {
+    db.remove_whitelisted_user(guild_id, github_username.trim())
+        .await
+}
+
+/// All whitelisted users of a guild, sorted alphabetically
+pub async fn list_users(db: &dyn Storage, guild_id: &str) -> Result<Vec<String>> {
+    db.list_whitelisted_users(guild_id).await
+}
+
+/// Whether a GitHub user is whitelisted in a guild
+pub async fn is_whitelisted(
+    db: &dyn Storage,
+    guild_id: &str,
+    github_username: &str,
+) -> Result<bool> {
+    db.is_whitelisted(guild_id, github_username).await
 }
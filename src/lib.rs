@@ -9,8 +9,14 @@ pub mod storage;
 use crate::config::Config;
 use crate::discord::client::DiscordInterface;
 use crate::discord::commands::handle_interaction;
+use crate::github::client::GitHubInterface;
 use crate::github::webhook::handle_webhook;
-use crate::storage::convex::ConvexDb;
+use crate::router::admin::{
+    handle_backfill, handle_get_failed, handle_list_failed, handle_replay_failed,
+};
+use crate::router::dry_run::handle_dry_run;
+use crate::router::queue::DispatchQueue;
+use crate::storage::Storage;
 use axum::{
     routing::{get, post},
     Json, Router,
@@ -22,15 +28,19 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[derive(Clone)]
 pub struct AppState {
     pub config: Config,
-    pub db: ConvexDb,
+    pub db: Arc<dyn Storage>,
     pub discord: Arc<dyn DiscordInterface>,
+    /// Set when `GITHUB_TOKEN` is configured
+    pub github: Option<Arc<dyn GitHubInterface>>,
+    pub queue: DispatchQueue,
 }
 
 pub async fn health() -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "status": "ok",
         "service": "bytehub",
-        "version": VERSION
+        "version": VERSION,
+        "duplicate_deliveries_dropped": github::deliveries::duplicates_dropped()
     }))
 }
 
@@ -46,8 +56,11 @@ pub async fn debug_env(State(state): State<AppState>) -> Json<serde_json::Value>
         "discord_public_key_prefix": &state.config.discord_public_key[..8.min(state.config.discord_public_key.len())],
         "discord_application_id": state.config.discord_application_id,
         "discord_bot_token_len": state.config.discord_bot_token.len(),
+        "storage_backend": state.config.storage_backend.name(),
         "convex_url_set": !state.config.convex_url.is_empty(),
         "github_webhook_secret_set": !state.config.github_webhook_secret.is_empty(),
+        "admin_token_set": state.config.admin_token.is_some(),
+        "github_token_set": state.config.github_token.is_some(),
     }))
 }
 
@@ -58,5 +71,13 @@ pub fn create_app(state: AppState) -> Router {
         .route("/debug", get(debug_env))
         .route("/webhooks/github", post(handle_webhook))
         .route("/webhooks/discord", post(handle_interaction))
+        .route("/admin/dry-run", post(handle_dry_run))
+        .route("/admin/deliveries/failed", get(handle_list_failed))
+        .route("/admin/deliveries/failed/{id}", get(handle_get_failed))
+        .route(
+            "/admin/deliveries/failed/{id}/replay",
+            post(handle_replay_failed),
+        )
+        .route("/admin/backfill", post(handle_backfill))
         .with_state(state)
 }
@@ -1,6 +1,9 @@
-use bytehub::config::Config;
+use bytehub::config::{Config, StorageBackend};
 use bytehub::discord::client::DiscordClient;
-use bytehub::storage::convex::ConvexDb;
+use bytehub::github::client::{GitHubClient, GitHubInterface};
+use bytehub::router::queue::DispatchQueue;
+use bytehub::router::retries;
+use bytehub::storage;
 use bytehub::{create_app, AppState};
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -8,7 +11,7 @@ use tracing_subscriber::EnvFilter;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-fn print_banner(addr: &SocketAddr) {
+fn print_banner(addr: &SocketAddr, database: &str) {
     let display_host = if addr.ip().is_unspecified() {
         "localhost"
     } else {
@@ -26,13 +29,22 @@ fn print_banner(addr: &SocketAddr) {
         addr.port()
     );
     println!("  \x1b[32m→\x1b[0m Version: \x1b[33m{}\x1b[0m", VERSION);
-    println!("  \x1b[32m→\x1b[0m Database: \x1b[34mConvex\x1b[0m");
+    println!("  \x1b[32m→\x1b[0m Database: \x1b[34m{}\x1b[0m", database);
     println!();
     println!("  \x1b[90mEndpoints:\x1b[0m");
     println!("    \x1b[32mGET \x1b[0m /                  \x1b[90m← Health check\x1b[0m");
     println!("    \x1b[32mGET \x1b[0m /health             \x1b[90m← JSON status\x1b[0m");
     println!("    \x1b[34mPOST\x1b[0m /webhooks/github   \x1b[90m← GitHub events\x1b[0m");
     println!("    \x1b[34mPOST\x1b[0m /webhooks/discord  \x1b[90m← Discord interactions\x1b[0m");
+    println!(
+        "    \x1b[34mPOST\x1b[0m /admin/dry-run      \x1b[90m← Rule dry-run (ADMIN_TOKEN)\x1b[0m"
+    );
+    println!(
+        "    \x1b[32mGET \x1b[0m /admin/deliveries/failed \x1b[90m← Failed deliveries (ADMIN_TOKEN)\x1b[0m"
+    );
+    println!(
+        "    \x1b[34mPOST\x1b[0m /admin/backfill    \x1b[90m← Replay missed deliveries (GITHUB_TOKEN)\x1b[0m"
+    );
     println!();
     println!("  \x1b[90mPress Ctrl+C to stop\x1b[0m");
     println!();
@@ -49,23 +61,52 @@ async fn main() -> anyhow::Result<()> {
         .with_env_filter(EnvFilter::from_default_env())
         .init();
 
-    let config = Config::from_env();
-    let db = ConvexDb::new(&config.convex_url).await?;
-    let discord = DiscordClient::new(&config.discord_bot_token, config.discord_application_id);
+    // `--dev` runs against volatile in-memory storage, no database required
+    let config = if std::env::args().any(|arg| arg == "--dev") {
+        Config::from_env_with_backend(StorageBackend::Memory)
+    } else {
+        Config::from_env()
+    };
+    let db = storage::connect(&config).await?;
+    let discord = Arc::new(DiscordClient::new(
+        &config.discord_bot_token,
+        config.discord_application_id,
+    ));
+    let queue = DispatchQueue::start(
+        db.clone(),
+        discord.clone(),
+        config.dispatch_workers,
+        config.dispatch_queue_size,
+    );
+
+    retries::spawn_retry_loop(db.clone(), queue.clone());
+    let github = match &config.github_token {
+        Some(token) => Some(Arc::new(GitHubClient::new(token)?) as Arc<dyn GitHubInterface>),
+        None => None,
+    };
 
     let state = AppState {
         config: config.clone(),
         db,
-        discord: Arc::new(discord),
+        discord,
+        github,
+        queue: queue.clone(),
     };
 
     let app = create_app(state);
     let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
 
-    print_banner(&addr);
+    print_banner(&addr, config.storage_backend.name());
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    // Deliveries were already acknowledged, so finish them before exiting
+    queue.flush().await;
 
     Ok(())
 }
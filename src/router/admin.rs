@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use tracing::warn;
+
+use crate::error::{Error, Result};
+use crate::github::backfill::{self, BackfillReport, BackfillRequest};
+use crate::router::retries::{self, FailedDelivery};
+use crate::AppState;
+
+/// Check `Authorization: Bearer <ADMIN_TOKEN>`. The admin endpoints answer
+/// `404` unless `ADMIN_TOKEN` is set.
+pub fn authorize(state: &AppState, headers: &HeaderMap) -> Result<()> {
+    let admin_token = state
+        .config
+        .admin_token
+        .as_deref()
+        .ok_or_else(|| Error::NotFound("admin endpoints are disabled".into()))?;
+
+    let provided = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(Error::Unauthorized)?;
+
+    if !constant_time_eq(provided.as_bytes(), admin_token.as_bytes()) {
+        warn!("invalid admin token");
+        return Err(Error::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// `GET /admin/deliveries/failed` - every failed delivery, oldest first,
+/// including dead letters and their raw payloads
+pub async fn handle_list_failed(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FailedDelivery>>> {
+    authorize(&state, &headers)?;
+    Ok(Json(retries::list_failed(state.db.as_ref(), None).await?))
+}
+
+/// `GET /admin/deliveries/failed/{id}` - a single failed delivery
+pub async fn handle_get_failed(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<FailedDelivery>> {
+    authorize(&state, &headers)?;
+    state
+        .db
+        .get_failed_delivery(&id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| Error::NotFound(format!("failed delivery {}", id)))
+}
+
+/// `POST /admin/deliveries/failed/{id}/replay` - queue a failed delivery
+/// again right away, dead-lettered or not. Answers `202`; the delivery is
+/// removed once it goes through.
+pub async fn handle_replay_failed(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<FailedDelivery>)> {
+    authorize(&state, &headers)?;
+    let failed = retries::replay(state.db.as_ref(), &state.queue, &id).await?;
+    Ok((StatusCode::ACCEPTED, Json(failed)))
+}
+
+/// `POST /admin/backfill` - fetch a repository's webhook deliveries in a
+/// time window from GitHub and queue those ByteHub has not processed.
+/// Needs `GITHUB_TOKEN`.
+pub async fn handle_backfill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<BackfillRequest>,
+) -> Result<Json<BackfillReport>> {
+    authorize(&state, &headers)?;
+    let github = state
+        .github
+        .as_deref()
+        .ok_or_else(|| Error::NotFound("backfill is disabled, set GITHUB_TOKEN".into()))?;
+
+    let report = backfill::backfill(state.db.as_ref(), github, &state.queue, &request).await?;
+    Ok(Json(report))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
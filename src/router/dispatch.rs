@@ -1,77 +1,423 @@
-use tracing::info;
+use tracing::{info, warn};
 use twilight_model::id::Id;
 
 use crate::discord::client::DiscordInterface;
 use crate::discord::formatters::{
-    COLOR_BOUNTY, COLOR_FAILURE, COLOR_ISSUE, COLOR_PR, COLOR_SUCCESS,
+    checks_url, deployment_style, format_comment_excerpt, format_milestone_progress, format_push,
+    review_style, severity_color, COLOR_BOUNTY, COLOR_COMMENT, COLOR_DISCUSSION, COLOR_FAILURE,
+    COLOR_ISSUE, COLOR_MILESTONE, COLOR_PR, COLOR_PUSH, COLOR_REF, COLOR_SKIPPED, COLOR_STAR,
+    COLOR_SUCCESS,
 };
-use crate::error::Result;
-use crate::github::events::ParsedEvent;
-use crate::governance::projects;
-use crate::storage::convex::ConvexDb;
+use crate::discord::templates;
+use crate::error::{Error, Result};
+use crate::github::events::{milestone_key, star_milestone_key, Label, Milestone, ParsedEvent};
+use crate::governance::rules::{self, RuleEvaluation, RuleMatch};
+use crate::governance::{milestones, projects, server_config};
+use crate::router::messages;
+use crate::storage::Storage;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Where an event should be posted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Routing {
+    /// The pinned "📦 <project> Activity" thread
+    pub activity: bool,
+    /// A dedicated sidebar thread in the project forum (e.g. "🧩 PR Merged")
+    pub sidebar: bool,
+    /// The server's announcements channel
+    pub announce: bool,
+    /// The server's private moderator channel (security alerts only)
+    pub moderators: bool,
+}
+
+/// A Discord destination for a planned message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Destination {
+    /// The pinned project activity thread
+    Activity { thread_name: String },
+    /// A sidebar thread in the project forum, created on first use
+    Sidebar { thread_name: String },
+    /// The sidebar thread an issue or pull request was posted to; skipped
+    /// when that thread does not exist
+    ItemThread { thread_name: String },
+    /// The server's announcements channel
+    Announcements,
+    /// The server's private moderator channel
+    Moderators,
+}
+
+impl Destination {
+    /// Human-readable name, e.g. "Sidebar thread `🧩 PR Merged`"
+    pub fn label(&self) -> String {
+        match self {
+            Destination::Activity { thread_name } => format!("Activity thread `{}`", thread_name),
+            Destination::Sidebar { thread_name } => format!("Sidebar thread `{}`", thread_name),
+            Destination::ItemThread { thread_name } => format!("Item thread `{}`", thread_name),
+            Destination::Announcements => "Announcements".to_string(),
+            Destination::Moderators => "Moderator channel".to_string(),
+        }
+    }
+}
+
+/// An embed the dispatcher would send
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedMessage {
+    pub destination: Destination,
+    pub title: String,
+    pub description: String,
+    pub color: u32,
+    pub footer: Option<String>,
+    /// Set when the message replaces the one posted earlier for the same
+    /// GitHub item (see `ParsedEvent::item_key`). Announcements are recorded
+    /// under it instead, so they are not posted twice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_key: Option<String>,
+    /// Only edits the message posted earlier for `edit_key`; nothing is
+    /// posted when there is none
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub edit_only: bool,
+}
+
+/// Everything the dispatcher decided for one event, before any Discord call
+#[derive(Debug, Clone, Serialize)]
+pub struct DispatchPlan {
+    pub repo: String,
+    pub event_key: Option<String>,
+    /// Number of project rules; zero means built-in routing applied
+    pub rule_count: usize,
+    pub matches: Vec<RuleMatch>,
+    pub routing: Routing,
+    pub messages: Vec<PlannedMessage>,
+}
+
 pub struct Dispatcher {
-    db: ConvexDb,
+    db: Arc<dyn Storage>,
     discord: Arc<dyn DiscordInterface>,
 }
 
 impl Dispatcher {
-    pub fn new(db: ConvexDb, discord: Arc<dyn DiscordInterface>) -> Self {
+    pub fn new(db: Arc<dyn Storage>, discord: Arc<dyn DiscordInterface>) -> Self {
         Self { db, discord }
     }
 
     pub async fn dispatch(&self, event: ParsedEvent) -> Result<()> {
+        let failures = self.deliver(event, None).await?;
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(Error::Discord(describe_failures(&failures)))
+    }
+
+    /// Dispatch an event and report the destinations that could not be
+    /// posted to, so a retry can skip the ones that worked. `only` limits the
+    /// event to some destinations of its plan, e.g. those of a failed attempt.
+    ///
+    /// Errors before anything is posted (storage, forum setup) are returned
+    /// as `Err`; the whole event should then be retried.
+    pub async fn deliver(
+        &self,
+        event: ParsedEvent,
+        only: Option<&[Destination]>,
+    ) -> Result<Vec<(Destination, Error)>> {
+        // Follow renames and transfers first, so the project is found under its new name
+        if let ParsedEvent::Repository(e) = &event {
+            if let Some(previous) = e.previous_full_name() {
+                self.sync_renamed_project(&previous, &e.repository.full_name)
+                    .await?;
+            }
+        }
+
         let repo = match event.repo_full_name() {
             Some(r) => r.to_lowercase(),
-            None => return Ok(()),
+            None => return Ok(vec![]),
         };
 
-        let project = match projects::get_approved_project(&self.db, &repo).await? {
+        let project = match projects::get_approved_project(self.db.as_ref(), &repo).await? {
             Some(p) => p,
             None => {
                 info!(repo, "event from unlisted/unapproved project, ignoring");
-                return Ok(());
+                return Ok(vec![]);
             }
         };
 
+        if let Err(e) = self.track_milestone(&project, &repo, &event).await {
+            warn!(repo, error = %e, "failed to update milestone");
+        }
+
+        let mut plan = self.plan_for_project(&project, &event).await?;
+        if let Some(only) = only {
+            plan.messages.retain(|m| only.contains(&m.destination));
+        }
+        if plan.messages.is_empty() {
+            info!(repo, "no destination for event, ignoring");
+            return Ok(vec![]);
+        }
+
         // Ensure forum exists and is synced
         let guild_id = Id::new(project.guild_id.parse::<u64>().unwrap_or(0));
         let forum_id = self.ensure_forum_exists(&project, &repo, guild_id).await?;
 
-        // 1. Log to the persistent "Project Activity" thread
-        if !self.is_bot_actor(event.actor().unwrap_or("")) && self.should_log(&event) {
-            let activity_tid_str = self
-                .get_or_create_thread(&project, &repo, forum_id, guild_id)
-                .await?;
-            let activity_tid = Id::new(activity_tid_str.parse::<u64>().unwrap_or(0));
-            if let Err(e) = self.post_event_to_thread(activity_tid, &event).await {
-                info!(repo, error = %e, "failed to post to activity thread");
-            } else {
-                info!(repo, "logged event to project activity thread");
+        let mut failures = Vec::new();
+        for message in &plan.messages {
+            let result = match &message.destination {
+                // 1. Log to the persistent "Project Activity" thread
+                Destination::Activity { .. } => {
+                    match self
+                        .get_or_create_thread(&project, &repo, forum_id, guild_id)
+                        .await
+                    {
+                        Ok(tid) => {
+                            let activity_tid = Id::new(tid.parse::<u64>().unwrap_or(0));
+                            self.post_event_to_thread(activity_tid, message).await
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                // 2. Manage dedicated Sidebar threads for major milestones
+                Destination::Sidebar { thread_name } => {
+                    self.manage_sidebar_thread(guild_id, forum_id, thread_name, message)
+                        .await
+                }
+                Destination::ItemThread { thread_name } => {
+                    match self
+                        .discord
+                        .find_active_thread_by_name(guild_id, forum_id, thread_name)
+                        .await
+                    {
+                        Ok(Some(tid)) => self.post_event_to_thread(tid, message).await,
+                        Ok(None) => {
+                            info!(repo, thread_name, "no thread for item, skipping");
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                // 3. Post to announcements if applicable
+                Destination::Announcements => self.post_to_announcements(&project, message).await,
+                // 4. Security alerts stay with the moderators
+                Destination::Moderators => self.post_to_moderators(&project, message).await,
+            };
+
+            match result {
+                Ok(()) => info!(repo, destination = ?message.destination, "posted event"),
+                Err(e) => {
+                    warn!(repo, destination = ?message.destination, error = %e, "failed to post event");
+                    failures.push((message.destination.clone(), e));
+                }
             }
         }
 
-        // 2. Manage dedicated Sidebar threads for major milestones
-        if self.should_post(&event) {
-            if let Err(e) = self.manage_sidebar_thread(guild_id, forum_id, &event).await {
-                info!(repo, error = %e, "failed to manage sidebar thread");
-            } else {
-                info!(repo, "handled sidebar thread for event");
-            }
+        Ok(failures)
+    }
+
+    /// Work out what an event would produce without touching Discord.
+    ///
+    /// Returns `None` for events of unknown types or from projects that are
+    /// not approved, which `dispatch` ignores.
+    pub async fn plan(&self, event: &ParsedEvent) -> Result<Option<DispatchPlan>> {
+        let repo = match event.repo_full_name() {
+            Some(r) => r.to_lowercase(),
+            None => return Ok(None),
+        };
+
+        match projects::get_approved_project(self.db.as_ref(), &repo).await? {
+            Some(project) => Ok(Some(self.plan_for_project(&project, event).await?)),
+            None => Ok(None),
         }
+    }
+
+    async fn plan_for_project(
+        &self,
+        project: &projects::Project,
+        event: &ParsedEvent,
+    ) -> Result<DispatchPlan> {
+        let repo = project.github_repo.as_str();
+
+        // Per-project rules decide where the event goes
+        let evaluation = rules::evaluate_rules(self.db.as_ref(), project, event).await?;
+        for m in &evaluation.matches {
+            info!(repo, rule_id = %m.rule_id, priority = m.priority, reason = %m.reason, "event matched rule");
+        }
+        let routing = self.routing(event, &evaluation);
+
+        // The highest-priority matching rule with a template customises the messages
+        let template = evaluation
+            .matches
+            .iter()
+            .find_map(|m| m.actions.template.as_deref());
 
-        // 3. Post to announcements if applicable
-        if self.should_announce(&event) {
-            if let Err(e) = self.post_to_announcements(&event, &project).await {
-                info!(repo, error = %e, "failed to post announcement");
+        let mut messages = Vec::new();
+        if routing.activity {
+            if let Some((title, description, color, footer)) = activity_embed(event) {
+                let (title, description) = apply_template(template, event, title, description);
+                messages.push(PlannedMessage {
+                    destination: Destination::Activity {
+                        thread_name: activity_thread_name(repo),
+                    },
+                    title,
+                    description,
+                    color,
+                    footer,
+                    edit_key: None,
+                    edit_only: false,
+                });
+            }
+        }
+        // Changes to an item that was posted before update its message, unless
+        // a matching rule keeps the event out of the forum
+        let forum_allowed = evaluation.matches.is_empty()
+            || evaluation.matches.iter().any(|m| m.actions.post_forum);
+        let edit_only = !routing.sidebar
+            && forum_allowed
+            && self.updates_item(event, evaluation.actor_whitelisted);
+        if routing.sidebar || edit_only {
+            if let Some((thread_name, title, description, color, footer)) = sidebar_embed(event) {
+                let (title, description) = apply_template(template, event, title, description);
+                let thread_name = thread_name.to_string();
+                // Comments follow their issue or pull request instead of opening threads
+                let destination = if event.targets_item_thread() {
+                    Destination::ItemThread { thread_name }
+                } else {
+                    Destination::Sidebar { thread_name }
+                };
+                messages.push(PlannedMessage {
+                    destination,
+                    title,
+                    description,
+                    color,
+                    footer,
+                    edit_key: event.item_key(),
+                    edit_only,
+                });
+            }
+        }
+        // Star milestones are announced once, even when the count is reached again
+        let announce_key = match event {
+            ParsedEvent::Star(e) => e.milestone().map(|count| star_milestone_key(repo, count)),
+            _ => None,
+        };
+        let announced = match &announce_key {
+            Some(key) => messages::get_posted_message(self.db.as_ref(), key)
+                .await?
+                .is_some(),
+            None => false,
+        };
+        if routing.announce && !announced && !project.guild_id.is_empty() {
+            if let Some((title, description, color)) = announcement_embed(event) {
+                let (title, description) = apply_template(template, event, title, description);
+                messages.push(PlannedMessage {
+                    destination: Destination::Announcements,
+                    title,
+                    description,
+                    color,
+                    footer: Some(format!("Project: {}", project.name)),
+                    edit_key: announce_key,
+                    edit_only: false,
+                });
+            }
+        }
+        if routing.moderators && !project.guild_id.is_empty() {
+            if let Some((title, description, color)) = moderator_embed(event) {
+                let (title, description) = apply_template(template, event, title, description);
+                messages.push(PlannedMessage {
+                    destination: Destination::Moderators,
+                    title,
+                    description,
+                    color,
+                    footer: Some(format!("Project: {}", project.name)),
+                    edit_key: None,
+                    edit_only: false,
+                });
             }
         }
 
-        Ok(())
+        Ok(DispatchPlan {
+            repo: repo.to_string(),
+            event_key: event.event_key(),
+            rule_count: evaluation.rule_count,
+            matches: evaluation.matches,
+            routing,
+            messages,
+        })
+    }
+
+    /// Decide the destinations of an event.
+    ///
+    /// Projects without rules keep the built-in behaviour (`should_log`,
+    /// `should_post`, `should_announce`). Otherwise the actions of every
+    /// matching rule are combined; events no rule matches are only logged to
+    /// the activity thread. Bot filtering and the built-in eligibility checks
+    /// (e.g. CI runs on the default branch only) still apply either way.
+    ///
+    /// Whitelisted actors bypass bot filtering, and their events that are
+    /// posted to the sidebar are always announced. Pushes are only logged for
+    /// the default branch unless a matching rule selects them (see
+    /// `RuleConditions::branches`).
+    ///
+    /// Security alerts bypass all of the above: they only ever go to the
+    /// private moderator channel, whatever the rules say. Likewise a webhook
+    /// ping always confirms the connection in the activity thread.
+    pub fn routing(&self, event: &ParsedEvent, evaluation: &RuleEvaluation) -> Routing {
+        if event.is_security_alert() {
+            return Routing {
+                moderators: true,
+                ..Default::default()
+            };
+        }
+        if matches!(event, ParsedEvent::Ping(_)) {
+            return Routing {
+                activity: true,
+                ..Default::default()
+            };
+        }
+
+        let trusted = evaluation.actor_whitelisted;
+        let is_bot = !trusted && self.is_bot_actor(event.actor().unwrap_or(""));
+        let loggable = !is_bot && self.should_log(event);
+        let postable = self.is_postable(event, !is_bot);
+
+        if !evaluation.has_rules() {
+            return Routing {
+                activity: loggable,
+                sidebar: postable,
+                announce: self.should_announce(event)
+                    || (trusted && postable && self.can_announce(event)),
+                moderators: false,
+            };
+        }
+
+        if evaluation.matches.is_empty() {
+            return Routing {
+                activity: loggable,
+                sidebar: false,
+                announce: false,
+                moderators: false,
+            };
+        }
+
+        let actions = evaluation.matches.iter().map(|m| &m.actions);
+        let sidebar = postable && actions.clone().any(|a| a.post_forum);
+        Routing {
+            activity: !is_bot
+                && self.is_loggable(event, true)
+                && actions.clone().any(|a| a.post_activity),
+            sidebar,
+            announce: self.can_announce(event)
+                && (actions.clone().any(|a| a.post_announce) || (trusted && sidebar)),
+            moderators: false,
+        }
     }
 
     pub fn should_log(&self, event: &ParsedEvent) -> bool {
+        self.is_loggable(event, false)
+    }
+
+    /// Activity eligibility; `rule_selected` lifts the default filters on
+    /// pushes (branch), reviews (state) and branch/tag refs when a rule asked
+    /// for the event. Stars are only ever logged at milestones.
+    fn is_loggable(&self, event: &ParsedEvent, rule_selected: bool) -> bool {
         match event {
             ParsedEvent::WorkflowRun(e) => {
                 if e.action != "completed" {
@@ -80,14 +426,75 @@ impl Dispatcher {
                 let conclusion = e.workflow_run.conclusion.as_deref().unwrap_or("unknown");
                 conclusion == "success" || conclusion == "failure"
             }
+            // Checks from GitHub Actions would repeat its workflow runs
+            ParsedEvent::CheckSuite(e) => {
+                !event.is_actions_check()
+                    && e.action == "completed"
+                    && is_ci_outcome(e.check_suite.conclusion.as_deref())
+            }
+            // The suite sums up its runs, so single runs need a rule
+            ParsedEvent::CheckRun(e) => {
+                rule_selected
+                    && !event.is_actions_check()
+                    && e.action == "completed"
+                    && is_ci_outcome(e.check_run.conclusion.as_deref())
+            }
             ParsedEvent::PullRequest(_) => true,
             ParsedEvent::Issue(_) => true,
             ParsedEvent::Release(_) => true,
+            // Branch deletions and pushes without new commits have nothing to show
+            ParsedEvent::Push(e) => {
+                let branch = e.branch().unwrap_or("");
+                !e.deleted
+                    && !e.commits.is_empty()
+                    && (rule_selected || branch == "main" || branch == "master")
+            }
+            // Edits and deletions would repeat or orphan earlier messages
+            ParsedEvent::IssueComment(e) => e.action == "created",
+            ParsedEvent::PullRequestReviewComment(e) => e.action == "created",
+            // Plain "commented" reviews duplicate their review comments
+            ParsedEvent::PullRequestReview(e) => {
+                e.action == "submitted"
+                    && (rule_selected
+                        || e.review.state == "approved"
+                        || e.review.state == "changes_requested")
+            }
+            ParsedEvent::Discussion(e) => e.action == "created" || e.action == "answered",
+            ParsedEvent::DiscussionComment(e) => e.action == "created",
+            ParsedEvent::SecurityAdvisory(_)
+            | ParsedEvent::DependabotAlert(_)
+            | ParsedEvent::CodeScanningAlert(_)
+            | ParsedEvent::SecretScanningAlert(_) => false,
+            ParsedEvent::Star(e) => e.milestone().is_some(),
+            ParsedEvent::Fork(_) => true,
+            // New tags are worth logging; branches come and go with every PR
+            ParsedEvent::Create(e) => rule_selected || e.is_tag(),
+            ParsedEvent::Delete(_) => rule_selected,
+            ParsedEvent::Ping(_) => true,
+            ParsedEvent::Repository(e) => matches!(
+                e.action.as_str(),
+                "renamed" | "transferred" | "archived" | "unarchived" | "deleted"
+            ),
+            // Only the outcome; the Deployments thread shows the progress
+            ParsedEvent::Deployment(_) => false,
+            ParsedEvent::DeploymentStatus(e) => matches!(
+                e.deployment_status.state.as_str(),
+                "success" | "failure" | "error"
+            ),
+            ParsedEvent::Milestone(e) => {
+                matches!(e.action.as_str(), "created" | "closed")
+                    || (e.action == "edited" && e.due_date_changed())
+            }
             ParsedEvent::Unknown => false,
         }
     }
 
     pub fn should_post(&self, event: &ParsedEvent) -> bool {
+        self.is_postable(event, false)
+    }
+
+    /// Sidebar eligibility; `allow_bots` skips the bot filter for pull requests and comments
+    fn is_postable(&self, event: &ParsedEvent, allow_bots: bool) -> bool {
         match event {
             ParsedEvent::WorkflowRun(e) => {
                 if !self.should_log(event) {
@@ -96,8 +503,13 @@ impl Dispatcher {
                 let branch = e.workflow_run.head_branch.as_deref().unwrap_or("");
                 branch == "main" || branch == "master"
             }
+            ParsedEvent::CheckSuite(_) => {
+                let branch = event.branch().unwrap_or("");
+                self.should_log(event) && (branch == "main" || branch == "master")
+            }
+            ParsedEvent::CheckRun(_) => false,
             ParsedEvent::PullRequest(e) => {
-                if self.is_bot_actor(e.sender.login.as_str()) {
+                if !allow_bots && self.is_bot_actor(e.sender.login.as_str()) {
                     return false;
                 }
                 e.action == "opened"
@@ -106,15 +518,64 @@ impl Dispatcher {
             }
             ParsedEvent::Issue(e) => e.action == "opened" || e.action == "labeled",
             ParsedEvent::Release(e) => e.action == "published",
+            ParsedEvent::Push(_) => false,
+            ParsedEvent::IssueComment(_)
+            | ParsedEvent::PullRequestReviewComment(_)
+            | ParsedEvent::PullRequestReview(_) => {
+                let actor = event.actor().unwrap_or("");
+                (allow_bots || !self.is_bot_actor(actor)) && self.should_log(event)
+            }
+            // New discussions and accepted answers get the "💬 Discussions" thread
+            ParsedEvent::Discussion(_) => self.should_log(event),
+            ParsedEvent::DiscussionComment(_) => false,
+            ParsedEvent::SecurityAdvisory(_)
+            | ParsedEvent::DependabotAlert(_)
+            | ParsedEvent::CodeScanningAlert(_)
+            | ParsedEvent::SecretScanningAlert(_) => false,
+            ParsedEvent::Star(_)
+            | ParsedEvent::Fork(_)
+            | ParsedEvent::Create(_)
+            | ParsedEvent::Delete(_) => false,
+            ParsedEvent::Ping(_) | ParsedEvent::Repository(_) => false,
+            // Deployments are usually made by CI bots, so they are not filtered
+            ParsedEvent::Deployment(_) | ParsedEvent::DeploymentStatus(_) => true,
+            // Later changes edit the milestone's message, see `track_milestone`
+            ParsedEvent::Milestone(e) => e.action == "created",
             ParsedEvent::Unknown => false,
         }
     }
 
+    /// Changes to an issue, pull request or workflow run that are not posted
+    /// on their own but edit the item's message, if it has one; `allow_bots`
+    /// skips the bot filter for pull requests, as in `is_postable`
+    fn updates_item(&self, event: &ParsedEvent, allow_bots: bool) -> bool {
+        match event {
+            ParsedEvent::Issue(e) => is_item_update(&e.action),
+            ParsedEvent::PullRequest(e) => {
+                (allow_bots || !self.is_bot_actor(e.sender.login.as_str()))
+                    && is_item_update(&e.action)
+            }
+            // Re-runs keep the run's id, so they update its message
+            ParsedEvent::WorkflowRun(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn should_announce(&self, event: &ParsedEvent) -> bool {
         match event {
             ParsedEvent::Release(_) => true,
             ParsedEvent::Issue(e) => e.issue.labels.iter().any(|l| l.name == "bounty"),
             ParsedEvent::PullRequest(e) => e.pull_request.labels.iter().any(|l| l.name == "bounty"),
+            ParsedEvent::Star(e) => e.milestone().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Whether an announcement can be rendered for this event
+    fn can_announce(&self, event: &ParsedEvent) -> bool {
+        match event {
+            ParsedEvent::Release(_) | ParsedEvent::Issue(_) | ParsedEvent::PullRequest(_) => true,
+            ParsedEvent::Star(e) => e.milestone().is_some(),
             _ => false,
         }
     }
@@ -161,11 +622,110 @@ impl Dispatcher {
             .await?;
 
         // Sync to DB
-        projects::update_forum_id(&self.db, repo, &new_forum_id.get().to_string()).await?;
+        projects::update_forum_id(self.db.as_ref(), repo, &new_forum_id.get().to_string()).await?;
 
         Ok(new_forum_id)
     }
 
+    /// Move a project to its new repo key and rename its forum and activity
+    /// thread to match. Discord failures are logged, the key change is not
+    /// rolled back.
+    async fn sync_renamed_project(&self, previous: &str, current: &str) -> Result<()> {
+        let previous = previous.to_lowercase();
+        let current = current.to_lowercase();
+        if previous == current {
+            return Ok(());
+        }
+
+        let project = match projects::get_project(self.db.as_ref(), &previous).await? {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        projects::rename_project(self.db.as_ref(), &previous, &current).await?;
+        messages::rename_repo(self.db.as_ref(), &previous, &current).await?;
+        info!(previous, current, "project follows repository rename");
+
+        let forum_name = current.rsplit('/').next().unwrap_or(&current);
+        let renames = [
+            (
+                Some(project.forum_channel_id.as_str()),
+                forum_name.to_string(),
+            ),
+            (project.thread_id.as_deref(), activity_thread_name(&current)),
+        ];
+        for (channel_id, name) in renames {
+            let Some(id) = channel_id
+                .and_then(|id| id.parse::<u64>().ok())
+                .and_then(Id::new_checked)
+            else {
+                continue;
+            };
+            if let Err(e) = self.discord.rename_channel(id, &name).await {
+                warn!(repo = current, error = %e, "failed to rename channel");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keep the stored milestone and its forum message in step with the
+    /// payload. Issue events carry the milestone's current issue counts, so
+    /// closing an issue moves the progress bar without a milestone event.
+    async fn track_milestone(
+        &self,
+        project: &projects::Project,
+        repo: &str,
+        event: &ParsedEvent,
+    ) -> Result<()> {
+        let Some(milestone) = event.milestone() else {
+            return Ok(());
+        };
+        let action = match event {
+            ParsedEvent::Milestone(e) => e.action.as_str(),
+            _ => "",
+        };
+        if action == "deleted" {
+            return milestones::delete_milestone(self.db.as_ref(), &project.id, milestone.number)
+                .await;
+        }
+        milestones::save_milestone(self.db.as_ref(), &project.id, milestone).await?;
+
+        // New milestones are posted through the normal routing
+        if action == "created" {
+            return Ok(());
+        }
+        let key = milestone_key(repo, milestone.number);
+        let Some(posted) = messages::get_posted_message(self.db.as_ref(), &key).await? else {
+            return Ok(());
+        };
+        let (Some(channel_id), Some(message_id)) = (
+            posted
+                .channel_id
+                .parse::<u64>()
+                .ok()
+                .and_then(Id::new_checked),
+            posted
+                .message_id
+                .parse::<u64>()
+                .ok()
+                .and_then(Id::new_checked),
+        ) else {
+            return Ok(());
+        };
+
+        let (title, description, color, footer) = milestone_embed(milestone);
+        self.discord
+            .edit_message_embed(
+                channel_id,
+                message_id,
+                &title,
+                &description,
+                color,
+                footer.as_deref(),
+            )
+            .await
+    }
+
     async fn get_or_create_thread(
         &self,
         project: &projects::Project,
@@ -173,8 +733,7 @@ impl Dispatcher {
         forum_id: Id<twilight_model::id::marker::ChannelMarker>,
         guild_id: Id<twilight_model::id::marker::GuildMarker>,
     ) -> Result<String> {
-        let project_name = repo.rsplit('/').next().unwrap_or(repo);
-        let thread_name = format!("📦 {} Activity", project_name);
+        let thread_name = activity_thread_name(repo);
 
         // If thread ID exists in DB, verify it still exists in Discord
         if let Some(ref tid_str) = project.thread_id {
@@ -214,7 +773,7 @@ impl Dispatcher {
         }
 
         let tid_str = tid.get().to_string();
-        projects::update_thread_id(&self.db, repo, &tid_str).await?;
+        projects::update_thread_id(self.db.as_ref(), repo, &tid_str).await?;
 
         Ok(tid_str)
     }
@@ -222,107 +781,17 @@ impl Dispatcher {
     async fn post_event_to_thread(
         &self,
         thread_id: Id<twilight_model::id::marker::ChannelMarker>,
-        event: &ParsedEvent,
+        message: &PlannedMessage,
     ) -> Result<()> {
-        match event {
-            ParsedEvent::WorkflowRun(e) => {
-                let conclusion = e.workflow_run.conclusion.as_deref().unwrap_or("unknown");
-                let color = if conclusion == "success" {
-                    COLOR_SUCCESS
-                } else {
-                    COLOR_FAILURE
-                };
-                let emoji = if conclusion == "success" {
-                    "✅"
-                } else {
-                    "❌"
-                };
-                let name = e.workflow_run.name.as_deref().unwrap_or("CI");
-                let branch = e.workflow_run.head_branch.as_deref().unwrap_or("unknown");
-
-                self.discord
-                    .send_message_with_embed(
-                        thread_id,
-                        &format!("{} {} {}", emoji, name, conclusion),
-                        &format!(
-                            "Branch: `{}`\n[View Run]({})",
-                            branch, e.workflow_run.html_url
-                        ),
-                        color,
-                        None,
-                    )
-                    .await?;
-            }
-            ParsedEvent::PullRequest(e) => {
-                let has_bounty = e.pull_request.labels.iter().any(|l| l.name == "bounty");
-                let color = if has_bounty { COLOR_BOUNTY } else { COLOR_PR };
-                let emoji = if has_bounty { "🪙" } else { "🧩" };
-                let action_text = match e.action.as_str() {
-                    "opened" => "opened",
-                    "labeled" => "labeled",
-                    "closed" if e.pull_request.merged.unwrap_or(false) => "merged",
-                    "closed" => "closed",
-                    _ => &e.action,
-                };
-
-                self.discord
-                    .send_message_with_embed(
-                        thread_id,
-                        &format!("{} PR #{} {}", emoji, e.pull_request.number, action_text),
-                        &format!(
-                            "**{}**\nby @{}\n[View PR]({})",
-                            e.pull_request.title, e.sender.login, e.pull_request.html_url
-                        ),
-                        color,
-                        None,
-                    )
-                    .await?;
-            }
-            ParsedEvent::Issue(e) => {
-                let has_bounty = e.issue.labels.iter().any(|l| l.name == "bounty");
-                let color = if has_bounty {
-                    COLOR_BOUNTY
-                } else {
-                    COLOR_ISSUE
-                };
-                let emoji = if has_bounty { "🪙" } else { "📋" };
-                let action_text = match e.action.as_str() {
-                    "opened" => "opened",
-                    "labeled" => "labeled",
-                    "closed" => "closed",
-                    _ => &e.action,
-                };
-
-                self.discord
-                    .send_message_with_embed(
-                        thread_id,
-                        &format!("{} Issue #{} {}", emoji, e.issue.number, action_text),
-                        &format!(
-                            "**{}**\nby @{}\n[View Issue]({})",
-                            e.issue.title, e.sender.login, e.issue.html_url
-                        ),
-                        color,
-                        None,
-                    )
-                    .await?;
-            }
-            ParsedEvent::Release(e) => {
-                self.discord
-                    .send_message_with_embed(
-                        thread_id,
-                        &format!("🚀 Release {}", e.release.tag_name),
-                        &format!(
-                            "{}\n\n[View Release]({})",
-                            e.release.body.as_deref().unwrap_or(""),
-                            e.release.html_url
-                        ),
-                        COLOR_SUCCESS,
-                        Some(&format!("by @{}", e.sender.login)),
-                    )
-                    .await?;
-            }
-            _ => {}
-        }
+        self.discord
+            .send_message_with_embed(
+                thread_id,
+                &message.title,
+                &message.description,
+                message.color,
+                message.footer.as_deref(),
+            )
+            .await?;
         Ok(())
     }
 
@@ -330,99 +799,18 @@ impl Dispatcher {
         &self,
         guild_id: Id<twilight_model::id::marker::GuildMarker>,
         forum_id: Id<twilight_model::id::marker::ChannelMarker>,
-        event: &ParsedEvent,
+        thread_name: &str,
+        message: &PlannedMessage,
     ) -> Result<()> {
-        let (thread_name, title, description, color, footer_text) = match event {
-            ParsedEvent::WorkflowRun(e) => {
-                let conclusion = e.workflow_run.conclusion.as_deref().unwrap_or("unknown");
-                let color = if conclusion == "success" {
-                    COLOR_SUCCESS
-                } else {
-                    COLOR_FAILURE
-                };
-                let thread_name = if conclusion == "success" {
-                    "✅ CI Passed"
-                } else {
-                    "❌ CI Failed"
-                };
-                let name = e.workflow_run.name.as_deref().unwrap_or("CI");
-                let branch = e.workflow_run.head_branch.as_deref().unwrap_or("unknown");
-
-                (
-                    thread_name,
-                    format!("{} Run Details", name),
-                    format!(
-                        "**{}** - {}\nBranch: `{}`\n[View Run]({})",
-                        name, conclusion, branch, e.workflow_run.html_url
-                    ),
-                    color,
-                    Some(format!("Branch: {}", branch)),
-                )
-            }
-            ParsedEvent::PullRequest(e) => {
-                let has_bounty = e.pull_request.labels.iter().any(|l| l.name == "bounty");
-                let color = if has_bounty { COLOR_BOUNTY } else { COLOR_PR };
-                let thread_name = if has_bounty {
-                    "🪙 PR with bounty"
-                } else if e.action == "opened" {
-                    "🧩 PR Opened"
-                } else {
-                    "🧩 PR Merged"
-                };
-
-                let action_verb = if e.action == "opened" {
-                    "Opened"
-                } else {
-                    "Merged"
-                };
-                (
-                    thread_name,
-                    e.pull_request.title.clone(),
-                    format!(
-                        "{} by @{}\n[View PR]({})",
-                        action_verb, e.sender.login, e.pull_request.html_url
-                    ),
-                    color,
-                    Some(format!("by @{}", e.sender.login)),
-                )
-            }
-            ParsedEvent::Issue(e) => {
-                let has_bounty = e.issue.labels.iter().any(|l| l.name == "bounty");
-                let color = if has_bounty {
-                    COLOR_BOUNTY
-                } else {
-                    COLOR_ISSUE
-                };
-                let thread_name = if has_bounty {
-                    "🪙 Issue with bounty"
-                } else {
-                    "📋 Other issues"
-                };
-
-                (
-                    thread_name,
-                    e.issue.title.clone(),
-                    format!(
-                        "Opened by @{}\n[View Issue]({})",
-                        e.sender.login, e.issue.html_url
-                    ),
-                    color,
-                    Some(format!("by @{}", e.sender.login)),
-                )
+        // Items posted before get their message edited instead, wherever it is
+        if let Some(key) = &message.edit_key {
+            if self.edit_posted_message(key, message).await? {
+                return Ok(());
             }
-            ParsedEvent::Release(e) => (
-                "🚀 Release Published",
-                format!("Release {}", e.release.tag_name),
-                format!(
-                    "{}\n\n[View Release]({})",
-                    e.release.body.as_deref().unwrap_or(""),
-                    e.release.html_url
-                ),
-                COLOR_SUCCESS,
-                Some(format!("by @{}", e.sender.login)),
-            ),
-            _ => return Ok(()),
-        };
+        }
+        if message.edit_only {
+            return Ok(());
+        }
 
         // Reuse thread if it exists
         if let Some(tid) = self
@@ -430,9 +818,20 @@ impl Dispatcher {
             .find_active_thread_by_name(guild_id, forum_id, thread_name)
             .await?
         {
-            self.discord
-                .send_message_with_embed(tid, &title, &description, color, footer_text.as_deref())
+            let message_id = self
+                .discord
+                .send_message_with_embed(
+                    tid,
+                    &message.title,
+                    &message.description,
+                    message.color,
+                    message.footer.as_deref(),
+                )
                 .await?;
+            if let Some(key) = &message.edit_key {
+                self.record_posted_message(key, &tid.to_string(), &message_id.to_string())
+                    .await;
+            }
         } else {
             // Create as public forum thread, but then immediately lock and pin
             let tid = self
@@ -440,13 +839,19 @@ impl Dispatcher {
                 .create_forum_thread_with_embed(
                     forum_id,
                     thread_name,
-                    &title,
-                    &description,
-                    color,
-                    footer_text.as_deref(),
+                    &message.title,
+                    &message.description,
+                    message.color,
+                    message.footer.as_deref(),
                 )
                 .await?;
 
+            // The starter message of a forum thread shares the thread's ID
+            if let Some(key) = &message.edit_key {
+                let id = tid.to_string();
+                self.record_posted_message(key, &id, &id).await;
+            }
+
             // Lock the sidebar thread (but don't pin - Discord allows only 1 pinned thread per forum)
             // Note: Requires bot to have MANAGE_THREADS permission in the guild
             if let Err(e) = self.discord.lock_thread(tid).await {
@@ -457,22 +862,123 @@ impl Dispatcher {
         Ok(())
     }
 
+    /// Remember where a message was sent. Failures are only logged: the
+    /// message is already out, and retrying the destination would post it twice.
+    async fn record_posted_message(&self, key: &str, channel_id: &str, message_id: &str) {
+        if let Err(e) =
+            messages::save_posted_message(self.db.as_ref(), key, channel_id, message_id).await
+        {
+            warn!(key, error = %e, "failed to record posted message");
+        }
+    }
+
+    /// Edit the message recorded for `key`, in the thread it was posted to.
+    /// Returns false when there is nothing to edit, e.g. the message or its
+    /// thread was deleted.
+    async fn edit_posted_message(&self, key: &str, message: &PlannedMessage) -> Result<bool> {
+        let Some(posted) = messages::get_posted_message(self.db.as_ref(), key).await? else {
+            return Ok(false);
+        };
+        let (Some(channel_id), Some(message_id)) = (
+            posted
+                .channel_id
+                .parse::<u64>()
+                .ok()
+                .and_then(Id::new_checked),
+            posted
+                .message_id
+                .parse::<u64>()
+                .ok()
+                .and_then(Id::new_checked),
+        ) else {
+            return Ok(false);
+        };
+
+        match self
+            .discord
+            .edit_message_embed(
+                channel_id,
+                message_id,
+                &message.title,
+                &message.description,
+                message.color,
+                message.footer.as_deref(),
+            )
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                info!(key, error = %e, "failed to edit message, posting a new one");
+                Ok(false)
+            }
+        }
+    }
+
+    /// Post to the private moderator channel, recreating it if it was
+    /// deleted. Never falls back to a channel found by name, which could be public.
+    async fn post_to_moderators(
+        &self,
+        project: &projects::Project,
+        message: &PlannedMessage,
+    ) -> Result<()> {
+        let guild_id = Id::new(project.guild_id.parse::<u64>().unwrap_or(0));
+        let config = match server_config::get_config(self.db.as_ref(), &project.guild_id).await? {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+
+        let channels = self.discord.guild_channels(guild_id).await?;
+        let existing = config
+            .moderator_channel_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+            .map(Id::new)
+            .filter(|id| channels.iter().any(|c| c.id == *id));
+
+        let channel_id = match existing {
+            Some(id) => id,
+            None => {
+                info!(guild_id = %project.guild_id, "moderator channel missing, creating it");
+                let id = self.discord.create_moderator_channel(guild_id).await?;
+                server_config::set_moderator_channel(
+                    self.db.as_ref(),
+                    &project.guild_id,
+                    &id.get().to_string(),
+                )
+                .await?;
+                id
+            }
+        };
+
+        self.discord
+            .send_message_with_embed(
+                channel_id,
+                &message.title,
+                &message.description,
+                message.color,
+                message.footer.as_deref(),
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn post_to_announcements(
         &self,
-        event: &ParsedEvent,
         project: &projects::Project,
+        message: &PlannedMessage,
     ) -> Result<()> {
         if project.guild_id.is_empty() {
             return Ok(());
         }
 
         let guild_id = Id::new(project.guild_id.parse::<u64>().unwrap_or(0));
-        let config = match crate::governance::server_config::get_config(&self.db, &project.guild_id)
-            .await?
-        {
-            Some(c) => c,
-            None => return Ok(()),
-        };
+        let config =
+            match crate::governance::server_config::get_config(self.db.as_ref(), &project.guild_id)
+                .await?
+            {
+                Some(c) => c,
+                None => return Ok(()),
+            };
 
         // Self-healing for announcements channel
         let announce_channel = match self
@@ -484,7 +990,7 @@ impl Dispatcher {
                 // Sync if DB has wrong ID
                 if id.get().to_string() != config.announcements_id {
                     crate::governance::server_config::save_config(
-                        &self.db,
+                        self.db.as_ref(),
                         &project.guild_id,
                         &id.get().to_string(),
                         &config.github_forum_id,
@@ -496,7 +1002,7 @@ impl Dispatcher {
             None => {
                 let id = self.discord.create_announcements_channel(guild_id).await?;
                 crate::governance::server_config::save_config(
-                    &self.db,
+                    self.db.as_ref(),
                     &project.guild_id,
                     &id.get().to_string(),
                     &config.github_forum_id,
@@ -506,67 +1012,878 @@ impl Dispatcher {
             }
         };
 
-        match event {
-            ParsedEvent::Release(e) => {
-                self.discord
-                    .send_message_with_embed(
-                        announce_channel,
-                        &format!("🚀 New Release: {}", e.release.tag_name),
-                        &format!(
-                            "{}\n\n[View Release]({})",
-                            e.release.body.as_deref().unwrap_or(""),
-                            e.release.html_url
-                        ),
-                        COLOR_SUCCESS,
-                        Some(&format!("Project: {}", project.name)),
-                    )
-                    .await?;
-            }
-            ParsedEvent::PullRequest(e) => {
-                if e.pull_request.labels.iter().any(|l| l.name == "bounty") {
-                    let verb = match e.action.as_str() {
-                        "opened" => "Opened",
-                        "labeled" => "Labeled",
-                        _ => "Merged",
-                    };
-                    self.discord
-                        .send_message_with_embed(
-                            announce_channel,
-                            &format!("🪙 Bounty PR {}: #{}", verb, e.pull_request.number),
-                            &format!(
-                                "**{}**\nby @{}\n[View PR]({})",
-                                e.pull_request.title, e.sender.login, e.pull_request.html_url
-                            ),
-                            COLOR_BOUNTY,
-                            Some(&format!("Project: {}", project.name)),
-                        )
-                        .await?;
-                }
+        let message_id = self
+            .discord
+            .send_message_with_embed(
+                announce_channel,
+                &message.title,
+                &message.description,
+                message.color,
+                message.footer.as_deref(),
+            )
+            .await?;
+        if let Some(key) = &message.edit_key {
+            self.record_posted_message(key, &announce_channel.to_string(), &message_id.to_string())
+                .await;
+        }
+        Ok(())
+    }
+}
+
+/// Render the rule template if there is one, keeping the built-in layout
+/// when it cannot be rendered for this event
+fn apply_template(
+    template: Option<&str>,
+    event: &ParsedEvent,
+    title: String,
+    description: String,
+) -> (String, String) {
+    match template.map(|t| templates::render(t, event)) {
+        Some(Ok(rendered)) => rendered,
+        Some(Err(e)) => {
+            warn!(error = %e, "failed to render template, using built-in layout");
+            (title, description)
+        }
+        None => (title, description),
+    }
+}
+
+/// Built-in layout for the activity thread: (title, description, color, footer)
+pub fn activity_embed(event: &ParsedEvent) -> Option<(String, String, u32, Option<String>)> {
+    match event {
+        ParsedEvent::WorkflowRun(e) => {
+            let conclusion = e.workflow_run.conclusion.as_deref().unwrap_or("unknown");
+            let (emoji, _, color) = ci_style(conclusion);
+            let name = e.workflow_run.name.as_deref().unwrap_or("CI");
+            let branch = e.workflow_run.head_branch.as_deref().unwrap_or("unknown");
+
+            Some((
+                format!("{} {} {}", emoji, name, conclusion),
+                format!(
+                    "Branch: `{}`\n[View Run]({})",
+                    branch, e.workflow_run.html_url
+                ),
+                color,
+                None,
+            ))
+        }
+        ParsedEvent::CheckSuite(e) => {
+            let suite = &e.check_suite;
+            let conclusion = suite.conclusion.as_deref().unwrap_or("unknown");
+            let (emoji, _, color) = ci_style(conclusion);
+            let branch = suite.head_branch.as_deref().unwrap_or("unknown");
+
+            Some((
+                format!("{} {} {}", emoji, suite.app.name, conclusion),
+                format!(
+                    "Branch: `{}`\nCommit: `{}`\n[View Checks]({})",
+                    branch,
+                    short_sha(&suite.head_sha),
+                    checks_url(&e.repository.full_name, &suite.head_sha)
+                ),
+                color,
+                None,
+            ))
+        }
+        ParsedEvent::CheckRun(e) => {
+            let run = &e.check_run;
+            let conclusion = run.conclusion.as_deref().unwrap_or("unknown");
+            let (emoji, _, color) = ci_style(conclusion);
+            let branch = run.check_suite.head_branch.as_deref().unwrap_or("unknown");
+
+            Some((
+                format!("{} {} {}", emoji, run.name, conclusion),
+                format!(
+                    "Branch: `{}`\nCommit: `{}`\n[View Check]({})",
+                    branch,
+                    short_sha(&run.head_sha),
+                    run.html_url
+                ),
+                color,
+                Some(run.app.name.clone()),
+            ))
+        }
+        ParsedEvent::PullRequest(e) => {
+            let has_bounty = e.pull_request.labels.iter().any(|l| l.name == "bounty");
+            let color = if has_bounty { COLOR_BOUNTY } else { COLOR_PR };
+            let emoji = if has_bounty { "🪙" } else { "🧩" };
+            let action_text = match e.action.as_str() {
+                "opened" => "opened",
+                "labeled" => "labeled",
+                "closed" if e.pull_request.merged.unwrap_or(false) => "merged",
+                "closed" => "closed",
+                _ => &e.action,
+            };
+
+            Some((
+                format!("{} PR #{} {}", emoji, e.pull_request.number, action_text),
+                format!(
+                    "**{}**\nby @{}\n[View PR]({})",
+                    e.pull_request.title, e.sender.login, e.pull_request.html_url
+                ),
+                color,
+                None,
+            ))
+        }
+        ParsedEvent::Issue(e) => {
+            let has_bounty = e.issue.labels.iter().any(|l| l.name == "bounty");
+            let color = if has_bounty {
+                COLOR_BOUNTY
+            } else {
+                COLOR_ISSUE
+            };
+            let emoji = if has_bounty { "🪙" } else { "📋" };
+            let action_text = match e.action.as_str() {
+                "opened" => "opened",
+                "labeled" => "labeled",
+                "closed" => "closed",
+                _ => &e.action,
+            };
+
+            Some((
+                format!("{} Issue #{} {}", emoji, e.issue.number, action_text),
+                format!(
+                    "**{}**\nby @{}\n[View Issue]({})",
+                    e.issue.title, e.sender.login, e.issue.html_url
+                ),
+                color,
+                None,
+            ))
+        }
+        ParsedEvent::Release(e) => Some((
+            format!("🚀 Release {}", e.release.tag_name),
+            format!(
+                "{}\n\n[View Release]({})",
+                e.release.body.as_deref().unwrap_or(""),
+                e.release.html_url
+            ),
+            COLOR_SUCCESS,
+            Some(format!("by @{}", e.sender.login)),
+        )),
+        ParsedEvent::Push(e) => {
+            let (title, description) = format_push(e);
+            Some((title, description, COLOR_PUSH, None))
+        }
+        ParsedEvent::IssueComment(e) => {
+            let kind = if e.is_pull_request() { "PR" } else { "issue" };
+            Some((
+                format!("💬 Comment on {} #{}", kind, e.issue.number),
+                format!(
+                    "**{}**\n{}\nby @{}\n[View Comment]({})",
+                    e.issue.title,
+                    format_comment_excerpt(&e.comment.body),
+                    e.comment.user.login,
+                    e.comment.html_url
+                ),
+                COLOR_COMMENT,
+                None,
+            ))
+        }
+        ParsedEvent::PullRequestReviewComment(e) => Some((
+            format!("💬 Review comment on PR #{}", e.pull_request.number),
+            format!(
+                "**{}**\n{}\nby @{}\n[View Comment]({})",
+                e.pull_request.title,
+                format_comment_excerpt(&e.comment.body),
+                e.comment.user.login,
+                e.comment.html_url
+            ),
+            COLOR_COMMENT,
+            None,
+        )),
+        ParsedEvent::PullRequestReview(e) => {
+            let (emoji, verb, color) = review_style(&e.review.state);
+            let body = e
+                .review
+                .body
+                .as_deref()
+                .filter(|b| !b.trim().is_empty())
+                .map(|b| format!("{}\n", format_comment_excerpt(b)))
+                .unwrap_or_default();
+            Some((
+                format!("{} PR #{} {}", emoji, e.pull_request.number, verb),
+                format!(
+                    "**{}**\n{}by @{}\n[View Review]({})",
+                    e.pull_request.title, body, e.review.user.login, e.review.html_url
+                ),
+                color,
+                None,
+            ))
+        }
+        ParsedEvent::Discussion(e) => {
+            let d = &e.discussion;
+            match (e.action.as_str(), &e.answer) {
+                ("answered", Some(answer)) => Some((
+                    format!("✅ Discussion #{} answered", d.number),
+                    format!(
+                        "**{}**\n{}\nAnswer by @{}\n[View Answer]({})",
+                        d.title,
+                        format_comment_excerpt(&answer.body),
+                        answer.user.login,
+                        answer.html_url
+                    ),
+                    COLOR_SUCCESS,
+                    None,
+                )),
+                _ => Some((
+                    format!("💬 Discussion #{} {}", d.number, e.action),
+                    format!(
+                        "**{}**\nin {} by @{}\n[View Discussion]({})",
+                        d.title, d.category.name, d.user.login, d.html_url
+                    ),
+                    COLOR_DISCUSSION,
+                    None,
+                )),
             }
-            ParsedEvent::Issue(e) => {
-                if e.issue.labels.iter().any(|l| l.name == "bounty") {
-                    let verb = if e.action == "labeled" {
-                        "Labeled"
-                    } else {
-                        "Opened"
-                    };
-                    self.discord
-                        .send_message_with_embed(
-                            announce_channel,
-                            &format!("🪙 Bounty Issue {}: #{}", verb, e.issue.number),
-                            &format!(
-                                "**{}**\nby @{}\n[View Issue]({})",
-                                e.issue.title, e.sender.login, e.issue.html_url
-                            ),
-                            COLOR_BOUNTY,
-                            Some(&format!("Project: {}", project.name)),
-                        )
-                        .await?;
+        }
+        ParsedEvent::DiscussionComment(e) => Some((
+            format!("💬 Comment on discussion #{}", e.discussion.number),
+            format!(
+                "**{}**\n{}\nby @{}\n[View Comment]({})",
+                e.discussion.title,
+                format_comment_excerpt(&e.comment.body),
+                e.comment.user.login,
+                e.comment.html_url
+            ),
+            COLOR_DISCUSSION,
+            None,
+        )),
+        ParsedEvent::SecurityAdvisory(_)
+        | ParsedEvent::DependabotAlert(_)
+        | ParsedEvent::CodeScanningAlert(_)
+        | ParsedEvent::SecretScanningAlert(_) => None,
+        ParsedEvent::Star(e) => e.milestone().map(|stars| {
+            (
+                format!("⭐ {} stars", stars),
+                format!(
+                    "**{}** just reached {} stars, the latest from @{}\n[View Stargazers]({}/stargazers)",
+                    e.repository.full_name, stars, e.sender.login, e.repository.html_url
+                ),
+                COLOR_STAR,
+                None,
+            )
+        }),
+        ParsedEvent::Fork(e) => Some((
+            format!("🍴 Forked by @{}", e.forkee.owner.login),
+            format!(
+                "[{}]({})\n{} forks in total",
+                e.forkee.full_name, e.forkee.html_url, e.repository.forks_count
+            ),
+            COLOR_REF,
+            None,
+        )),
+        ParsedEvent::Create(e) | ParsedEvent::Delete(e) => {
+            let created = matches!(event, ParsedEvent::Create(_));
+            let kind = if e.is_tag() { "Tag" } else { "Branch" };
+            let (emoji, verb) = match (created, e.is_tag()) {
+                (true, true) => ("🏷️", "created"),
+                (true, false) => ("🌿", "created"),
+                (false, _) => ("🗑️", "deleted"),
+            };
+            let link = if created {
+                format!(
+                    "\n[View {}](https://github.com/{}/tree/{})",
+                    kind, e.repository.full_name, e.ref_name
+                )
+            } else {
+                String::new()
+            };
+            Some((
+                format!("{} {} `{}` {}", emoji, kind, e.ref_name, verb),
+                format!("by @{}{}", e.sender.login, link),
+                COLOR_REF,
+                None,
+            ))
+        }
+        ParsedEvent::Ping(e) => Some((
+            "🔌 Webhook connected".to_string(),
+            format!(
+                "GitHub will now deliver events for **{}** here.\n> {}",
+                e.repository
+                    .as_ref()
+                    .map(|r| r.full_name.as_str())
+                    .unwrap_or("this project"),
+                e.zen
+            ),
+            COLOR_SUCCESS,
+            Some(format!("Hook ID: {}", e.hook_id)),
+        )),
+        ParsedEvent::Repository(e) => {
+            let repo = &e.repository.full_name;
+            let (title, description) = match e.action.as_str() {
+                "renamed" | "transferred" => {
+                    let previous = e.previous_full_name().unwrap_or_default();
+                    (
+                        format!("✏️ Repository {}", e.action),
+                        format!("`{}` → `{}`\nby @{}", previous, repo, e.sender.login),
+                    )
                 }
+                "archived" => (
+                    "🗄️ Repository archived".to_string(),
+                    format!("**{}** is now read-only\nby @{}", repo, e.sender.login),
+                ),
+                "unarchived" => (
+                    "📂 Repository unarchived".to_string(),
+                    format!("**{}** is active again\nby @{}", repo, e.sender.login),
+                ),
+                "deleted" => (
+                    "🗑️ Repository deleted".to_string(),
+                    format!(
+                        "**{}** was deleted by @{}\nUse `/deny` to stop tracking it.",
+                        repo, e.sender.login
+                    ),
+                ),
+                _ => return None,
+            };
+            Some((title, description, COLOR_REF, None))
+        }
+        ParsedEvent::Deployment(_) | ParsedEvent::DeploymentStatus(_) => deployment_embed(event),
+        ParsedEvent::Milestone(e) => {
+            let milestone = &e.milestone;
+            let (emoji, verb) = match e.action.as_str() {
+                "created" => ("🎯", "created"),
+                "closed" => ("🏁", "closed"),
+                "edited" if e.due_date_changed() => ("📅", "rescheduled"),
+                _ => return None,
+            };
+            Some((
+                format!("{} Milestone {}: {}", emoji, verb, milestone.title),
+                format!(
+                    "{}\n[View Milestone]({})",
+                    format_milestone_progress(milestone),
+                    milestone.html_url
+                ),
+                if milestone.is_open() {
+                    COLOR_MILESTONE
+                } else {
+                    COLOR_SUCCESS
+                },
+                Some(format!("by @{}", e.sender.login)),
+            ))
+        }
+        ParsedEvent::Unknown => None,
+    }
+}
+
+/// Built-in layout for sidebar threads: (thread name, title, description, color, footer)
+pub fn sidebar_embed(
+    event: &ParsedEvent,
+) -> Option<(&'static str, String, String, u32, Option<String>)> {
+    let embed = match event {
+        ParsedEvent::WorkflowRun(e) => {
+            let conclusion = e.workflow_run.conclusion.as_deref().unwrap_or("unknown");
+            let (_, thread_name, mut color) = ci_style(conclusion);
+            let name = e.workflow_run.name.as_deref().unwrap_or("CI");
+            let branch = e.workflow_run.head_branch.as_deref().unwrap_or("unknown");
+            // A re-run shows as running until it completes again
+            let conclusion = if e.action == "completed" {
+                conclusion
+            } else {
+                color = COLOR_SKIPPED;
+                "in progress"
+            };
+
+            (
+                thread_name,
+                format!("{} Run Details", name),
+                format!(
+                    "**{}** - {}\nBranch: `{}`\n[View Run]({})",
+                    name, conclusion, branch, e.workflow_run.html_url
+                ),
+                color,
+                Some(format!("Branch: {}", branch)),
+            )
+        }
+        ParsedEvent::CheckSuite(e) => {
+            let suite = &e.check_suite;
+            let conclusion = suite.conclusion.as_deref().unwrap_or("unknown");
+            let (_, thread_name, color) = ci_style(conclusion);
+            let branch = suite.head_branch.as_deref().unwrap_or("unknown");
+
+            (
+                thread_name,
+                format!("{} Run Details", suite.app.name),
+                format!(
+                    "**{}** - {}\nBranch: `{}`\nCommit: `{}`\n[View Checks]({})",
+                    suite.app.name,
+                    conclusion,
+                    branch,
+                    short_sha(&suite.head_sha),
+                    checks_url(&e.repository.full_name, &suite.head_sha)
+                ),
+                color,
+                Some(format!("Branch: {}", branch)),
+            )
+        }
+        ParsedEvent::PullRequest(e) => {
+            let pr = &e.pull_request;
+            let has_bounty = pr.labels.iter().any(|l| l.name == "bounty");
+            let merged = pr.merged.unwrap_or(false);
+            let closed = e.action == "closed" || pr.state.as_deref() == Some("closed");
+            let author = pr.user.as_ref().unwrap_or(&e.sender);
+
+            let color = if has_bounty {
+                COLOR_BOUNTY
+            } else if closed && !merged {
+                COLOR_SKIPPED
+            } else {
+                COLOR_PR
+            };
+            let thread_name = if has_bounty {
+                "🪙 PR with bounty"
+            } else if merged {
+                "🧩 PR Merged"
+            } else {
+                "🧩 PR Opened"
+            };
+            let status = if merged {
+                let by = pr.merged_by.as_ref().unwrap_or(&e.sender);
+                format!("Merged by @{}", by.login)
+            } else if closed {
+                format!("Closed by @{}", e.sender.login)
+            } else {
+                format!("Opened by @{}", author.login)
+            };
+
+            (
+                thread_name,
+                pr.title.clone(),
+                format!(
+                    "{}{}\n[View PR]({})",
+                    status,
+                    format_labels(&pr.labels),
+                    pr.html_url
+                ),
+                color,
+                Some(format!("by @{}", author.login)),
+            )
+        }
+        ParsedEvent::Issue(e) => {
+            let issue = &e.issue;
+            let has_bounty = issue.labels.iter().any(|l| l.name == "bounty");
+            let closed = e.action == "closed" || issue.state.as_deref() == Some("closed");
+            let author = issue.user.as_ref().unwrap_or(&e.sender);
+
+            let color = if has_bounty {
+                COLOR_BOUNTY
+            } else if closed {
+                COLOR_SKIPPED
+            } else {
+                COLOR_ISSUE
+            };
+            let thread_name = if has_bounty {
+                "🪙 Issue with bounty"
+            } else {
+                "📋 Other issues"
+            };
+            let status = if closed {
+                format!("Closed by @{}", e.sender.login)
+            } else {
+                format!("Opened by @{}", author.login)
+            };
+
+            (
+                thread_name,
+                issue.title.clone(),
+                format!(
+                    "{}{}\n[View Issue]({})",
+                    status,
+                    format_labels(&issue.labels),
+                    issue.html_url
+                ),
+                color,
+                Some(format!("by @{}", author.login)),
+            )
+        }
+        ParsedEvent::Release(e) => (
+            "🚀 Release Published",
+            format!("Release {}", e.release.tag_name),
+            format!(
+                "{}\n\n[View Release]({})",
+                e.release.body.as_deref().unwrap_or(""),
+                e.release.html_url
+            ),
+            COLOR_SUCCESS,
+            Some(format!("by @{}", e.sender.login)),
+        ),
+        ParsedEvent::IssueComment(e) => {
+            let has_bounty = e.issue.labels.iter().any(|l| l.name == "bounty");
+            let thread_name = match (e.is_pull_request(), has_bounty) {
+                (true, true) => "🪙 PR with bounty",
+                (true, false) => "🧩 PR Opened",
+                (false, true) => "🪙 Issue with bounty",
+                (false, false) => "📋 Other issues",
+            };
+            (
+                thread_name,
+                format!("💬 #{} {}", e.issue.number, e.issue.title),
+                format!(
+                    "{}\n[View Comment]({})",
+                    format_comment_excerpt(&e.comment.body),
+                    e.comment.html_url
+                ),
+                COLOR_COMMENT,
+                Some(format!("by @{}", e.comment.user.login)),
+            )
+        }
+        ParsedEvent::PullRequestReviewComment(e) => {
+            let has_bounty = e.pull_request.labels.iter().any(|l| l.name == "bounty");
+            let thread_name = if has_bounty {
+                "🪙 PR with bounty"
+            } else {
+                "🧩 PR Opened"
+            };
+            (
+                thread_name,
+                format!("💬 #{} {}", e.pull_request.number, e.pull_request.title),
+                format!(
+                    "{}\n[View Comment]({})",
+                    format_comment_excerpt(&e.comment.body),
+                    e.comment.html_url
+                ),
+                COLOR_COMMENT,
+                Some(format!("by @{}", e.comment.user.login)),
+            )
+        }
+        ParsedEvent::PullRequestReview(e) => {
+            let has_bounty = e.pull_request.labels.iter().any(|l| l.name == "bounty");
+            let thread_name = if has_bounty {
+                "🪙 PR with bounty"
+            } else {
+                "🧩 PR Opened"
+            };
+            let (emoji, verb, color) = review_style(&e.review.state);
+            (
+                thread_name,
+                format!(
+                    "{} #{} {}",
+                    emoji, e.pull_request.number, e.pull_request.title
+                ),
+                format!(
+                    "{} by @{}\n[View Review]({})",
+                    capitalize(verb),
+                    e.review.user.login,
+                    e.review.html_url
+                ),
+                color,
+                Some(format!("by @{}", e.review.user.login)),
+            )
+        }
+        ParsedEvent::Discussion(e) => {
+            let d = &e.discussion;
+            match (e.action.as_str(), &e.answer) {
+                ("answered", Some(answer)) => (
+                    DISCUSSIONS_THREAD,
+                    format!("✅ {}", d.title),
+                    format!(
+                        "Answered in **{}**\n{}\n[View Answer]({})",
+                        d.category.name,
+                        format_comment_excerpt(&answer.body),
+                        answer.html_url
+                    ),
+                    COLOR_SUCCESS,
+                    Some(format!("answer by @{}", answer.user.login)),
+                ),
+                _ => (
+                    DISCUSSIONS_THREAD,
+                    d.title.clone(),
+                    format!(
+                        "New in **{}**\n[View Discussion]({})",
+                        d.category.name, d.html_url
+                    ),
+                    COLOR_DISCUSSION,
+                    Some(format!("by @{}", d.user.login)),
+                ),
             }
-            _ => {}
         }
+        ParsedEvent::Deployment(_) | ParsedEvent::DeploymentStatus(_) => {
+            let (title, description, color, footer) = deployment_embed(event)?;
+            (DEPLOYMENTS_THREAD, title, description, color, footer)
+        }
+        ParsedEvent::Milestone(e) => {
+            let (title, description, color, footer) = milestone_embed(&e.milestone);
+            (MILESTONES_THREAD, title, description, color, footer)
+        }
+        _ => return None,
+    };
+    Some(embed)
+}
 
-        Ok(())
+/// Sidebar thread collecting new and answered discussions
+pub const DISCUSSIONS_THREAD: &str = "💬 Discussions";
+
+/// Sidebar thread with one message per deployment, edited as its status changes
+pub const DEPLOYMENTS_THREAD: &str = "🚢 Deployments";
+
+/// Sidebar thread with one message per milestone, edited as its issues move
+pub const MILESTONES_THREAD: &str = "🎯 Milestones";
+
+/// Current state of a milestone: (title, description, color, footer)
+fn milestone_embed(milestone: &Milestone) -> (String, String, u32, Option<String>) {
+    let (emoji, color) = if milestone.is_open() {
+        ("🎯", COLOR_MILESTONE)
+    } else {
+        ("🏁", COLOR_SUCCESS)
+    };
+
+    let mut description = format_milestone_progress(milestone);
+    if let Some(text) = milestone.description.as_deref().filter(|d| !d.is_empty()) {
+        description.push_str(&format!("\n{}", format_comment_excerpt(text)));
     }
+    description.push_str(&format!("\n[View Milestone]({})", milestone.html_url));
+
+    (
+        format!("{} {}", emoji, milestone.title),
+        description,
+        color,
+        Some(format!("Milestone #{}", milestone.number)),
+    )
+}
+
+/// Current state of a deployment: (title, description, color, footer)
+fn deployment_embed(event: &ParsedEvent) -> Option<(String, String, u32, Option<String>)> {
+    let deployment = event.deployment()?;
+    let status = match event {
+        ParsedEvent::DeploymentStatus(e) => Some(&e.deployment_status),
+        _ => None,
+    };
+    let state = status.map(|s| s.state.as_str()).unwrap_or("pending");
+    let (emoji, label, color) = deployment_style(state);
+
+    let sha: String = deployment.sha.chars().take(7).collect();
+    let mut description = format!(
+        "`{}` on `{}` by @{}",
+        sha, deployment.ref_name, deployment.creator.login
+    );
+    let note = status
+        .and_then(|s| s.description.as_deref())
+        .or(deployment.description.as_deref())
+        .filter(|d| !d.is_empty());
+    if let Some(note) = note {
+        description.push_str(&format!("\n{}", note));
+    }
+    let mut links = Vec::new();
+    if let Some(url) = status.and_then(|s| s.environment_url.as_ref().or(s.target_url.as_ref())) {
+        links.push(format!("[Open {}]({})", deployment.environment, url));
+    }
+    if let Some(url) = status.and_then(|s| s.log_url.as_ref()) {
+        links.push(format!("[Logs]({})", url));
+    }
+    if !links.is_empty() {
+        description.push_str(&format!("\n{}", links.join(" · ")));
+    }
+
+    Some((
+        format!("{} Deploy to {} {}", emoji, deployment.environment, label),
+        description,
+        color,
+        Some(format!("Deployment #{}", deployment.id)),
+    ))
+}
+
+/// One line per destination that could not be posted to
+pub fn describe_failures(failures: &[(Destination, Error)]) -> String {
+    failures
+        .iter()
+        .map(|(destination, error)| format!("{}: {}", destination.label(), error))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// (emoji, sidebar thread, color) for a CI conclusion
+fn ci_style(conclusion: &str) -> (&'static str, &'static str, u32) {
+    if conclusion == "success" {
+        ("✅", "✅ CI Passed", COLOR_SUCCESS)
+    } else {
+        ("❌", "❌ CI Failed", COLOR_FAILURE)
+    }
+}
+
+/// Labels line of item messages, empty when there are none
+fn format_labels(labels: &[Label]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let names: Vec<String> = labels.iter().map(|l| format!("`{}`", l.name)).collect();
+    format!("\nLabels: {}", names.join(", "))
+}
+
+fn is_item_update(action: &str) -> bool {
+    matches!(
+        action,
+        "closed" | "reopened" | "labeled" | "unlabeled" | "edited"
+    )
+}
+
+/// Only clear passes and failures are reported, not e.g. cancelled or skipped runs
+fn is_ci_outcome(conclusion: Option<&str>) -> bool {
+    matches!(conclusion, Some("success") | Some("failure"))
+}
+
+fn short_sha(sha: &str) -> String {
+    sha.chars().take(7).collect()
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn activity_thread_name(repo: &str) -> String {
+    let project_name = repo.rsplit('/').next().unwrap_or(repo);
+    format!("📦 {} Activity", project_name)
+}
+
+/// Built-in layout for the announcements channel: (title, description, color)
+pub fn announcement_embed(event: &ParsedEvent) -> Option<(String, String, u32)> {
+    match event {
+        ParsedEvent::Release(e) => Some((
+            format!("🚀 New Release: {}", e.release.tag_name),
+            format!(
+                "{}\n\n[View Release]({})",
+                e.release.body.as_deref().unwrap_or(""),
+                e.release.html_url
+            ),
+            COLOR_SUCCESS,
+        )),
+        ParsedEvent::PullRequest(e) => {
+            let has_bounty = e.pull_request.labels.iter().any(|l| l.name == "bounty");
+            let verb = match e.action.as_str() {
+                "opened" => "Opened",
+                "labeled" => "Labeled",
+                "closed" if !e.pull_request.merged.unwrap_or(false) => "Closed",
+                _ => "Merged",
+            };
+            let (title, color) = if has_bounty {
+                (
+                    format!("🪙 Bounty PR {}: #{}", verb, e.pull_request.number),
+                    COLOR_BOUNTY,
+                )
+            } else {
+                (
+                    format!("🧩 PR {}: #{}", verb, e.pull_request.number),
+                    COLOR_PR,
+                )
+            };
+            Some((
+                title,
+                format!(
+                    "**{}**\nby @{}\n[View PR]({})",
+                    e.pull_request.title, e.sender.login, e.pull_request.html_url
+                ),
+                color,
+            ))
+        }
+        ParsedEvent::Issue(e) => {
+            let has_bounty = e.issue.labels.iter().any(|l| l.name == "bounty");
+            let verb = match e.action.as_str() {
+                "labeled" => "Labeled",
+                "closed" => "Closed",
+                _ => "Opened",
+            };
+            let (title, color) = if has_bounty {
+                (
+                    format!("🪙 Bounty Issue {}: #{}", verb, e.issue.number),
+                    COLOR_BOUNTY,
+                )
+            } else {
+                (
+                    format!("📋 Issue {}: #{}", verb, e.issue.number),
+                    COLOR_ISSUE,
+                )
+            };
+            Some((
+                title,
+                format!(
+                    "**{}**\nby @{}\n[View Issue]({})",
+                    e.issue.title, e.sender.login, e.issue.html_url
+                ),
+                color,
+            ))
+        }
+        ParsedEvent::Star(e) => e.milestone().map(|stars| {
+            (
+                format!("⭐ {} reached {} stars!", e.repository.name, stars),
+                format!(
+                    "Thank you to everyone who starred **{}** 💛\n[View Stargazers]({}/stargazers)",
+                    e.repository.full_name, e.repository.html_url
+                ),
+                COLOR_STAR,
+            )
+        }),
+        _ => None,
+    }
+}
+
+/// Built-in layout for the moderator channel: (title, description, color)
+pub fn moderator_embed(event: &ParsedEvent) -> Option<(String, String, u32)> {
+    let severity = event.security_severity().unwrap_or("unknown");
+    let (title, description) = match event {
+        ParsedEvent::SecurityAdvisory(e) => {
+            let advisory = &e.security_advisory;
+            let link = advisory
+                .html_url
+                .as_deref()
+                .map(|url| format!("\n[View Advisory]({})", url))
+                .unwrap_or_default();
+            (
+                format!("🛡️ Advisory {} {}", advisory.ghsa_id, e.action),
+                format!(
+                    "**{}**\nSeverity: **{}**{}",
+                    advisory.summary, severity, link
+                ),
+            )
+        }
+        ParsedEvent::DependabotAlert(e) => {
+            let package = &e.alert.dependency.package;
+            let manifest = e
+                .alert
+                .dependency
+                .manifest_path
+                .as_deref()
+                .map(|path| format!(" in `{}`", path))
+                .unwrap_or_default();
+            (
+                format!("🛡️ Dependabot alert #{} {}", e.alert.number, e.action),
+                format!(
+                    "**{}**\nPackage: `{}` ({}){}\nSeverity: **{}**\n[View Alert]({})",
+                    e.alert.security_advisory.summary,
+                    package.name,
+                    package.ecosystem,
+                    manifest,
+                    severity,
+                    e.alert.html_url
+                ),
+            )
+        }
+        ParsedEvent::CodeScanningAlert(e) => {
+            let rule = &e.alert.rule;
+            (
+                format!("🔍 Code scanning alert #{} {}", e.alert.number, e.action),
+                format!(
+                    "**{}**\nTool: {} · Rule: `{}`\nSeverity: **{}**\n[View Alert]({})",
+                    rule.description, e.alert.tool.name, rule.id, severity, e.alert.html_url
+                ),
+            )
+        }
+        ParsedEvent::SecretScanningAlert(e) => {
+            let secret = e
+                .alert
+                .secret_type_display_name
+                .as_deref()
+                .unwrap_or(&e.alert.secret_type);
+            (
+                format!("🔑 Secret scanning alert #{} {}", e.alert.number, e.action),
+                format!(
+                    "**{}** detected\nSeverity: **{}**\n[View Alert]({})",
+                    secret, severity, e.alert.html_url
+                ),
+            )
+        }
+        _ => return None,
+    };
+    Some((title, description, severity_color(severity)))
 }
@@ -0,0 +1,45 @@
+use axum::{body::Bytes, extract::State, http::HeaderMap, Json};
+
+use crate::error::{Error, Result};
+use crate::github::events::ParsedEvent;
+use crate::router::admin;
+use crate::router::dispatch::{DispatchPlan, Dispatcher};
+use crate::AppState;
+
+/// `POST /admin/dry-run` - run a GitHub payload through the rule engine and
+/// return what would be posted, without calling Discord.
+///
+/// Requires `Authorization: Bearer <ADMIN_TOKEN>` and the usual
+/// `X-GitHub-Event` header. Disabled unless `ADMIN_TOKEN` is set.
+pub async fn handle_dry_run(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<DispatchPlan>> {
+    admin::authorize(&state, &headers)?;
+
+    let event_type = headers
+        .get("x-github-event")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| Error::InvalidPayload("missing x-github-event header".into()))?;
+
+    let event = ParsedEvent::from_payload(event_type, &body)
+        .map_err(|e| Error::InvalidPayload(e.to_string()))?;
+
+    if matches!(event, ParsedEvent::Unknown) {
+        return Err(Error::InvalidPayload(format!(
+            "unsupported event type: {}",
+            event_type
+        )));
+    }
+
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let plan = dispatcher.plan(&event).await?.ok_or_else(|| {
+        Error::NotFound(format!(
+            "no approved project for {}",
+            event.repo_full_name().unwrap_or("this event")
+        ))
+    })?;
+
+    Ok(Json(plan))
+}
@@ -0,0 +1,37 @@
+//! Discord messages that later events edit in place
+//!
+//! Each tracked GitHub item (see `ParsedEvent::item_key`) remembers the
+//! message it was last posted as, so a status change can update that embed
+//! instead of posting a new one.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::storage::Storage;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostedMessage {
+    pub item_key: String,
+    pub channel_id: String,
+    pub message_id: String,
+}
+
+pub async fn get_posted_message(db: &dyn Storage, item_key: &str) -> Result<Option<PostedMessage>> {
+    db.get_posted_message(item_key).await
+}
+
+pub async fn save_posted_message(
+    db: &dyn Storage,
+    item_key: &str,
+    channel_id: &str,
+    message_id: &str,
+) -> Result<()> {
+    db.save_posted_message(item_key, channel_id, message_id)
+        .await
+}
+
+/// Keep a renamed repository's messages editable; item keys start with the
+/// repository's full name
+pub async fn rename_repo(db: &dyn Storage, previous: &str, current: &str) -> Result<()> {
+    db.rename_posted_messages(previous, current).await
+}
@@ -1 +1,7 @@
+pub mod admin;
 pub mod dispatch;
+pub mod dry_run;
+pub mod messages;
+pub mod queue;
+pub mod retries;
+pub mod samples;
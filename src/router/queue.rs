@@ -0,0 +1,194 @@
+//! Background dispatch queue
+//!
+//! Dispatching an event can take many Discord and storage round-trips, while
+//! GitHub gives up on a delivery after 10 seconds. The webhook handler only
+//! verifies and enqueues events; a fixed pool of workers dispatches them.
+//!
+//! Every worker owns a bounded channel and events are assigned to workers
+//! by repository, so the events of one repository are dispatched one at a
+//! time and in the order they arrived. Events that fail are handed to
+//! `retries`, which stores them and queues them again later.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+use crate::discord::client::DiscordInterface;
+use crate::error::{Error, Result};
+use crate::github::events::ParsedEvent;
+use crate::router::dispatch::{describe_failures, Dispatcher};
+use crate::router::retries::{self, AttemptFailure, FailedDelivery};
+use crate::storage::Storage;
+
+/// A verified webhook delivery, kept so a failed dispatch can be retried
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    /// `X-GitHub-Delivery` GUID, if GitHub sent one
+    pub delivery_id: Option<String>,
+    /// `X-GitHub-Event` header
+    pub event_type: String,
+    /// Raw webhook body
+    pub payload: String,
+}
+
+enum Attempt {
+    First(Delivery),
+    Retry(Box<FailedDelivery>),
+}
+
+enum Job {
+    Dispatch {
+        event: Box<ParsedEvent>,
+        attempt: Attempt,
+    },
+    /// Answered once every job queued before it was handled
+    Flush(oneshot::Sender<()>),
+}
+
+#[derive(Clone)]
+pub struct DispatchQueue {
+    shards: Arc<Vec<mpsc::Sender<Job>>>,
+}
+
+impl DispatchQueue {
+    /// Spawn `workers` workers with room for `capacity` waiting events each.
+    /// Must be called inside a Tokio runtime.
+    pub fn start(
+        db: Arc<dyn Storage>,
+        discord: Arc<dyn DiscordInterface>,
+        workers: usize,
+        capacity: usize,
+    ) -> Self {
+        let shards = (0..workers.max(1))
+            .map(|_| {
+                let (tx, rx) = mpsc::channel(capacity.max(1));
+                tokio::spawn(run_worker(db.clone(), discord.clone(), rx));
+                tx
+            })
+            .collect();
+
+        Self {
+            shards: Arc::new(shards),
+        }
+    }
+
+    /// Queue an event for dispatch. Fails with `Unavailable` when the
+    /// repository's worker is backed up, so GitHub records a failed delivery
+    /// that can be redelivered.
+    pub fn enqueue(&self, event: ParsedEvent, delivery: Delivery) -> Result<()> {
+        self.send(event, Attempt::First(delivery))
+    }
+
+    /// Queue an event, waiting for room instead of failing when the
+    /// repository's worker is backed up
+    pub async fn enqueue_waiting(&self, event: ParsedEvent, delivery: Delivery) -> Result<()> {
+        let repo = event.repo_full_name().unwrap_or("").to_lowercase();
+        self.shards[self.shard_index(&repo)]
+            .send(Job::Dispatch {
+                event: Box::new(event),
+                attempt: Attempt::First(delivery),
+            })
+            .await
+            .map_err(|_| Error::Internal("dispatch worker stopped".into()))
+    }
+
+    /// Queue a stored failed delivery for another attempt
+    pub fn enqueue_retry(&self, event: ParsedEvent, failed: FailedDelivery) -> Result<()> {
+        self.send(event, Attempt::Retry(Box::new(failed)))
+    }
+
+    fn send(&self, event: ParsedEvent, attempt: Attempt) -> Result<()> {
+        let repo = event.repo_full_name().unwrap_or("").to_lowercase();
+        let shard = &self.shards[self.shard_index(&repo)];
+
+        shard
+            .try_send(Job::Dispatch {
+                event: Box::new(event),
+                attempt,
+            })
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => {
+                    warn!(repo, "dispatch queue full, rejecting event");
+                    Error::Unavailable("dispatch queue is full".into())
+                }
+                mpsc::error::TrySendError::Closed(_) => {
+                    Error::Internal("dispatch worker stopped".into())
+                }
+            })
+    }
+
+    /// Wait until every event queued so far has been dispatched
+    pub async fn flush(&self) {
+        for shard in self.shards.iter() {
+            let (done_tx, done_rx) = oneshot::channel();
+            if shard.send(Job::Flush(done_tx)).await.is_ok() {
+                let _ = done_rx.await;
+            }
+        }
+    }
+
+    fn shard_index(&self, repo: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        repo.hash(&mut hasher);
+        (hasher.finish() % self.shards.len() as u64) as usize
+    }
+}
+
+async fn run_worker(
+    db: Arc<dyn Storage>,
+    discord: Arc<dyn DiscordInterface>,
+    mut jobs: mpsc::Receiver<Job>,
+) {
+    while let Some(job) = jobs.recv().await {
+        match job {
+            Job::Dispatch { event, attempt } => {
+                let repo = event.repo_full_name().unwrap_or("").to_lowercase();
+                let dispatcher = Dispatcher::new(db.clone(), discord.clone());
+                let only = match &attempt {
+                    Attempt::First(_) => None,
+                    Attempt::Retry(failed) => failed.destinations.clone(),
+                };
+
+                // A separate task keeps a panicking dispatch from taking the worker down
+                let task_only = only.clone();
+                let outcome =
+                    tokio::spawn(
+                        async move { dispatcher.deliver(*event, task_only.as_deref()).await },
+                    )
+                    .await;
+
+                let failure = match outcome {
+                    Ok(Ok(failures)) if failures.is_empty() => None,
+                    Ok(Ok(failures)) => Some(AttemptFailure {
+                        error: describe_failures(&failures),
+                        destinations: Some(failures.into_iter().map(|(d, _)| d).collect()),
+                    }),
+                    // Nothing was posted, so the next attempt tries the same destinations
+                    Ok(Err(e)) => Some(AttemptFailure {
+                        error: e.to_string(),
+                        destinations: only,
+                    }),
+                    Err(e) => Some(AttemptFailure {
+                        error: e.to_string(),
+                        destinations: only,
+                    }),
+                };
+
+                match attempt {
+                    Attempt::First(delivery) => {
+                        if let Some(failure) = failure {
+                            retries::record_failure(db.as_ref(), delivery, &repo, failure).await;
+                        }
+                    }
+                    Attempt::Retry(failed) => {
+                        retries::record_retry(db.as_ref(), *failed, failure).await;
+                    }
+                }
+            }
+            Job::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
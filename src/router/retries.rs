@@ -0,0 +1,246 @@
+//! Durable retries for failed dispatches
+//!
+//! When an event cannot be posted (Discord down, rate limited, missing
+//! permissions), the raw payload is stored together with the destinations
+//! that failed. A background loop retries it with exponential backoff; after
+//! `MAX_ATTEMPTS` attempts it is dead-lettered and only retried when a
+//! moderator or admin replays it.
+//!
+//! The webhook delivery stays claimed while it is retried, so a redelivery
+//! from GitHub cannot post it twice. Dead-lettered deliveries release their
+//! claim, so they can also be redelivered from GitHub's webhook settings;
+//! once a replay of one goes through, the delivery is claimed again.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::error::{Error, Result};
+use crate::github::deliveries;
+use crate::github::events::ParsedEvent;
+use crate::router::dispatch::Destination;
+use crate::router::queue::{Delivery, DispatchQueue};
+use crate::storage::{unix_now, Storage};
+
+/// Attempts (including the first dispatch) before a delivery is dead-lettered
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry, doubled after every further attempt
+pub const BASE_BACKOFF_SECS: i64 = 60;
+
+/// Upper bound for the delay between two attempts
+pub const MAX_BACKOFF_SECS: i64 = 60 * 60;
+
+/// How often the retry loop looks for due deliveries
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A webhook delivery that could not be dispatched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedDelivery {
+    pub id: String,
+    /// `X-GitHub-Delivery` GUID, if GitHub sent one
+    pub delivery_id: Option<String>,
+    /// `X-GitHub-Event` header
+    pub event_type: String,
+    pub repo: String,
+    /// Raw webhook body
+    pub payload: String,
+    /// Destinations still to post to; `None` retries the whole event
+    pub destinations: Option<Vec<Destination>>,
+    /// Error of the latest attempt
+    pub error: String,
+    pub attempts: u32,
+    /// Unix seconds
+    pub next_attempt_at: i64,
+    /// Out of attempts, only retried by a replay
+    pub dead_lettered: bool,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+/// Why an attempt failed, and what is left to retry
+#[derive(Debug)]
+pub struct AttemptFailure {
+    pub error: String,
+    pub destinations: Option<Vec<Destination>>,
+}
+
+/// Delay after the given number of failed attempts
+pub fn backoff_secs(attempts: u32) -> i64 {
+    let doublings = attempts.saturating_sub(1).min(16);
+    (BASE_BACKOFF_SECS << doublings).min(MAX_BACKOFF_SECS)
+}
+
+/// Store a delivery whose first dispatch failed. If it cannot be stored, its
+/// claim is released so GitHub can redeliver it.
+pub async fn record_failure(
+    db: &dyn Storage,
+    delivery: Delivery,
+    repo: &str,
+    failure: AttemptFailure,
+) {
+    let now = unix_now();
+    let failed = FailedDelivery {
+        id: String::new(),
+        delivery_id: delivery.delivery_id,
+        event_type: delivery.event_type,
+        repo: repo.to_string(),
+        payload: delivery.payload,
+        destinations: failure.destinations,
+        error: failure.error,
+        attempts: 1,
+        next_attempt_at: now + backoff_secs(1),
+        dead_lettered: false,
+        created_at: now,
+    };
+
+    match db.insert_failed_delivery(&failed).await {
+        Ok(id) => warn!(
+            id,
+            repo,
+            error = failed.error,
+            "failed to dispatch event, will retry"
+        ),
+        Err(e) => {
+            error!(repo, error = %e, dispatch_error = failed.error, "failed to store failed delivery");
+            if let Some(delivery_id) = &failed.delivery_id {
+                deliveries::release_delivery(db, delivery_id).await;
+            }
+        }
+    }
+}
+
+/// Record the outcome of a retry: remove the delivery once it went through,
+/// otherwise back off again or dead-letter it.
+pub async fn record_retry(
+    db: &dyn Storage,
+    mut failed: FailedDelivery,
+    failure: Option<AttemptFailure>,
+) {
+    let Some(failure) = failure else {
+        info!(
+            id = failed.id,
+            repo = failed.repo,
+            "retried delivery went through"
+        );
+        // Dead letters gave up their claim when they were moved there
+        if let (true, Some(delivery_id)) = (failed.dead_lettered, &failed.delivery_id) {
+            deliveries::reclaim_delivery(db, delivery_id).await;
+        }
+        if let Err(e) = db.delete_failed_delivery(&failed.id).await {
+            warn!(id = failed.id, error = %e, "failed to remove retried delivery");
+        }
+        return;
+    };
+
+    failed.attempts += 1;
+    failed.error = failure.error;
+    failed.destinations = failure.destinations;
+    failed.next_attempt_at = unix_now() + backoff_secs(failed.attempts);
+
+    if failed.attempts >= MAX_ATTEMPTS && !failed.dead_lettered {
+        failed.dead_lettered = true;
+        error!(
+            id = failed.id,
+            repo = failed.repo,
+            attempts = failed.attempts,
+            error = failed.error,
+            "giving up on delivery, moved to dead letters"
+        );
+        if let Some(delivery_id) = &failed.delivery_id {
+            deliveries::release_delivery(db, delivery_id).await;
+        }
+    } else {
+        warn!(
+            id = failed.id,
+            repo = failed.repo,
+            attempts = failed.attempts,
+            error = failed.error,
+            "retry failed"
+        );
+    }
+
+    if let Err(e) = db.update_failed_delivery(&failed).await {
+        error!(id = failed.id, error = %e, "failed to update failed delivery");
+    }
+}
+
+/// Queue every delivery whose backoff has passed. Each one is pushed back
+/// by its backoff first, so it is not queued twice while its retry runs.
+/// Returns how many deliveries were queued.
+pub async fn retry_due(db: &dyn Storage, queue: &DispatchQueue) -> Result<usize> {
+    let now = unix_now();
+    let mut queued = 0;
+
+    for mut failed in db.list_failed_deliveries().await? {
+        if failed.dead_lettered || failed.next_attempt_at > now {
+            continue;
+        }
+
+        failed.next_attempt_at = now + backoff_secs(failed.attempts);
+        db.update_failed_delivery(&failed).await?;
+
+        let failed_id = failed.id.clone();
+        match enqueue(queue, failed) {
+            Ok(()) => queued += 1,
+            // The worker is busy, the lease runs out and it is tried again later
+            Err(Error::Unavailable(_)) => {}
+            Err(e) => warn!(id = failed_id, error = %e, "failed to queue retry"),
+        }
+    }
+
+    Ok(queued)
+}
+
+/// Retry a delivery right away, dead-lettered or not
+pub async fn replay(db: &dyn Storage, queue: &DispatchQueue, id: &str) -> Result<FailedDelivery> {
+    let mut failed = db
+        .get_failed_delivery(id)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("failed delivery {}", id)))?;
+
+    failed.next_attempt_at = unix_now() + backoff_secs(failed.attempts);
+    db.update_failed_delivery(&failed).await?;
+
+    enqueue(queue, failed.clone())?;
+    info!(id, repo = failed.repo, "replaying failed delivery");
+    Ok(failed)
+}
+
+/// Failed deliveries, oldest first, optionally only those of some repositories
+pub async fn list_failed(
+    db: &dyn Storage,
+    repos: Option<&[String]>,
+) -> Result<Vec<FailedDelivery>> {
+    let failed = db.list_failed_deliveries().await?;
+    Ok(match repos {
+        Some(repos) => failed
+            .into_iter()
+            .filter(|f| repos.iter().any(|r| r.eq_ignore_ascii_case(&f.repo)))
+            .collect(),
+        None => failed,
+    })
+}
+
+/// Retry due deliveries every `RETRY_INTERVAL`. Must be called inside a
+/// Tokio runtime.
+pub fn spawn_retry_loop(db: Arc<dyn Storage>, queue: DispatchQueue) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETRY_INTERVAL);
+        loop {
+            interval.tick().await;
+            match retry_due(db.as_ref(), &queue).await {
+                Ok(0) => {}
+                Ok(queued) => info!(queued, "retrying failed deliveries"),
+                Err(e) => warn!(error = %e, "failed to retry failed deliveries"),
+            }
+        }
+    });
+}
+
+fn enqueue(queue: &DispatchQueue, failed: FailedDelivery) -> Result<()> {
+    let event = ParsedEvent::from_payload(&failed.event_type, failed.payload.as_bytes())
+        .map_err(|e| Error::InvalidPayload(e.to_string()))?;
+    queue.enqueue_retry(event, failed)
+}
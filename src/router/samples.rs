@@ -0,0 +1,366 @@
+//! Sample GitHub payloads for rule dry-runs (`/rules test`)
+//!
+//! The payloads go through `ParsedEvent::from_payload` exactly like real
+//! webhook deliveries, so a dry-run exercises the same parsing path.
+
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::github::events::ParsedEvent;
+
+/// Knobs for the generated payload
+#[derive(Debug, Clone)]
+pub struct SampleOptions {
+    pub actor: String,
+    pub labels: Vec<String>,
+    pub merged: bool,
+    pub branch: String,
+    pub review_state: String,
+}
+
+impl Default for SampleOptions {
+    fn default() -> Self {
+        Self {
+            actor: "octocat".into(),
+            labels: vec![],
+            merged: false,
+            branch: "main".into(),
+            review_state: "approved".into(),
+        }
+    }
+}
+
+/// Event types a sample can be generated for
+pub const SAMPLE_EVENTS: &[&str] = &[
+    "pull_request",
+    "issues",
+    "release",
+    "workflow_run",
+    "push",
+    "issue_comment",
+    "pull_request_review_comment",
+    "pull_request_review",
+    "discussion",
+    "discussion_comment",
+    "star",
+    "fork",
+    "create",
+    "delete",
+    "deployment",
+    "deployment_status",
+    "check_suite",
+    "check_run",
+    "milestone",
+];
+
+/// Build and parse a sample event for `event_key`, e.g. `pull_request.closed`.
+/// Without an action the most common one for the event type is used.
+pub fn sample_event(repo: &str, event_key: &str, options: &SampleOptions) -> Result<ParsedEvent> {
+    let (event_type, action) = match event_key.split_once('.') {
+        Some((event_type, action)) => (event_type, Some(action)),
+        None => (event_key, None),
+    };
+
+    let name = repo.rsplit('/').next().unwrap_or(repo);
+    let repository = json!({
+        "full_name": repo,
+        "name": name,
+    });
+    let sender = json!({ "login": options.actor });
+    let labels: Vec<Value> = options
+        .labels
+        .iter()
+        .map(|name| json!({ "name": name }))
+        .collect();
+    let url = format!("https://github.com/{}", repo);
+    let state = if action == Some("closed") {
+        "closed"
+    } else {
+        "open"
+    };
+    let merged_by = if options.merged {
+        sender.clone()
+    } else {
+        Value::Null
+    };
+    let discussion = json!({
+        "number": 1,
+        "title": "Sample discussion",
+        "html_url": format!("{}/discussions/1", url),
+        "category": { "name": "Q&A" },
+        "answer_html_url": null,
+        "user": sender,
+        "labels": labels,
+    });
+    let comment = json!({
+        "id": 1,
+        "body": "Sample comment",
+        "html_url": format!("{}/discussions/1#discussioncomment-1", url),
+        "user": sender,
+    });
+    // Star and fork events carry the repository counters; 100 is a milestone
+    let repository_stats = json!({
+        "full_name": repo,
+        "name": name,
+        "html_url": url,
+        "stargazers_count": 100,
+        "forks_count": 10,
+    });
+
+    let payload = match event_type {
+        "pull_request" => json!({
+            "action": action.unwrap_or("opened"),
+            "pull_request": {
+                "number": 1,
+                "title": "Sample pull request",
+                "html_url": format!("{}/pull/1", url),
+                "merged": options.merged,
+                "labels": labels,
+                "state": state,
+                "user": sender,
+                "merged_by": merged_by,
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "issues" => json!({
+            "action": action.unwrap_or("opened"),
+            "issue": {
+                "number": 1,
+                "title": "Sample issue",
+                "html_url": format!("{}/issues/1", url),
+                "labels": labels,
+                "state": state,
+                "user": sender,
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "release" => json!({
+            "action": action.unwrap_or("published"),
+            "release": {
+                "tag_name": "v0.0.0-sample",
+                "name": "Sample release",
+                "body": "Release notes",
+                "html_url": format!("{}/releases/tag/v0.0.0-sample", url),
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "workflow_run" => json!({
+            "action": action.unwrap_or("completed"),
+            "workflow_run": {
+                "id": 1,
+                "name": "CI",
+                "status": "completed",
+                "conclusion": "success",
+                "html_url": format!("{}/actions/runs/1", url),
+                "head_branch": options.branch,
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "push" => json!({
+            "ref": format!("refs/heads/{}", options.branch),
+            "before": "0000000000000000000000000000000000000000",
+            "after": "1111111111111111111111111111111111111111",
+            "compare": format!("{}/compare/000000000000...111111111111", url),
+            "commits": [{
+                "id": "1111111111111111111111111111111111111111",
+                "message": "Sample commit",
+                "url": format!("{}/commit/1111111111111111111111111111111111111111", url),
+                "author": { "name": options.actor, "username": options.actor },
+            }],
+            "repository": repository,
+            "sender": sender,
+        }),
+        "issue_comment" => json!({
+            "action": action.unwrap_or("created"),
+            "issue": {
+                "number": 1,
+                "title": "Sample issue",
+                "html_url": format!("{}/issues/1", url),
+                "labels": labels,
+            },
+            "comment": {
+                "id": 1,
+                "body": "Sample comment",
+                "html_url": format!("{}/issues/1#issuecomment-1", url),
+                "user": sender,
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "pull_request_review_comment" => json!({
+            "action": action.unwrap_or("created"),
+            "comment": {
+                "id": 1,
+                "body": "Sample review comment",
+                "html_url": format!("{}/pull/1#discussion_r1", url),
+                "user": sender,
+            },
+            "pull_request": {
+                "number": 1,
+                "title": "Sample pull request",
+                "html_url": format!("{}/pull/1", url),
+                "merged": options.merged,
+                "labels": labels,
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "pull_request_review" => json!({
+            "action": action.unwrap_or("submitted"),
+            "review": {
+                "id": 1,
+                "state": options.review_state,
+                "body": "Sample review",
+                "html_url": format!("{}/pull/1#pullrequestreview-1", url),
+                "user": sender,
+            },
+            "pull_request": {
+                "number": 1,
+                "title": "Sample pull request",
+                "html_url": format!("{}/pull/1", url),
+                "merged": options.merged,
+                "labels": labels,
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "discussion" => {
+            let action = action.unwrap_or("created");
+            let mut discussion = discussion;
+            let answer = if action == "answered" {
+                discussion["answer_html_url"] = comment["html_url"].clone();
+                comment
+            } else {
+                Value::Null
+            };
+            json!({
+                "action": action,
+                "discussion": discussion,
+                "answer": answer,
+                "repository": repository,
+                "sender": sender,
+            })
+        }
+        "discussion_comment" => json!({
+            "action": action.unwrap_or("created"),
+            "comment": comment,
+            "discussion": discussion,
+            "repository": repository,
+            "sender": sender,
+        }),
+        "deployment" | "deployment_status" => {
+            let deployment = json!({
+                "id": 1,
+                "sha": "0123456789abcdef0123456789abcdef01234567",
+                "ref": options.branch,
+                "environment": "production",
+                "description": null,
+                "creator": sender,
+            });
+            json!({
+                "action": action.unwrap_or("created"),
+                "deployment": deployment,
+                "deployment_status": {
+                    "id": 1,
+                    "state": "success",
+                    "description": null,
+                    "environment_url": format!("https://{}.example.com", name),
+                    "target_url": null,
+                    "log_url": format!("{}/actions/runs/1", url),
+                    "creator": sender,
+                },
+                "repository": repository,
+                "sender": sender,
+            })
+        }
+        // An external CI app, since GitHub Actions checks are ignored
+        "check_suite" | "check_run" => {
+            let sha = "0123456789abcdef0123456789abcdef01234567";
+            let app = json!({ "id": 1, "slug": "sample-ci", "name": "Sample CI" });
+            json!({
+                "action": action.unwrap_or("completed"),
+                "check_suite": {
+                    "id": 1,
+                    "head_branch": options.branch,
+                    "head_sha": sha,
+                    "status": "completed",
+                    "conclusion": "success",
+                    "app": app,
+                },
+                "check_run": {
+                    "id": 1,
+                    "name": "build",
+                    "head_sha": sha,
+                    "status": "completed",
+                    "conclusion": "success",
+                    "html_url": format!("{}/runs/1", url),
+                    "app": app,
+                    "check_suite": { "id": 1, "head_branch": options.branch },
+                },
+                "repository": repository,
+                "sender": sender,
+            })
+        }
+        "milestone" => json!({
+            "action": action.unwrap_or("created"),
+            "milestone": {
+                "number": 1,
+                "title": "v1.0",
+                "description": "First stable release",
+                "html_url": format!("{}/milestone/1", url),
+                "state": state,
+                "open_issues": 4,
+                "closed_issues": 6,
+                "due_on": "2030-01-01T08:00:00Z",
+            },
+            "repository": repository,
+            "sender": sender,
+        }),
+        "star" => json!({
+            "action": action.unwrap_or("created"),
+            "repository": repository_stats,
+            "sender": sender,
+        }),
+        "fork" => {
+            let fork = format!("{}/{}", options.actor, name);
+            json!({
+                "forkee": {
+                    "full_name": fork,
+                    "html_url": format!("https://github.com/{}", fork),
+                    "owner": sender,
+                },
+                "repository": repository_stats,
+                "sender": sender,
+            })
+        }
+        // The "action" of a ref event is its ref type, e.g. `create.tag`
+        "create" | "delete" => {
+            let ref_type = action.unwrap_or("tag");
+            let ref_name = if ref_type == "tag" {
+                "v1.0.0"
+            } else {
+                options.branch.as_str()
+            };
+            json!({
+                "ref": ref_name,
+                "ref_type": ref_type,
+                "repository": repository,
+                "sender": sender,
+            })
+        }
+        other => {
+            return Err(Error::InvalidPayload(format!(
+                "No sample payload for `{}`. Supported: {}",
+                other,
+                SAMPLE_EVENTS.join(", ")
+            )))
+        }
+    };
+
+    let body = serde_json::to_vec(&payload).map_err(|e| Error::Internal(e.to_string()))?;
+    ParsedEvent::from_payload(event_type, &body).map_err(|e| Error::InvalidPayload(e.to_string()))
+}
@@ -1,9 +1,19 @@
+use async_trait::async_trait;
 use convex::{ConvexClient, FunctionResult, Value};
+use maplit::btreemap;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::error::{Error, Result};
+use crate::github::events::Milestone;
+use crate::governance::projects::Project;
+use crate::governance::rules::{Rule, RuleActions, RuleConditions};
+use crate::governance::server_config::ServerConfig;
+use crate::router::dispatch::Destination;
+use crate::router::messages::PostedMessage;
+use crate::router::retries::FailedDelivery;
+use crate::storage::Storage;
 
 /// Wrapper around ConvexClient for ByteHub operations
 #[derive(Clone)]
@@ -81,3 +91,661 @@ impl ConvexDb {
         }
     }
 }
+
+/// Parse mutation result that returns { success: true/false, id?, error? }
+fn parse_mutation_result(result: &serde_json::Value) -> Result<Option<String>> {
+    let success = result
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if success {
+        let id = result
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        Ok(id)
+    } else {
+        let error = result
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error");
+        // Return dedicated error variant for type-safe matching (case-insensitive)
+        if error.to_lowercase().contains("already exists") {
+            Err(Error::ProjectAlreadyExists(error.to_string()))
+        } else {
+            Err(Error::InvalidPayload(error.to_string()))
+        }
+    }
+}
+
+/// Convert a serializable value into a Convex argument
+fn to_convex<T: serde::Serialize>(value: &T) -> Result<Value> {
+    let json = serde_json::to_value(value).map_err(|e| Error::Internal(e.to_string()))?;
+    Value::try_from(json).map_err(|e| Error::Internal(e.to_string()))
+}
+
+/// Mutations that return { success: false } when the document is missing
+fn expect_found(result: &serde_json::Value, what: &str) -> Result<()> {
+    let success = result
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !success {
+        return Err(Error::NotFound(format!("{} not found", what)));
+    }
+    Ok(())
+}
+
+/// Milestone document; Convex returns its counts as floats
+#[derive(serde::Deserialize)]
+struct StoredMilestone {
+    number: f64,
+    title: String,
+    description: Option<String>,
+    html_url: String,
+    state: String,
+    open_issues: f64,
+    closed_issues: f64,
+    due_on: Option<String>,
+}
+
+/// Failed delivery document; Convex returns its numbers as floats
+#[derive(serde::Deserialize)]
+struct StoredFailedDelivery {
+    _id: String,
+    delivery_id: Option<String>,
+    event_type: String,
+    repo: String,
+    payload: String,
+    destinations: Option<Vec<Destination>>,
+    error: String,
+    attempts: f64,
+    next_attempt_at: f64,
+    dead_lettered: bool,
+    created_at: f64,
+}
+
+impl From<StoredFailedDelivery> for FailedDelivery {
+    fn from(d: StoredFailedDelivery) -> Self {
+        Self {
+            id: d._id,
+            delivery_id: d.delivery_id,
+            event_type: d.event_type,
+            repo: d.repo,
+            payload: d.payload,
+            destinations: d.destinations,
+            error: d.error,
+            attempts: d.attempts as u32,
+            next_attempt_at: d.next_attempt_at as i64,
+            dead_lettered: d.dead_lettered,
+            created_at: d.created_at as i64,
+        }
+    }
+}
+
+/// Arguments shared by `failedDeliveries:insert` and `failedDeliveries:update`.
+/// `destinations` is left out to retry the whole event, which `update` clears.
+fn retry_fields(delivery: &FailedDelivery) -> Result<BTreeMap<String, Value>> {
+    let mut args = btreemap! {
+        "error".into() => Value::String(delivery.error.clone()),
+        "attempts".into() => Value::Float64(delivery.attempts as f64),
+        "next_attempt_at".into() => Value::Float64(delivery.next_attempt_at as f64),
+        "dead_lettered".into() => Value::Boolean(delivery.dead_lettered),
+    };
+    if let Some(destinations) = &delivery.destinations {
+        args.insert("destinations".into(), to_convex(destinations)?);
+    }
+    Ok(args)
+}
+
+impl From<StoredMilestone> for Milestone {
+    fn from(m: StoredMilestone) -> Self {
+        Self {
+            number: m.number as u64,
+            title: m.title,
+            description: m.description,
+            html_url: m.html_url,
+            state: m.state,
+            open_issues: m.open_issues as u64,
+            closed_issues: m.closed_issues as u64,
+            due_on: m.due_on,
+        }
+    }
+}
+
+fn whitelist_args(guild_id: &str, github_username: &str) -> BTreeMap<String, Value> {
+    btreemap! {
+        "guild_id".into() => Value::String(guild_id.to_string()),
+        "github_username".into() => Value::String(github_username.to_string()),
+    }
+}
+
+fn repo_args(github_repo: &str) -> BTreeMap<String, Value> {
+    btreemap! {
+        "github_repo".into() => Value::String(github_repo.to_string()),
+    }
+}
+
+#[async_trait]
+impl Storage for ConvexDb {
+    async fn submit_project(&self, github_repo: &str) -> Result<String> {
+        let result = self
+            .mutation("projects:submit", repo_args(github_repo))
+            .await?;
+
+        parse_mutation_result(&result)?
+            .ok_or_else(|| Error::InvalidPayload("Expected ID from submit".into()))
+    }
+
+    async fn approve_project(&self, github_repo: &str) -> Result<()> {
+        let result = self
+            .mutation("projects:approve", repo_args(github_repo))
+            .await?;
+
+        parse_mutation_result(&result)?;
+        Ok(())
+    }
+
+    async fn approve_project_with_forum(
+        &self,
+        github_repo: &str,
+        forum_channel_id: &str,
+        guild_id: &str,
+    ) -> Result<()> {
+        let result = self
+            .mutation(
+                "projects:approveWithForum",
+                btreemap! {
+                    "github_repo".into() => Value::String(github_repo.to_string()),
+                    "forum_channel_id".into() => Value::String(forum_channel_id.to_string()),
+                    "guild_id".into() => Value::String(guild_id.to_string()),
+                },
+            )
+            .await?;
+
+        parse_mutation_result(&result)?;
+        Ok(())
+    }
+
+    async fn deny_project(&self, github_repo: &str) -> Result<()> {
+        let result = self
+            .mutation("projects:deny", repo_args(github_repo))
+            .await?;
+
+        // deny returns { success: true/false }
+        let success = result
+            .get("success")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !success {
+            let error = result
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Project not found");
+            return Err(Error::NotFound(error.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn get_approved_project(&self, github_repo: &str) -> Result<Option<Project>> {
+        let result = self
+            .query("projects:getApproved", repo_args(github_repo))
+            .await?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        let project: Project = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse project: {}", e)))?;
+
+        Ok(Some(project))
+    }
+
+    async fn get_project(&self, github_repo: &str) -> Result<Option<Project>> {
+        let result = self.query("projects:get", repo_args(github_repo)).await?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        let project: Project = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse project: {}", e)))?;
+
+        Ok(Some(project))
+    }
+
+    async fn list_projects_by_guild(&self, guild_id: &str) -> Result<Vec<Project>> {
+        let result = self
+            .query(
+                "projects:listByGuild",
+                btreemap! {
+                    "guild_id".into() => Value::String(guild_id.to_string()),
+                },
+            )
+            .await?;
+
+        let projects: Vec<Project> = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse projects: {}", e)))?;
+
+        Ok(projects)
+    }
+
+    async fn update_forum_id(&self, github_repo: &str, forum_id: &str) -> Result<()> {
+        self.mutation(
+            "projects:updateForumId",
+            btreemap! {
+                "github_repo".into() => Value::String(github_repo.to_string()),
+                "forum_id".into() => Value::String(forum_id.to_string()),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_thread_id(&self, github_repo: &str, thread_id: &str) -> Result<()> {
+        self.mutation(
+            "projects:updateThreadId",
+            btreemap! {
+                "github_repo".into() => Value::String(github_repo.to_string()),
+                "thread_id".into() => Value::String(thread_id.to_string()),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn rename_project(&self, github_repo: &str, new_github_repo: &str) -> Result<()> {
+        let result = self
+            .mutation(
+                "projects:rename",
+                btreemap! {
+                    "github_repo".into() => Value::String(github_repo.to_string()),
+                    "new_github_repo".into() => Value::String(new_github_repo.to_string()),
+                },
+            )
+            .await?;
+
+        match parse_mutation_result(&result) {
+            Ok(_) => Ok(()),
+            Err(Error::InvalidPayload(error)) => Err(Error::NotFound(error)),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_server_config(&self, guild_id: &str) -> Result<Option<ServerConfig>> {
+        let result = self
+            .query(
+                "serverConfig:get",
+                btreemap! {
+                    "guild_id".into() => Value::String(guild_id.to_string()),
+                },
+            )
+            .await?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        let config: ServerConfig = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse config: {}", e)))?;
+
+        Ok(Some(config))
+    }
+
+    async fn save_server_config(
+        &self,
+        guild_id: &str,
+        announcements_id: &str,
+        github_forum_id: &str,
+    ) -> Result<()> {
+        self.mutation(
+            "serverConfig:save",
+            btreemap! {
+                "guild_id".into() => Value::String(guild_id.to_string()),
+                "announcements_id".into() => Value::String(announcements_id.to_string()),
+                "github_forum_id".into() => Value::String(github_forum_id.to_string()),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_moderator_channel_id(&self, guild_id: &str, channel_id: &str) -> Result<()> {
+        let result = self
+            .mutation(
+                "serverConfig:updateModeratorChannel",
+                btreemap! {
+                    "guild_id".into() => Value::String(guild_id.to_string()),
+                    "moderator_channel_id".into() => Value::String(channel_id.to_string()),
+                },
+            )
+            .await?;
+
+        expect_found(&result, "Server config")
+    }
+
+    async fn list_rules(&self, project_id: &str) -> Result<Vec<Rule>> {
+        let result = self
+            .query(
+                "rules:getByProject",
+                btreemap! {
+                    "project_id".into() => Value::String(project_id.to_string()),
+                },
+            )
+            .await?;
+
+        let mut rules: Vec<Rule> = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse rules: {}", e)))?;
+        rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+
+        Ok(rules)
+    }
+
+    async fn create_rule(
+        &self,
+        project_id: &str,
+        priority: i64,
+        conditions: &RuleConditions,
+        actions: &RuleActions,
+    ) -> Result<String> {
+        let result = self
+            .mutation(
+                "rules:create",
+                btreemap! {
+                    "project_id".into() => Value::String(project_id.to_string()),
+                    "priority".into() => Value::Float64(priority as f64),
+                    "conditions".into() => to_convex(conditions)?,
+                    "actions".into() => to_convex(actions)?,
+                },
+            )
+            .await?;
+
+        result
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| Error::Database("rules:create returned no id".into()))
+    }
+
+    async fn delete_rule(&self, rule_id: &str) -> Result<()> {
+        let result = self
+            .mutation(
+                "rules:remove",
+                btreemap! {
+                    "rule_id".into() => Value::String(rule_id.to_string()),
+                },
+            )
+            .await?;
+
+        expect_found(&result, "Rule")
+    }
+
+    async fn update_rule_priority(&self, rule_id: &str, priority: i64) -> Result<()> {
+        let result = self
+            .mutation(
+                "rules:updatePriority",
+                btreemap! {
+                    "rule_id".into() => Value::String(rule_id.to_string()),
+                    "priority".into() => Value::Float64(priority as f64),
+                },
+            )
+            .await?;
+
+        expect_found(&result, "Rule")
+    }
+
+    async fn add_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()> {
+        self.mutation("whitelist:add", whitelist_args(guild_id, github_username))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()> {
+        let result = self
+            .mutation(
+                "whitelist:remove",
+                whitelist_args(guild_id, github_username),
+            )
+            .await?;
+
+        expect_found(&result, "Whitelisted user")
+    }
+
+    async fn list_whitelisted_users(&self, guild_id: &str) -> Result<Vec<String>> {
+        let result = self
+            .query(
+                "whitelist:listByGuild",
+                btreemap! {
+                    "guild_id".into() => Value::String(guild_id.to_string()),
+                },
+            )
+            .await?;
+
+        let mut users: Vec<String> = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse whitelist: {}", e)))?;
+        users.sort();
+
+        Ok(users)
+    }
+
+    async fn is_whitelisted(&self, guild_id: &str, github_username: &str) -> Result<bool> {
+        let result = self
+            .query(
+                "whitelist:isWhitelisted",
+                whitelist_args(guild_id, github_username),
+            )
+            .await?;
+
+        Ok(result.as_bool().unwrap_or(false))
+    }
+
+    async fn get_posted_message(&self, item_key: &str) -> Result<Option<PostedMessage>> {
+        let result = self
+            .query(
+                "messages:get",
+                btreemap! {
+                    "item_key".into() => Value::String(item_key.to_string()),
+                },
+            )
+            .await?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        let message: PostedMessage = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse posted message: {}", e)))?;
+
+        Ok(Some(message))
+    }
+
+    async fn save_posted_message(
+        &self,
+        item_key: &str,
+        channel_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        self.mutation(
+            "messages:save",
+            btreemap! {
+                "item_key".into() => Value::String(item_key.to_string()),
+                "channel_id".into() => Value::String(channel_id.to_string()),
+                "message_id".into() => Value::String(message_id.to_string()),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn rename_posted_messages(&self, from: &str, to: &str) -> Result<()> {
+        self.mutation(
+            "messages:renameRepo",
+            btreemap! {
+                "from".into() => Value::String(from.to_string()),
+                "to".into() => Value::String(to.to_string()),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn save_milestone(&self, project_id: &str, milestone: &Milestone) -> Result<()> {
+        let mut args = btreemap! {
+            "project_id".into() => Value::String(project_id.to_string()),
+            "number".into() => Value::Float64(milestone.number as f64),
+            "title".into() => Value::String(milestone.title.clone()),
+            "html_url".into() => Value::String(milestone.html_url.clone()),
+            "state".into() => Value::String(milestone.state.clone()),
+            "open_issues".into() => Value::Float64(milestone.open_issues as f64),
+            "closed_issues".into() => Value::Float64(milestone.closed_issues as f64),
+        };
+        if let Some(description) = &milestone.description {
+            args.insert("description".into(), Value::String(description.clone()));
+        }
+        if let Some(due_on) = &milestone.due_on {
+            args.insert("due_on".into(), Value::String(due_on.clone()));
+        }
+
+        self.mutation("milestones:save", args).await?;
+        Ok(())
+    }
+
+    async fn delete_milestone(&self, project_id: &str, number: u64) -> Result<()> {
+        self.mutation(
+            "milestones:remove",
+            btreemap! {
+                "project_id".into() => Value::String(project_id.to_string()),
+                "number".into() => Value::Float64(number as f64),
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn list_milestones(&self, project_id: &str) -> Result<Vec<Milestone>> {
+        let result = self
+            .query(
+                "milestones:listByProject",
+                btreemap! {
+                    "project_id".into() => Value::String(project_id.to_string()),
+                },
+            )
+            .await?;
+
+        let milestones: Vec<StoredMilestone> = serde_json::from_value(result)
+            .map_err(|e| Error::InvalidPayload(format!("Failed to parse milestones: {}", e)))?;
+
+        Ok(milestones.into_iter().map(Milestone::from).collect())
+    }
+
+    async fn record_delivery(&self, delivery_id: &str, ttl_secs: u64) -> Result<bool> {
+        let result = self
+            .mutation(
+                "deliveries:record",
+                btreemap! {
+                    "delivery_id".into() => Value::String(delivery_id.to_string()),
+                    "ttl_ms".into() => Value::Float64((ttl_secs * 1000) as f64),
+                },
+            )
+            .await?;
+
+        result
+            .as_bool()
+            .ok_or_else(|| Error::Database("deliveries:record returned no result".into()))
+    }
+
+    async fn forget_delivery(&self, delivery_id: &str) -> Result<()> {
+        self.mutation(
+            "deliveries:forget",
+            btreemap! {
+                "delivery_id".into() => Value::String(delivery_id.to_string()),
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn insert_failed_delivery(&self, delivery: &FailedDelivery) -> Result<String> {
+        let mut args = retry_fields(delivery)?;
+        args.insert(
+            "event_type".into(),
+            Value::String(delivery.event_type.clone()),
+        );
+        args.insert("repo".into(), Value::String(delivery.repo.clone()));
+        args.insert("payload".into(), Value::String(delivery.payload.clone()));
+        args.insert(
+            "created_at".into(),
+            Value::Float64(delivery.created_at as f64),
+        );
+        if let Some(delivery_id) = &delivery.delivery_id {
+            args.insert("delivery_id".into(), Value::String(delivery_id.clone()));
+        }
+
+        let result = self.mutation("failedDeliveries:insert", args).await?;
+
+        result
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| Error::Database("failedDeliveries:insert returned no id".into()))
+    }
+
+    async fn update_failed_delivery(&self, delivery: &FailedDelivery) -> Result<()> {
+        let mut args = retry_fields(delivery)?;
+        args.insert("id".into(), Value::String(delivery.id.clone()));
+
+        let result = self.mutation("failedDeliveries:update", args).await?;
+        expect_found(&result, "Failed delivery")
+    }
+
+    async fn delete_failed_delivery(&self, id: &str) -> Result<()> {
+        let result = self
+            .mutation(
+                "failedDeliveries:remove",
+                btreemap! {
+                    "id".into() => Value::String(id.to_string()),
+                },
+            )
+            .await?;
+
+        expect_found(&result, "Failed delivery")
+    }
+
+    async fn get_failed_delivery(&self, id: &str) -> Result<Option<FailedDelivery>> {
+        let result = self
+            .query(
+                "failedDeliveries:get",
+                btreemap! {
+                    "id".into() => Value::String(id.to_string()),
+                },
+            )
+            .await?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        let delivery: StoredFailedDelivery = serde_json::from_value(result).map_err(|e| {
+            Error::InvalidPayload(format!("Failed to parse failed delivery: {}", e))
+        })?;
+        Ok(Some(delivery.into()))
+    }
+
+    async fn list_failed_deliveries(&self) -> Result<Vec<FailedDelivery>> {
+        let result = self.query("failedDeliveries:list", BTreeMap::new()).await?;
+
+        let deliveries: Vec<StoredFailedDelivery> =
+            serde_json::from_value(result).map_err(|e| {
+                Error::InvalidPayload(format!("Failed to parse failed deliveries: {}", e))
+            })?;
+
+        Ok(deliveries.into_iter().map(FailedDelivery::from).collect())
+    }
+}
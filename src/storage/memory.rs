@@ -0,0 +1,464 @@
+//! In-memory backend for tests and `--dev` mode
+//!
+//! Reproduces the semantics of the Convex functions in `convex/` (repo
+//! lowercasing, duplicate detection, default rules on approval, config
+//! upserts) without any persistence. Everything is lost on restart.
+
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::error::{Error, Result};
+use crate::github::events::Milestone;
+use crate::governance::projects::Project;
+use crate::governance::rules::{self, Rule, RuleActions, RuleConditions};
+use crate::governance::server_config::ServerConfig;
+use crate::router::messages::PostedMessage;
+use crate::router::retries::FailedDelivery;
+use crate::storage::{unix_now, Storage};
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    projects: Vec<Project>,
+    rules: Vec<Rule>,
+    server_configs: Vec<ServerConfig>,
+    /// (guild_id, lowercase username)
+    whitelist: HashSet<(String, String)>,
+    posted_messages: HashMap<String, PostedMessage>,
+    /// (project_id, number)
+    milestones: BTreeMap<(String, u64), Milestone>,
+    /// Delivery GUID -> expiry (Unix seconds)
+    deliveries: HashMap<String, i64>,
+    /// In insertion order, so oldest first
+    failed_deliveries: Vec<FailedDelivery>,
+}
+
+impl State {
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        self.next_id.to_string()
+    }
+
+    fn project_mut(&mut self, github_repo: &str) -> Option<&mut Project> {
+        let github_repo = github_repo.to_lowercase();
+        self.projects
+            .iter_mut()
+            .find(|p| p.github_repo == github_repo)
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryDb {
+    state: Mutex<State>,
+}
+
+impl MemoryDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, State>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::Database("in-memory storage poisoned".into()))
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryDb {
+    async fn submit_project(&self, github_repo: &str) -> Result<String> {
+        let github_repo = github_repo.to_lowercase();
+        let mut state = self.state()?;
+
+        if state.project_mut(&github_repo).is_some() {
+            return Err(Error::ProjectAlreadyExists(
+                "Project already exists".to_string(),
+            ));
+        }
+
+        let id = state.new_id();
+        let name = github_repo
+            .rsplit('/')
+            .next()
+            .unwrap_or(&github_repo)
+            .to_string();
+        state.projects.push(Project {
+            id: id.clone(),
+            name,
+            github_repo,
+            forum_channel_id: String::new(),
+            thread_id: None,
+            guild_id: String::new(),
+            is_approved: false,
+        });
+
+        Ok(id)
+    }
+
+    async fn approve_project(&self, github_repo: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let project = state
+            .project_mut(github_repo)
+            .ok_or_else(|| Error::InvalidPayload("Project not found".into()))?;
+        project.is_approved = true;
+        Ok(())
+    }
+
+    async fn approve_project_with_forum(
+        &self,
+        github_repo: &str,
+        forum_channel_id: &str,
+        guild_id: &str,
+    ) -> Result<()> {
+        let mut state = self.state()?;
+        let project = state
+            .project_mut(github_repo)
+            .ok_or_else(|| Error::InvalidPayload("Project not found".into()))?;
+        project.is_approved = true;
+        project.forum_channel_id = forum_channel_id.to_string();
+        project.guild_id = guild_id.to_string();
+        let project_id = project.id.clone();
+
+        // Create default rules for this project
+        for (priority, (conditions, actions)) in rules::default_rules().into_iter().enumerate() {
+            let id = state.new_id();
+            state.rules.push(Rule {
+                id,
+                project_id: project_id.clone(),
+                priority: priority as i64,
+                conditions,
+                actions,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn deny_project(&self, github_repo: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let project_id = state
+            .project_mut(github_repo)
+            .map(|p| p.id.clone())
+            .ok_or_else(|| Error::NotFound("Project not found".into()))?;
+
+        // Delete associated rules first
+        state.rules.retain(|r| r.project_id != project_id);
+        state.milestones.retain(|(id, _), _| *id != project_id);
+        state.projects.retain(|p| p.id != project_id);
+        Ok(())
+    }
+
+    async fn get_approved_project(&self, github_repo: &str) -> Result<Option<Project>> {
+        let project = self.get_project(github_repo).await?;
+        Ok(project.filter(|p| p.is_approved))
+    }
+
+    async fn get_project(&self, github_repo: &str) -> Result<Option<Project>> {
+        Ok(self.state()?.project_mut(github_repo).map(|p| p.clone()))
+    }
+
+    async fn list_projects_by_guild(&self, guild_id: &str) -> Result<Vec<Project>> {
+        Ok(self
+            .state()?
+            .projects
+            .iter()
+            .filter(|p| p.guild_id == guild_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn update_forum_id(&self, github_repo: &str, forum_id: &str) -> Result<()> {
+        if let Some(project) = self.state()?.project_mut(github_repo) {
+            project.forum_channel_id = forum_id.to_string();
+        }
+        Ok(())
+    }
+
+    async fn update_thread_id(&self, github_repo: &str, thread_id: &str) -> Result<()> {
+        if let Some(project) = self.state()?.project_mut(github_repo) {
+            project.thread_id = Some(thread_id.to_string());
+        }
+        Ok(())
+    }
+
+    async fn rename_project(&self, github_repo: &str, new_github_repo: &str) -> Result<()> {
+        let new_github_repo = new_github_repo.to_lowercase();
+        let mut state = self.state()?;
+
+        if state.project_mut(&new_github_repo).is_some() {
+            return Err(Error::ProjectAlreadyExists(
+                "Project already exists".to_string(),
+            ));
+        }
+
+        let project = state
+            .project_mut(github_repo)
+            .ok_or_else(|| Error::NotFound("Project not found".into()))?;
+        project.name = new_github_repo
+            .rsplit('/')
+            .next()
+            .unwrap_or(&new_github_repo)
+            .to_string();
+        project.github_repo = new_github_repo;
+        Ok(())
+    }
+
+    async fn get_server_config(&self, guild_id: &str) -> Result<Option<ServerConfig>> {
+        Ok(self
+            .state()?
+            .server_configs
+            .iter()
+            .find(|c| c.guild_id == guild_id)
+            .cloned())
+    }
+
+    async fn save_server_config(
+        &self,
+        guild_id: &str,
+        announcements_id: &str,
+        github_forum_id: &str,
+    ) -> Result<()> {
+        let mut state = self.state()?;
+        match state
+            .server_configs
+            .iter_mut()
+            .find(|c| c.guild_id == guild_id)
+        {
+            Some(existing) => {
+                existing.announcements_id = announcements_id.to_string();
+                existing.github_forum_id = github_forum_id.to_string();
+            }
+            None => state.server_configs.push(ServerConfig {
+                guild_id: guild_id.to_string(),
+                announcements_id: announcements_id.to_string(),
+                github_forum_id: github_forum_id.to_string(),
+                moderator_channel_id: None,
+            }),
+        }
+        Ok(())
+    }
+
+    async fn update_moderator_channel_id(&self, guild_id: &str, channel_id: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let config = state
+            .server_configs
+            .iter_mut()
+            .find(|c| c.guild_id == guild_id)
+            .ok_or_else(|| Error::NotFound("Server config not found".into()))?;
+        config.moderator_channel_id = Some(channel_id.to_string());
+        Ok(())
+    }
+
+    async fn list_rules(&self, project_id: &str) -> Result<Vec<Rule>> {
+        let mut project_rules: Vec<Rule> = self
+            .state()?
+            .rules
+            .iter()
+            .filter(|r| r.project_id == project_id)
+            .cloned()
+            .collect();
+        project_rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        Ok(project_rules)
+    }
+
+    async fn create_rule(
+        &self,
+        project_id: &str,
+        priority: i64,
+        conditions: &RuleConditions,
+        actions: &RuleActions,
+    ) -> Result<String> {
+        let mut state = self.state()?;
+        let id = state.new_id();
+        state.rules.push(Rule {
+            id: id.clone(),
+            project_id: project_id.to_string(),
+            priority,
+            conditions: conditions.clone(),
+            actions: actions.clone(),
+        });
+        Ok(id)
+    }
+
+    async fn delete_rule(&self, rule_id: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let before = state.rules.len();
+        state.rules.retain(|r| r.id != rule_id);
+        if state.rules.len() == before {
+            return Err(Error::NotFound("Rule not found".into()));
+        }
+        Ok(())
+    }
+
+    async fn update_rule_priority(&self, rule_id: &str, priority: i64) -> Result<()> {
+        let mut state = self.state()?;
+        let rule = state
+            .rules
+            .iter_mut()
+            .find(|r| r.id == rule_id)
+            .ok_or_else(|| Error::NotFound("Rule not found".into()))?;
+        rule.priority = priority;
+        Ok(())
+    }
+
+    async fn add_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()> {
+        self.state()?
+            .whitelist
+            .insert((guild_id.to_string(), github_username.to_lowercase()));
+        Ok(())
+    }
+
+    async fn remove_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()> {
+        let key = (guild_id.to_string(), github_username.to_lowercase());
+        if !self.state()?.whitelist.remove(&key) {
+            return Err(Error::NotFound("User is not whitelisted".into()));
+        }
+        Ok(())
+    }
+
+    async fn list_whitelisted_users(&self, guild_id: &str) -> Result<Vec<String>> {
+        let mut users: Vec<String> = self
+            .state()?
+            .whitelist
+            .iter()
+            .filter(|(guild, _)| guild == guild_id)
+            .map(|(_, user)| user.clone())
+            .collect();
+        users.sort();
+        Ok(users)
+    }
+
+    async fn is_whitelisted(&self, guild_id: &str, github_username: &str) -> Result<bool> {
+        let key = (guild_id.to_string(), github_username.to_lowercase());
+        Ok(self.state()?.whitelist.contains(&key))
+    }
+
+    async fn get_posted_message(&self, item_key: &str) -> Result<Option<PostedMessage>> {
+        Ok(self.state()?.posted_messages.get(item_key).cloned())
+    }
+
+    async fn save_posted_message(
+        &self,
+        item_key: &str,
+        channel_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        self.state()?.posted_messages.insert(
+            item_key.to_string(),
+            PostedMessage {
+                item_key: item_key.to_string(),
+                channel_id: channel_id.to_string(),
+                message_id: message_id.to_string(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn rename_posted_messages(&self, from: &str, to: &str) -> Result<()> {
+        let from = format!("{}/", from);
+        let mut state = self.state()?;
+        let keys: Vec<String> = state
+            .posted_messages
+            .keys()
+            .filter(|k| k.starts_with(&from))
+            .cloned()
+            .collect();
+        for key in keys {
+            if let Some(mut message) = state.posted_messages.remove(&key) {
+                message.item_key = format!("{}/{}", to, &key[from.len()..]);
+                state
+                    .posted_messages
+                    .insert(message.item_key.clone(), message);
+            }
+        }
+        Ok(())
+    }
+
+    async fn save_milestone(&self, project_id: &str, milestone: &Milestone) -> Result<()> {
+        self.state()?.milestones.insert(
+            (project_id.to_string(), milestone.number),
+            milestone.clone(),
+        );
+        Ok(())
+    }
+
+    async fn delete_milestone(&self, project_id: &str, number: u64) -> Result<()> {
+        self.state()?
+            .milestones
+            .remove(&(project_id.to_string(), number));
+        Ok(())
+    }
+
+    async fn list_milestones(&self, project_id: &str) -> Result<Vec<Milestone>> {
+        Ok(self
+            .state()?
+            .milestones
+            .iter()
+            .filter(|((id, _), _)| id == project_id)
+            .map(|(_, m)| m.clone())
+            .collect())
+    }
+
+    async fn record_delivery(&self, delivery_id: &str, ttl_secs: u64) -> Result<bool> {
+        let now = unix_now();
+        let mut state = self.state()?;
+        state.deliveries.retain(|_, expires_at| *expires_at > now);
+        if state.deliveries.contains_key(delivery_id) {
+            return Ok(false);
+        }
+        state
+            .deliveries
+            .insert(delivery_id.to_string(), now + ttl_secs as i64);
+        Ok(true)
+    }
+
+    async fn forget_delivery(&self, delivery_id: &str) -> Result<()> {
+        self.state()?.deliveries.remove(delivery_id);
+        Ok(())
+    }
+
+    async fn insert_failed_delivery(&self, delivery: &FailedDelivery) -> Result<String> {
+        let mut state = self.state()?;
+        let id = state.new_id();
+        state.failed_deliveries.push(FailedDelivery {
+            id: id.clone(),
+            ..delivery.clone()
+        });
+        Ok(id)
+    }
+
+    async fn update_failed_delivery(&self, delivery: &FailedDelivery) -> Result<()> {
+        let mut state = self.state()?;
+        let stored = state
+            .failed_deliveries
+            .iter_mut()
+            .find(|d| d.id == delivery.id)
+            .ok_or_else(|| Error::NotFound("Failed delivery not found".into()))?;
+        *stored = delivery.clone();
+        Ok(())
+    }
+
+    async fn delete_failed_delivery(&self, id: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let before = state.failed_deliveries.len();
+        state.failed_deliveries.retain(|d| d.id != id);
+        if state.failed_deliveries.len() == before {
+            return Err(Error::NotFound("Failed delivery not found".into()));
+        }
+        Ok(())
+    }
+
+    async fn get_failed_delivery(&self, id: &str) -> Result<Option<FailedDelivery>> {
+        Ok(self
+            .state()?
+            .failed_deliveries
+            .iter()
+            .find(|d| d.id == id)
+            .cloned())
+    }
+
+    async fn list_failed_deliveries(&self) -> Result<Vec<FailedDelivery>> {
+        Ok(self.state()?.failed_deliveries.clone())
+    }
+}
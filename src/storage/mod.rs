@@ -1 +1,135 @@
 pub mod convex;
+pub mod memory;
+pub mod sqlite;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{Config, StorageBackend};
+use crate::error::Result;
+use crate::github::events::Milestone;
+use crate::governance::projects::Project;
+use crate::governance::rules::{Rule, RuleActions, RuleConditions};
+use crate::governance::server_config::ServerConfig;
+use crate::router::messages::PostedMessage;
+use crate::router::retries::FailedDelivery;
+
+/// Persistence backend for ByteHub.
+///
+/// Every governance module goes through this trait, so the dispatcher and the
+/// slash command handlers can run against any implementation (Convex in
+/// production, a fake in tests).
+#[async_trait]
+pub trait Storage: Send + Sync {
+    // Projects
+    async fn submit_project(&self, github_repo: &str) -> Result<String>;
+    async fn approve_project(&self, github_repo: &str) -> Result<()>;
+    async fn approve_project_with_forum(
+        &self,
+        github_repo: &str,
+        forum_channel_id: &str,
+        guild_id: &str,
+    ) -> Result<()>;
+    async fn deny_project(&self, github_repo: &str) -> Result<()>;
+    async fn get_approved_project(&self, github_repo: &str) -> Result<Option<Project>>;
+    async fn get_project(&self, github_repo: &str) -> Result<Option<Project>>;
+    async fn list_projects_by_guild(&self, guild_id: &str) -> Result<Vec<Project>>;
+    async fn update_forum_id(&self, github_repo: &str, forum_id: &str) -> Result<()>;
+    async fn update_thread_id(&self, github_repo: &str, thread_id: &str) -> Result<()>;
+    /// Move a project to a new `owner/name` key, keeping its id and rules.
+    /// Returns `NotFound` if the project does not exist and
+    /// `ProjectAlreadyExists` if the new key is taken.
+    async fn rename_project(&self, github_repo: &str, new_github_repo: &str) -> Result<()>;
+
+    // Server config
+    async fn get_server_config(&self, guild_id: &str) -> Result<Option<ServerConfig>>;
+    async fn save_server_config(
+        &self,
+        guild_id: &str,
+        announcements_id: &str,
+        github_forum_id: &str,
+    ) -> Result<()>;
+    /// Returns `NotFound` if the server has no config yet
+    async fn update_moderator_channel_id(&self, guild_id: &str, channel_id: &str) -> Result<()>;
+
+    // Rules
+    /// All rules of a project, highest priority first
+    async fn list_rules(&self, project_id: &str) -> Result<Vec<Rule>>;
+    /// Insert a rule and return its id
+    async fn create_rule(
+        &self,
+        project_id: &str,
+        priority: i64,
+        conditions: &RuleConditions,
+        actions: &RuleActions,
+    ) -> Result<String>;
+    /// Returns `NotFound` if the rule does not exist
+    async fn delete_rule(&self, rule_id: &str) -> Result<()>;
+    /// Returns `NotFound` if the rule does not exist
+    async fn update_rule_priority(&self, rule_id: &str, priority: i64) -> Result<()>;
+
+    // Whitelist (per guild, usernames are case-insensitive)
+    async fn add_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()>;
+    /// Returns `NotFound` if the user is not whitelisted in the guild
+    async fn remove_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()>;
+    /// Whitelisted usernames of a guild, sorted alphabetically
+    async fn list_whitelisted_users(&self, guild_id: &str) -> Result<Vec<String>>;
+    async fn is_whitelisted(&self, guild_id: &str, github_username: &str) -> Result<bool>;
+
+    // Posted messages (edited in place by later events for the same item)
+    async fn get_posted_message(&self, item_key: &str) -> Result<Option<PostedMessage>>;
+    /// Insert or replace the message recorded for an item
+    async fn save_posted_message(
+        &self,
+        item_key: &str,
+        channel_id: &str,
+        message_id: &str,
+    ) -> Result<()>;
+    /// Move the messages recorded under `{from}/...` to `{to}/...`, e.g.
+    /// after a repository rename
+    async fn rename_posted_messages(&self, from: &str, to: &str) -> Result<()>;
+
+    // Milestones (latest snapshot from webhook payloads, removed with the project)
+    /// Insert or replace the snapshot of a project's milestone
+    async fn save_milestone(&self, project_id: &str, milestone: &Milestone) -> Result<()>;
+    /// Does nothing if the milestone is unknown
+    async fn delete_milestone(&self, project_id: &str, number: u64) -> Result<()>;
+    /// All stored milestones of a project, by number
+    async fn list_milestones(&self, project_id: &str) -> Result<Vec<Milestone>>;
+
+    // Webhook deliveries (`X-GitHub-Delivery` GUIDs, for de-duplication)
+    /// Remember a delivery for `ttl_secs`. Returns `false` if it is already
+    /// remembered. Expired deliveries are pruned along the way.
+    async fn record_delivery(&self, delivery_id: &str, ttl_secs: u64) -> Result<bool>;
+    /// Does nothing if the delivery is unknown
+    async fn forget_delivery(&self, delivery_id: &str) -> Result<()>;
+
+    // Failed deliveries (retried with backoff, then kept as dead letters)
+    /// Insert a failed delivery and return its id (`delivery.id` is ignored)
+    async fn insert_failed_delivery(&self, delivery: &FailedDelivery) -> Result<String>;
+    /// Returns `NotFound` if the failed delivery does not exist
+    async fn update_failed_delivery(&self, delivery: &FailedDelivery) -> Result<()>;
+    /// Returns `NotFound` if the failed delivery does not exist
+    async fn delete_failed_delivery(&self, id: &str) -> Result<()>;
+    async fn get_failed_delivery(&self, id: &str) -> Result<Option<FailedDelivery>>;
+    /// All failed deliveries, oldest first
+    async fn list_failed_deliveries(&self) -> Result<Vec<FailedDelivery>>;
+}
+
+/// Seconds since the Unix epoch, for expiry timestamps
+pub(crate) fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Connect to the backend selected in the config
+pub async fn connect(config: &Config) -> Result<Arc<dyn Storage>> {
+    match config.storage_backend {
+        StorageBackend::Convex => Ok(Arc::new(convex::ConvexDb::new(&config.convex_url).await?)),
+        StorageBackend::Sqlite => Ok(Arc::new(sqlite::SqliteDb::open(&config.database_path)?)),
+        StorageBackend::Memory => Ok(Arc::new(memory::MemoryDb::new())),
+    }
+}
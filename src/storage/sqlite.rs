@@ -0,0 +1,990 @@
+//! Embedded SQLite backend
+//!
+//! Mirrors the tables in `convex/schema.ts` so ByteHub can run as a single
+//! binary without a Convex deployment. The schema is versioned through
+//! `PRAGMA user_version` and migrated on startup.
+
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+use crate::error::{Error, Result};
+use crate::github::events::Milestone;
+use crate::governance::projects::Project;
+use crate::governance::rules::{self, Rule, RuleActions, RuleConditions};
+use crate::governance::server_config::ServerConfig;
+use crate::router::messages::PostedMessage;
+use crate::router::retries::FailedDelivery;
+use crate::storage::{unix_now, Storage};
+
+/// Schema migrations, applied in order. The position in this list (1-based)
+/// is the schema version, so existing entries must never be edited or reordered.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema (mirrors convex/schema.ts)
+    "CREATE TABLE projects (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        github_repo TEXT NOT NULL UNIQUE,
+        forum_channel_id TEXT NOT NULL DEFAULT '',
+        thread_id TEXT,
+        guild_id TEXT NOT NULL DEFAULT '',
+        is_approved INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX projects_by_guild ON projects (guild_id);
+
+    CREATE TABLE whitelist (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        github_username TEXT NOT NULL UNIQUE
+    );
+
+    CREATE TABLE moderators (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        discord_id TEXT NOT NULL UNIQUE
+    );
+
+    CREATE TABLE rules (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        project_id INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+        priority INTEGER NOT NULL,
+        conditions TEXT NOT NULL,
+        actions TEXT NOT NULL
+    );
+    CREATE INDEX rules_by_project ON rules (project_id);
+
+    CREATE TABLE server_config (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        guild_id TEXT NOT NULL UNIQUE,
+        announcements_id TEXT NOT NULL,
+        github_forum_id TEXT NOT NULL
+    );",
+    // 2: scope the whitelist per guild. The whitelist was shared by every
+    // server, so existing entries are copied to each server ByteHub knows.
+    "ALTER TABLE whitelist RENAME TO unscoped_whitelist;
+    CREATE TABLE whitelist (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        guild_id TEXT NOT NULL,
+        github_username TEXT NOT NULL,
+        UNIQUE (guild_id, github_username)
+    );
+    INSERT OR IGNORE INTO whitelist (guild_id, github_username)
+        SELECT guilds.guild_id, unscoped_whitelist.github_username
+        FROM unscoped_whitelist
+        CROSS JOIN (
+            SELECT guild_id FROM server_config
+            UNION SELECT guild_id FROM projects WHERE guild_id != ''
+        ) AS guilds;
+    DROP TABLE unscoped_whitelist;",
+    // 3: private moderator channel for security alerts
+    "ALTER TABLE server_config ADD COLUMN moderator_channel_id TEXT;",
+    // 4: Discord messages that are edited when their item changes
+    "CREATE TABLE posted_messages (
+        item_key TEXT PRIMARY KEY,
+        channel_id TEXT NOT NULL,
+        message_id TEXT NOT NULL
+    );",
+    // 5: latest milestone snapshots for `/milestone`
+    "CREATE TABLE milestones (
+        project_id INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+        number INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        description TEXT,
+        html_url TEXT NOT NULL,
+        state TEXT NOT NULL,
+        open_issues INTEGER NOT NULL,
+        closed_issues INTEGER NOT NULL,
+        due_on TEXT,
+        PRIMARY KEY (project_id, number)
+    );",
+    // 6: webhook deliveries seen recently, for de-duplication
+    "CREATE TABLE deliveries (
+        delivery_id TEXT PRIMARY KEY,
+        expires_at INTEGER NOT NULL
+    );
+    CREATE INDEX deliveries_by_expiry ON deliveries (expires_at);",
+    // 7: deliveries that failed to dispatch, retried and then dead-lettered
+    "CREATE TABLE failed_deliveries (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        delivery_id TEXT,
+        event_type TEXT NOT NULL,
+        repo TEXT NOT NULL,
+        payload TEXT NOT NULL,
+        destinations TEXT,
+        error TEXT NOT NULL,
+        attempts INTEGER NOT NULL,
+        next_attempt_at INTEGER NOT NULL,
+        dead_lettered INTEGER NOT NULL DEFAULT 0,
+        created_at INTEGER NOT NULL
+    );",
+];
+
+/// SQLite-backed storage. The connection is shared behind a mutex and every
+/// query runs on the blocking thread pool.
+#[derive(Clone)]
+pub struct SqliteDb {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteDb {
+    /// Open (or create) the database at `path` and apply pending migrations.
+    /// Use `":memory:"` for a throwaway database.
+    pub fn open(path: &str) -> Result<Self> {
+        let mut conn = Connection::open(path)
+            .map_err(|e| Error::Database(format!("Failed to open SQLite database: {}", e)))?;
+
+        conn.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")
+            .map_err(db_err)?;
+        migrate(&mut conn)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Current schema version of the database
+    pub async fn schema_version(&self) -> Result<usize> {
+        self.call(|conn| user_version(conn)).await
+    }
+
+    /// Run a closure against the connection on the blocking thread pool
+    async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| Error::Database("SQLite connection poisoned".into()))?;
+            f(&mut conn)
+        })
+        .await
+        .map_err(|e| Error::Internal(format!("SQLite task failed: {}", e)))?
+    }
+}
+
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Database(e.to_string())
+}
+
+fn user_version(conn: &Connection) -> Result<usize> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
+        .map(|v| v as usize)
+        .map_err(db_err)
+}
+
+/// Apply every migration newer than the database's `user_version`
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let current = user_version(conn)?;
+    if current > MIGRATIONS.len() {
+        return Err(Error::Database(format!(
+            "Database schema version {} is newer than this binary supports ({})",
+            current,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+        let version = i + 1;
+        let tx = conn.transaction().map_err(db_err)?;
+        if version == 2 {
+            warn_unscoped_whitelist(&tx)?;
+        }
+        tx.execute_batch(sql)
+            .map_err(|e| Error::Database(format!("Migration {} failed: {}", version, e)))?;
+        tx.pragma_update(None, "user_version", version as i64)
+            .map_err(db_err)?;
+        tx.commit().map_err(db_err)?;
+        tracing::info!(version, "applied SQLite migration");
+    }
+
+    Ok(())
+}
+
+/// Migration 2 copies the whitelist to every known server; without any, its
+/// entries are lost and have to be re-added with `/whitelist-user add`
+fn warn_unscoped_whitelist(conn: &Connection) -> Result<()> {
+    let dropped: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM whitelist
+             WHERE NOT EXISTS (SELECT 1 FROM server_config)
+               AND NOT EXISTS (SELECT 1 FROM projects WHERE guild_id != '')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(db_err)?;
+    if dropped > 0 {
+        tracing::warn!(
+            dropped,
+            "no Discord server to move the whitelist to, dropping its entries"
+        );
+    }
+    Ok(())
+}
+
+const PROJECT_COLUMNS: &str =
+    "id, name, github_repo, forum_channel_id, thread_id, guild_id, is_approved";
+
+fn project_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Project> {
+    Ok(Project {
+        id: row.get::<_, i64>(0)?.to_string(),
+        name: row.get(1)?,
+        github_repo: row.get(2)?,
+        forum_channel_id: row.get(3)?,
+        thread_id: row.get(4)?,
+        guild_id: row.get(5)?,
+        is_approved: row.get(6)?,
+    })
+}
+
+fn find_project(conn: &Connection, github_repo: &str) -> Result<Option<Project>> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM projects WHERE github_repo = ?1",
+            PROJECT_COLUMNS
+        ),
+        params![github_repo.to_lowercase()],
+        project_from_row,
+    )
+    .optional()
+    .map_err(db_err)
+}
+
+fn parse_id(id: &str) -> Result<i64> {
+    id.parse()
+        .map_err(|_| Error::InvalidPayload(format!("Invalid id: {}", id)))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| Error::Internal(e.to_string()))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: &str) -> Result<T> {
+    serde_json::from_str(value)
+        .map_err(|e| Error::InvalidPayload(format!("Failed to parse stored JSON: {}", e)))
+}
+
+const FAILED_DELIVERY_COLUMNS: &str = "id, delivery_id, event_type, repo, payload, destinations, \
+     error, attempts, next_attempt_at, dead_lettered, created_at";
+
+/// Read a `failed_deliveries` row; `destinations` is decoded by the caller
+fn failed_delivery_from_row(
+    row: &rusqlite::Row<'_>,
+) -> rusqlite::Result<(FailedDelivery, Option<String>)> {
+    Ok((
+        FailedDelivery {
+            id: row.get::<_, i64>(0)?.to_string(),
+            delivery_id: row.get(1)?,
+            event_type: row.get(2)?,
+            repo: row.get(3)?,
+            payload: row.get(4)?,
+            destinations: None,
+            error: row.get(6)?,
+            attempts: row.get::<_, i64>(7)? as u32,
+            next_attempt_at: row.get(8)?,
+            dead_lettered: row.get(9)?,
+            created_at: row.get(10)?,
+        },
+        row.get(5)?,
+    ))
+}
+
+fn with_destinations(
+    (mut delivery, destinations): (FailedDelivery, Option<String>),
+) -> Result<FailedDelivery> {
+    delivery.destinations = destinations.as_deref().map(from_json).transpose()?;
+    Ok(delivery)
+}
+
+fn insert_rule(
+    conn: &Connection,
+    project_id: i64,
+    priority: i64,
+    conditions: &RuleConditions,
+    actions: &RuleActions,
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO rules (project_id, priority, conditions, actions) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            project_id,
+            priority,
+            to_json(conditions)?,
+            to_json(actions)?
+        ],
+    )
+    .map_err(db_err)?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn load_rules(conn: &Connection, project_id: i64) -> Result<Vec<Rule>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, project_id, priority, conditions, actions FROM rules
+             WHERE project_id = ?1 ORDER BY priority DESC",
+        )
+        .map_err(db_err)?;
+
+    let rows = stmt
+        .query_map(params![project_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })
+        .map_err(db_err)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(db_err)?;
+
+    rows.into_iter()
+        .map(|(id, project_id, priority, conditions, actions)| {
+            Ok(Rule {
+                id: id.to_string(),
+                project_id: project_id.to_string(),
+                priority,
+                conditions: from_json(&conditions)?,
+                actions: from_json(&actions)?,
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Storage for SqliteDb {
+    async fn submit_project(&self, github_repo: &str) -> Result<String> {
+        let github_repo = github_repo.to_lowercase();
+        self.call(move |conn| {
+            if find_project(conn, &github_repo)?.is_some() {
+                return Err(Error::ProjectAlreadyExists(
+                    "Project already exists".to_string(),
+                ));
+            }
+
+            let name = github_repo.rsplit('/').next().unwrap_or(&github_repo);
+            conn.execute(
+                "INSERT INTO projects (name, github_repo) VALUES (?1, ?2)",
+                params![name, github_repo],
+            )
+            .map_err(db_err)?;
+
+            Ok(conn.last_insert_rowid().to_string())
+        })
+        .await
+    }
+
+    async fn approve_project(&self, github_repo: &str) -> Result<()> {
+        let github_repo = github_repo.to_lowercase();
+        self.call(move |conn| {
+            let updated = conn
+                .execute(
+                    "UPDATE projects SET is_approved = 1 WHERE github_repo = ?1",
+                    params![github_repo],
+                )
+                .map_err(db_err)?;
+            if updated == 0 {
+                return Err(Error::InvalidPayload("Project not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn approve_project_with_forum(
+        &self,
+        github_repo: &str,
+        forum_channel_id: &str,
+        guild_id: &str,
+    ) -> Result<()> {
+        let github_repo = github_repo.to_lowercase();
+        let forum_channel_id = forum_channel_id.to_string();
+        let guild_id = guild_id.to_string();
+        self.call(move |conn| {
+            let tx = conn.transaction().map_err(db_err)?;
+            let project = find_project(&tx, &github_repo)?
+                .ok_or_else(|| Error::InvalidPayload("Project not found".into()))?;
+            let project_id = parse_id(&project.id)?;
+
+            tx.execute(
+                "UPDATE projects SET is_approved = 1, forum_channel_id = ?1, guild_id = ?2
+                 WHERE id = ?3",
+                params![forum_channel_id, guild_id, project_id],
+            )
+            .map_err(db_err)?;
+
+            // Create default rules for this project
+            for (priority, (conditions, actions)) in rules::default_rules().iter().enumerate() {
+                insert_rule(&tx, project_id, priority as i64, conditions, actions)?;
+            }
+
+            tx.commit().map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn deny_project(&self, github_repo: &str) -> Result<()> {
+        let github_repo = github_repo.to_lowercase();
+        self.call(move |conn| {
+            // Associated rules and milestones are removed by ON DELETE CASCADE
+            let deleted = conn
+                .execute(
+                    "DELETE FROM projects WHERE github_repo = ?1",
+                    params![github_repo],
+                )
+                .map_err(db_err)?;
+            if deleted == 0 {
+                return Err(Error::NotFound("Project not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_approved_project(&self, github_repo: &str) -> Result<Option<Project>> {
+        let project = self.get_project(github_repo).await?;
+        Ok(project.filter(|p| p.is_approved))
+    }
+
+    async fn get_project(&self, github_repo: &str) -> Result<Option<Project>> {
+        let github_repo = github_repo.to_string();
+        self.call(move |conn| find_project(conn, &github_repo))
+            .await
+    }
+
+    async fn list_projects_by_guild(&self, guild_id: &str) -> Result<Vec<Project>> {
+        let guild_id = guild_id.to_string();
+        self.call(move |conn| {
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT {} FROM projects WHERE guild_id = ?1 ORDER BY id",
+                    PROJECT_COLUMNS
+                ))
+                .map_err(db_err)?;
+            let projects = stmt
+                .query_map(params![guild_id], project_from_row)
+                .map_err(db_err)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(db_err)?;
+            Ok(projects)
+        })
+        .await
+    }
+
+    async fn update_forum_id(&self, github_repo: &str, forum_id: &str) -> Result<()> {
+        let github_repo = github_repo.to_lowercase();
+        let forum_id = forum_id.to_string();
+        self.call(move |conn| {
+            conn.execute(
+                "UPDATE projects SET forum_channel_id = ?1 WHERE github_repo = ?2",
+                params![forum_id, github_repo],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn update_thread_id(&self, github_repo: &str, thread_id: &str) -> Result<()> {
+        let github_repo = github_repo.to_lowercase();
+        let thread_id = thread_id.to_string();
+        self.call(move |conn| {
+            conn.execute(
+                "UPDATE projects SET thread_id = ?1 WHERE github_repo = ?2",
+                params![thread_id, github_repo],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn rename_project(&self, github_repo: &str, new_github_repo: &str) -> Result<()> {
+        let github_repo = github_repo.to_lowercase();
+        let new_github_repo = new_github_repo.to_lowercase();
+        self.call(move |conn| {
+            if find_project(conn, &new_github_repo)?.is_some() {
+                return Err(Error::ProjectAlreadyExists(
+                    "Project already exists".to_string(),
+                ));
+            }
+
+            let name = new_github_repo
+                .rsplit('/')
+                .next()
+                .unwrap_or(&new_github_repo);
+            let updated = conn
+                .execute(
+                    "UPDATE projects SET github_repo = ?1, name = ?2 WHERE github_repo = ?3",
+                    params![new_github_repo, name, github_repo],
+                )
+                .map_err(db_err)?;
+            if updated == 0 {
+                return Err(Error::NotFound("Project not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_server_config(&self, guild_id: &str) -> Result<Option<ServerConfig>> {
+        let guild_id = guild_id.to_string();
+        self.call(move |conn| {
+            conn.query_row(
+                "SELECT guild_id, announcements_id, github_forum_id, moderator_channel_id
+                 FROM server_config WHERE guild_id = ?1",
+                params![guild_id],
+                |row| {
+                    Ok(ServerConfig {
+                        guild_id: row.get(0)?,
+                        announcements_id: row.get(1)?,
+                        github_forum_id: row.get(2)?,
+                        moderator_channel_id: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(db_err)
+        })
+        .await
+    }
+
+    async fn save_server_config(
+        &self,
+        guild_id: &str,
+        announcements_id: &str,
+        github_forum_id: &str,
+    ) -> Result<()> {
+        let guild_id = guild_id.to_string();
+        let announcements_id = announcements_id.to_string();
+        let github_forum_id = github_forum_id.to_string();
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO server_config (guild_id, announcements_id, github_forum_id)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT (guild_id) DO UPDATE SET
+                    announcements_id = excluded.announcements_id,
+                    github_forum_id = excluded.github_forum_id",
+                params![guild_id, announcements_id, github_forum_id],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn update_moderator_channel_id(&self, guild_id: &str, channel_id: &str) -> Result<()> {
+        let guild_id = guild_id.to_string();
+        let channel_id = channel_id.to_string();
+        self.call(move |conn| {
+            let updated = conn
+                .execute(
+                    "UPDATE server_config SET moderator_channel_id = ?2 WHERE guild_id = ?1",
+                    params![guild_id, channel_id],
+                )
+                .map_err(db_err)?;
+            if updated == 0 {
+                return Err(Error::NotFound("Server config not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn list_rules(&self, project_id: &str) -> Result<Vec<Rule>> {
+        let project_id = parse_id(project_id)?;
+        self.call(move |conn| load_rules(conn, project_id)).await
+    }
+
+    async fn create_rule(
+        &self,
+        project_id: &str,
+        priority: i64,
+        conditions: &RuleConditions,
+        actions: &RuleActions,
+    ) -> Result<String> {
+        let project_id = parse_id(project_id)?;
+        let conditions = conditions.clone();
+        let actions = actions.clone();
+        self.call(move |conn| {
+            let id = insert_rule(conn, project_id, priority, &conditions, &actions)?;
+            Ok(id.to_string())
+        })
+        .await
+    }
+
+    async fn delete_rule(&self, rule_id: &str) -> Result<()> {
+        let rule_id = parse_id(rule_id)?;
+        self.call(move |conn| {
+            let deleted = conn
+                .execute("DELETE FROM rules WHERE id = ?1", params![rule_id])
+                .map_err(db_err)?;
+            if deleted == 0 {
+                return Err(Error::NotFound("Rule not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn update_rule_priority(&self, rule_id: &str, priority: i64) -> Result<()> {
+        let rule_id = parse_id(rule_id)?;
+        self.call(move |conn| {
+            let updated = conn
+                .execute(
+                    "UPDATE rules SET priority = ?1 WHERE id = ?2",
+                    params![priority, rule_id],
+                )
+                .map_err(db_err)?;
+            if updated == 0 {
+                return Err(Error::NotFound("Rule not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn add_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()> {
+        let guild_id = guild_id.to_string();
+        let github_username = github_username.to_lowercase();
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO whitelist (guild_id, github_username) VALUES (?1, ?2)",
+                params![guild_id, github_username],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove_whitelisted_user(&self, guild_id: &str, github_username: &str) -> Result<()> {
+        let guild_id = guild_id.to_string();
+        let github_username = github_username.to_lowercase();
+        self.call(move |conn| {
+            let deleted = conn
+                .execute(
+                    "DELETE FROM whitelist WHERE guild_id = ?1 AND github_username = ?2",
+                    params![guild_id, github_username],
+                )
+                .map_err(db_err)?;
+            if deleted == 0 {
+                return Err(Error::NotFound("User is not whitelisted".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn list_whitelisted_users(&self, guild_id: &str) -> Result<Vec<String>> {
+        let guild_id = guild_id.to_string();
+        self.call(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT github_username FROM whitelist WHERE guild_id = ?1
+                     ORDER BY github_username",
+                )
+                .map_err(db_err)?;
+            let users = stmt
+                .query_map(params![guild_id], |row| row.get::<_, String>(0))
+                .map_err(db_err)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(db_err)?;
+            Ok(users)
+        })
+        .await
+    }
+
+    async fn is_whitelisted(&self, guild_id: &str, github_username: &str) -> Result<bool> {
+        let guild_id = guild_id.to_string();
+        let github_username = github_username.to_lowercase();
+        self.call(move |conn| {
+            conn.query_row(
+                "SELECT 1 FROM whitelist WHERE guild_id = ?1 AND github_username = ?2",
+                params![guild_id, github_username],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+            .map_err(db_err)
+        })
+        .await
+    }
+
+    async fn get_posted_message(&self, item_key: &str) -> Result<Option<PostedMessage>> {
+        let item_key = item_key.to_string();
+        self.call(move |conn| {
+            conn.query_row(
+                "SELECT item_key, channel_id, message_id FROM posted_messages WHERE item_key = ?1",
+                params![item_key],
+                |row| {
+                    Ok(PostedMessage {
+                        item_key: row.get(0)?,
+                        channel_id: row.get(1)?,
+                        message_id: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(db_err)
+        })
+        .await
+    }
+
+    async fn save_posted_message(
+        &self,
+        item_key: &str,
+        channel_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        let item_key = item_key.to_string();
+        let channel_id = channel_id.to_string();
+        let message_id = message_id.to_string();
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO posted_messages (item_key, channel_id, message_id)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT (item_key) DO UPDATE
+                 SET channel_id = excluded.channel_id, message_id = excluded.message_id",
+                params![item_key, channel_id, message_id],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn rename_posted_messages(&self, from: &str, to: &str) -> Result<()> {
+        let from = format!("{}/", from);
+        let to = format!("{}/", to);
+        self.call(move |conn| {
+            conn.execute(
+                "UPDATE OR REPLACE posted_messages
+                 SET item_key = ?2 || substr(item_key, length(?1) + 1)
+                 WHERE substr(item_key, 1, length(?1)) = ?1",
+                params![from, to],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn save_milestone(&self, project_id: &str, milestone: &Milestone) -> Result<()> {
+        let project_id = parse_id(project_id)?;
+        let m = milestone.clone();
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO milestones (project_id, number, title, description, html_url,
+                     state, open_issues, closed_issues, due_on)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT (project_id, number) DO UPDATE
+                 SET title = excluded.title, description = excluded.description,
+                     html_url = excluded.html_url, state = excluded.state,
+                     open_issues = excluded.open_issues,
+                     closed_issues = excluded.closed_issues, due_on = excluded.due_on",
+                params![
+                    project_id,
+                    m.number as i64,
+                    m.title,
+                    m.description,
+                    m.html_url,
+                    m.state,
+                    m.open_issues as i64,
+                    m.closed_issues as i64,
+                    m.due_on
+                ],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_milestone(&self, project_id: &str, number: u64) -> Result<()> {
+        let project_id = parse_id(project_id)?;
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM milestones WHERE project_id = ?1 AND number = ?2",
+                params![project_id, number as i64],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn list_milestones(&self, project_id: &str) -> Result<Vec<Milestone>> {
+        let project_id = parse_id(project_id)?;
+        self.call(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT number, title, description, html_url, state, open_issues,
+                         closed_issues, due_on
+                     FROM milestones WHERE project_id = ?1 ORDER BY number",
+                )
+                .map_err(db_err)?;
+            let milestones = stmt
+                .query_map(params![project_id], |row| {
+                    Ok(Milestone {
+                        number: row.get::<_, i64>(0)? as u64,
+                        title: row.get(1)?,
+                        description: row.get(2)?,
+                        html_url: row.get(3)?,
+                        state: row.get(4)?,
+                        open_issues: row.get::<_, i64>(5)? as u64,
+                        closed_issues: row.get::<_, i64>(6)? as u64,
+                        due_on: row.get(7)?,
+                    })
+                })
+                .map_err(db_err)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(db_err)?;
+            Ok(milestones)
+        })
+        .await
+    }
+
+    async fn record_delivery(&self, delivery_id: &str, ttl_secs: u64) -> Result<bool> {
+        let delivery_id = delivery_id.to_string();
+        let now = unix_now();
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM deliveries WHERE expires_at <= ?1",
+                params![now],
+            )
+            .map_err(db_err)?;
+            let inserted = conn
+                .execute(
+                    "INSERT OR IGNORE INTO deliveries (delivery_id, expires_at) VALUES (?1, ?2)",
+                    params![delivery_id, now + ttl_secs as i64],
+                )
+                .map_err(db_err)?;
+            Ok(inserted == 1)
+        })
+        .await
+    }
+
+    async fn forget_delivery(&self, delivery_id: &str) -> Result<()> {
+        let delivery_id = delivery_id.to_string();
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM deliveries WHERE delivery_id = ?1",
+                params![delivery_id],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn insert_failed_delivery(&self, delivery: &FailedDelivery) -> Result<String> {
+        let d = delivery.clone();
+        let destinations = d.destinations.as_ref().map(to_json).transpose()?;
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO failed_deliveries (delivery_id, event_type, repo, payload,
+                     destinations, error, attempts, next_attempt_at, dead_lettered, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    d.delivery_id,
+                    d.event_type,
+                    d.repo,
+                    d.payload,
+                    destinations,
+                    d.error,
+                    d.attempts as i64,
+                    d.next_attempt_at,
+                    d.dead_lettered,
+                    d.created_at
+                ],
+            )
+            .map_err(db_err)?;
+            Ok(conn.last_insert_rowid().to_string())
+        })
+        .await
+    }
+
+    async fn update_failed_delivery(&self, delivery: &FailedDelivery) -> Result<()> {
+        let id = parse_id(&delivery.id)?;
+        let d = delivery.clone();
+        let destinations = d.destinations.as_ref().map(to_json).transpose()?;
+        self.call(move |conn| {
+            let updated = conn
+                .execute(
+                    "UPDATE failed_deliveries
+                     SET destinations = ?2, error = ?3, attempts = ?4,
+                         next_attempt_at = ?5, dead_lettered = ?6
+                     WHERE id = ?1",
+                    params![
+                        id,
+                        destinations,
+                        d.error,
+                        d.attempts as i64,
+                        d.next_attempt_at,
+                        d.dead_lettered
+                    ],
+                )
+                .map_err(db_err)?;
+            if updated == 0 {
+                return Err(Error::NotFound("Failed delivery not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_failed_delivery(&self, id: &str) -> Result<()> {
+        let id = parse_id(id)?;
+        self.call(move |conn| {
+            let deleted = conn
+                .execute("DELETE FROM failed_deliveries WHERE id = ?1", params![id])
+                .map_err(db_err)?;
+            if deleted == 0 {
+                return Err(Error::NotFound("Failed delivery not found".into()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_failed_delivery(&self, id: &str) -> Result<Option<FailedDelivery>> {
+        let Ok(id) = id.parse::<i64>() else {
+            return Ok(None);
+        };
+        self.call(move |conn| {
+            conn.query_row(
+                &format!(
+                    "SELECT {} FROM failed_deliveries WHERE id = ?1",
+                    FAILED_DELIVERY_COLUMNS
+                ),
+                params![id],
+                failed_delivery_from_row,
+            )
+            .optional()
+            .map_err(db_err)?
+            .map(with_destinations)
+            .transpose()
+        })
+        .await
+    }
+
+    async fn list_failed_deliveries(&self) -> Result<Vec<FailedDelivery>> {
+        self.call(move |conn| {
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT {} FROM failed_deliveries ORDER BY created_at, id",
+                    FAILED_DELIVERY_COLUMNS
+                ))
+                .map_err(db_err)?;
+            let rows = stmt
+                .query_map([], failed_delivery_from_row)
+                .map_err(db_err)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(db_err)?;
+            rows.into_iter().map(with_destinations).collect()
+        })
+        .await
+    }
+}
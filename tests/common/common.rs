@@ -1,48 +1,133 @@
 use async_trait::async_trait;
+use bytehub::config::{Config, StorageBackend};
 use bytehub::error::Result;
-use bytehub::storage::convex::ConvexDb;
-use std::sync::Arc;
+use bytehub::storage::memory::MemoryDb;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use twilight_model::guild::Permissions;
 use twilight_model::id::{
-    marker::{ChannelMarker, GuildMarker},
+    marker::{ChannelMarker, GuildMarker, MessageMarker},
     Id,
 };
 
 use bytehub::discord::client::DiscordInterface;
+use bytehub::governance::{projects, rules};
+use bytehub::router::queue::DispatchQueue;
+use bytehub::storage::Storage;
 use twilight_model::id::marker::ApplicationMarker;
 
 pub struct MockDiscord {
     pub permissions: Permissions,
     #[allow(dead_code)]
     pub fail_all: bool,
+    /// While set, posting embeds fails as if Discord were unavailable
+    #[allow(dead_code)]
+    pub fail_posts: Arc<AtomicBool>,
+    /// Every embed edit, in order
+    #[allow(dead_code)]
+    pub edits: Arc<Mutex<Vec<EditedEmbed>>>,
 }
 
-/// Initialize environment variables from .env file
-fn init_env() {
-    let _ = crabgraph::tls::try_install_default();
-    dotenvy::dotenv().ok();
+/// An `edit_message_embed` call seen by MockDiscord
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct EditedEmbed {
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub title: String,
+    pub description: String,
+    pub color: u32,
+}
+
+impl MockDiscord {
+    fn check_posts(&self) -> Result<()> {
+        if self.fail_posts.load(Ordering::SeqCst) {
+            return Err(bytehub::error::Error::Discord("Mock outage".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Static config for tests (no environment or .env file required)
+pub fn test_config() -> Config {
+    Config {
+        storage_backend: StorageBackend::Memory,
+        convex_url: String::new(),
+        database_path: String::new(),
+        github_webhook_secret: "test_secret".into(),
+        discord_public_key: "test_key".into(),
+        discord_bot_token: "test_token".into(),
+        discord_application_id: 123,
+        discord_invite: None,
+        admin_token: Some("test_admin_token".into()),
+        github_token: None,
+        host: "127.0.0.1".into(),
+        port: 3000,
+        dispatch_workers: 2,
+        dispatch_queue_size: 16,
+    }
 }
 
-/// Create a test AppState with MockDiscord
-/// Note: For tests that need database access, you'll need a real Convex dev connection
+/// Create a test AppState with MockDiscord and in-memory storage
+#[allow(dead_code)]
 pub async fn create_state(permissions: Permissions) -> bytehub::AppState {
-    init_env();
-    let discord = Arc::new(MockDiscord {
+    create_state_with(MockDiscord {
         permissions,
         fail_all: false,
-    });
+        fail_posts: Arc::new(AtomicBool::new(false)),
+        edits: Arc::default(),
+    })
+    .await
+}
+
+/// Approve a project with only the rules every project was first given, as
+/// for projects approved before more default rules were added
+#[allow(dead_code)]
+pub async fn approve_with_original_rules(db: &dyn Storage, repo: &str, guild_id: &str) {
+    const ORIGINAL_RULES: [&str; 4] = [
+        "workflow_run.completed",
+        "release.published",
+        "pull_request.closed",
+        "issues.opened",
+    ];
 
-    // Create a real ConvexDb connection for testing
-    // This requires CONVEX_URL to be set in the environment
-    let convex_url = std::env::var("CONVEX_URL").expect("CONVEX_URL required for tests");
-    let db = ConvexDb::new(&convex_url)
+    projects::submit_project(db, repo).await.unwrap();
+    projects::approve_project_with_forum(db, repo, "300", guild_id)
         .await
-        .expect("Failed to connect to Convex for tests");
+        .unwrap();
+    let project = projects::get_project(db, repo).await.unwrap().unwrap();
+    for rule in rules::list_rules(db, &project.id).await.unwrap() {
+        let event_type = rule.conditions.event_type.as_deref().unwrap_or_default();
+        if !ORIGINAL_RULES.contains(&event_type) {
+            rules::delete_rule(db, &rule.id).await.unwrap();
+        }
+    }
+    assert_eq!(
+        rules::list_rules(db, &project.id).await.unwrap().len(),
+        ORIGINAL_RULES.len()
+    );
+}
+
+/// Create a test AppState around a configured MockDiscord
+#[allow(dead_code)]
+pub async fn create_state_with(discord: MockDiscord) -> bytehub::AppState {
+    let _ = crabgraph::tls::try_install_default();
+    let discord = Arc::new(discord);
+    let db: Arc<dyn bytehub::storage::Storage> = Arc::new(MemoryDb::new());
+    let config = test_config();
+    let queue = DispatchQueue::start(
+        db.clone(),
+        discord.clone(),
+        config.dispatch_workers,
+        config.dispatch_queue_size,
+    );
 
     bytehub::AppState {
-        config: bytehub::config::Config::from_env(),
+        config,
         db,
         discord,
+        github: None,
+        queue,
     }
 }
 
@@ -60,6 +145,12 @@ impl DiscordInterface for MockDiscord {
     ) -> Result<Id<ChannelMarker>> {
         Ok(Id::new(100))
     }
+    async fn create_moderator_channel(
+        &self,
+        _guild_id: Id<GuildMarker>,
+    ) -> Result<Id<ChannelMarker>> {
+        Ok(Id::new(800))
+    }
     async fn create_github_category(
         &self,
         _guild_id: Id<GuildMarker>,
@@ -140,6 +231,7 @@ impl DiscordInterface for MockDiscord {
         _color: u32,
         _footer: Option<&str>,
     ) -> Result<Id<ChannelMarker>> {
+        self.check_posts()?;
         Ok(Id::new(700))
     }
     async fn send_message(&self, _channel_id: Id<ChannelMarker>, _content: &str) -> Result<()> {
@@ -152,7 +244,26 @@ impl DiscordInterface for MockDiscord {
         _description: &str,
         _color: u32,
         _footer: Option<&str>,
+    ) -> Result<Id<MessageMarker>> {
+        self.check_posts()?;
+        Ok(Id::new(900))
+    }
+    async fn edit_message_embed(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        message_id: Id<MessageMarker>,
+        title: &str,
+        description: &str,
+        color: u32,
+        _footer: Option<&str>,
     ) -> Result<()> {
+        self.edits.lock().unwrap().push(EditedEmbed {
+            channel_id: channel_id.get(),
+            message_id: message_id.get(),
+            title: title.into(),
+            description: description.into(),
+            color,
+        });
         Ok(())
     }
     async fn lock_thread(&self, _thread_id: Id<ChannelMarker>) -> Result<()> {
@@ -161,4 +272,7 @@ impl DiscordInterface for MockDiscord {
     async fn pin_and_lock_thread(&self, _thread_id: Id<ChannelMarker>) -> Result<()> {
         Ok(())
     }
+    async fn rename_channel(&self, _channel_id: Id<ChannelMarker>, _name: &str) -> Result<()> {
+        Ok(())
+    }
 }
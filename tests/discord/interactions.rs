@@ -41,12 +41,13 @@ async fn test_setup_server_success() {
     assert!(result.unwrap().contains("Server setup complete"));
 
     // Verify config saved in DB
-    let config = server_config::get_config(&state.db, &guild_id_str)
+    let config = server_config::get_config(state.db.as_ref(), &guild_id_str)
         .await
         .unwrap();
     assert!(config.is_some());
     let config = config.unwrap();
     assert_eq!(config.announcements_id, "100");
+    assert_eq!(config.moderator_channel_id.as_deref(), Some("800"));
 }
 
 #[tokio::test]
@@ -67,7 +68,9 @@ async fn test_approve_success() {
 
     // 1. Create a project pending approval with unique name
     let repo = unique_name("test_approve");
-    projects::submit_project(&state.db, &repo).await.unwrap();
+    projects::submit_project(state.db.as_ref(), &repo)
+        .await
+        .unwrap();
 
     let guild_id_str = unique_guild_id();
     let guild_id = Some(guild_id_str.clone());
@@ -81,6 +84,7 @@ async fn test_approve_success() {
         options: Some(vec![CommandOption {
             name: "repo".into(),
             value: repo.clone().into(),
+            options: None,
         }]),
     };
 
@@ -90,7 +94,7 @@ async fn test_approve_success() {
     assert!(result.unwrap().contains("approved"));
 
     // 3. Verify project is now approved in DB
-    let project = projects::get_project(&state.db, &repo)
+    let project = projects::get_project(state.db.as_ref(), &repo)
         .await
         .unwrap()
         .unwrap();
@@ -122,6 +126,7 @@ async fn test_approve_permission_denied() {
         options: Some(vec![CommandOption {
             name: "repo".into(),
             value: "test/repo".into(),
+            options: None,
         }]),
     };
 
@@ -145,6 +150,7 @@ async fn test_approve_project_not_found() {
         options: Some(vec![CommandOption {
             name: "repo".into(),
             value: unique_name("nonexistent").into(),
+            options: None,
         }]),
     };
 
@@ -168,8 +174,10 @@ async fn test_approve_already_approved() {
 
     // 1. Submit and approve the project first with unique name
     let repo = unique_name("test_already_approved");
-    projects::submit_project(&state.db, &repo).await.unwrap();
-    let _ = projects::approve_project(&state.db, &repo).await;
+    projects::submit_project(state.db.as_ref(), &repo)
+        .await
+        .unwrap();
+    let _ = projects::approve_project(state.db.as_ref(), &repo).await;
 
     // 2. Try to approve again via the command
     let data = InteractionData {
@@ -177,6 +185,7 @@ async fn test_approve_already_approved() {
         options: Some(vec![CommandOption {
             name: "repo".into(),
             value: repo.clone().into(),
+            options: None,
         }]),
     };
 
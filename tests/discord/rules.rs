@@ -0,0 +1,305 @@
+use bytehub::discord::commands::{handle_rules, CommandOption, InteractionData, Member, User};
+use bytehub::error::Error;
+use bytehub::governance::rules::{RuleActions, RuleConditions};
+use bytehub::governance::{projects, rules};
+use bytehub::storage::Storage;
+use bytehub::AppState;
+use serde_json::{json, Value};
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+const GUILD: &str = "42";
+
+fn moderator() -> Member {
+    Member {
+        user: User { id: "1".into() },
+        roles: vec![],
+        permissions: Some("32".into()), // MANAGE_GUILD
+    }
+}
+
+fn option(name: &str, value: Value) -> CommandOption {
+    CommandOption {
+        name: name.into(),
+        value,
+        options: None,
+    }
+}
+
+/// Build `/rules <subcommand>` interaction data
+fn rules_command(subcommand: &str, options: Vec<CommandOption>) -> InteractionData {
+    InteractionData {
+        name: "rules".into(),
+        options: Some(vec![CommandOption {
+            name: subcommand.into(),
+            value: Value::Null,
+            options: Some(options),
+        }]),
+    }
+}
+
+async fn approved_project(db: &dyn Storage) -> String {
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", GUILD)
+        .await
+        .unwrap();
+    projects::get_project(db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap()
+        .id
+}
+
+async fn state() -> AppState {
+    common::create_state(Permissions::empty()).await
+}
+
+async fn run(state: &AppState, data: &InteractionData) -> String {
+    let member = moderator();
+    handle_rules(state, Some(&member), data, &Some(GUILD.into()))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_add_creates_rule_above_existing() {
+    let state = state().await;
+    let db = state.db.as_ref();
+    let project_id = approved_project(db).await;
+
+    let data = rules_command(
+        "add",
+        vec![
+            option("repo", json!("owner/repo")),
+            option("event", json!("pull_request.opened")),
+            option("labels", json!("security, bug")),
+            option("announce", json!(true)),
+        ],
+    );
+    let response = run(&state, &data).await;
+    assert!(response.contains("Rule added"));
+
+    let project_rules = rules::list_rules(db, &project_id).await.unwrap();
+    assert_eq!(project_rules.len(), rules::default_rules().len() + 1);
+
+    let added = &project_rules[0];
+    assert_eq!(
+        added.conditions.event_type.as_deref(),
+        Some("pull_request.opened")
+    );
+    assert_eq!(
+        added.conditions.labels,
+        Some(vec!["security".to_string(), "bug".to_string()])
+    );
+    assert!(added.actions.post_activity);
+    assert!(!added.actions.post_forum);
+    assert!(added.actions.post_announce);
+    assert!(added.priority > project_rules[1].priority);
+}
+
+#[tokio::test]
+async fn test_add_stays_within_max_priority() {
+    let state = state().await;
+    let db = state.db.as_ref();
+    let project_id = approved_project(db).await;
+
+    let data = rules_command(
+        "add",
+        vec![
+            option("repo", json!("owner/repo")),
+            option("event", json!("push")),
+            option("priority", json!(rules::MAX_PRIORITY)),
+        ],
+    );
+    assert!(run(&state, &data).await.contains("Rule added"));
+
+    // The next rule cannot go above it
+    let data = rules_command(
+        "add",
+        vec![
+            option("repo", json!("owner/repo")),
+            option("event", json!("fork")),
+        ],
+    );
+    let response = run(&state, &data).await;
+    assert!(response.contains("Rule added"), "{}", response);
+    assert!(response.contains(&format!("priority {}", rules::MAX_PRIORITY)));
+
+    let project_rules = rules::list_rules(db, &project_id).await.unwrap();
+    assert_eq!(project_rules.len(), rules::default_rules().len() + 2);
+    assert!(project_rules
+        .iter()
+        .all(|r| r.priority <= rules::MAX_PRIORITY));
+}
+
+#[tokio::test]
+async fn test_add_rejects_invalid_event_type() {
+    let state = state().await;
+    let db = state.db.as_ref();
+    let project_id = approved_project(db).await;
+
+    let data = rules_command(
+        "add",
+        vec![
+            option("repo", json!("owner/repo")),
+            option("event", json!("pull request")),
+        ],
+    );
+    let response = run(&state, &data).await;
+    assert!(response.starts_with("❌"));
+    assert!(response.contains("Invalid event type"));
+
+    let project_rules = rules::list_rules(db, &project_id).await.unwrap();
+    assert_eq!(project_rules.len(), rules::default_rules().len());
+}
+
+#[tokio::test]
+async fn test_list_remove_and_reprioritize() {
+    let state = state().await;
+    let db = state.db.as_ref();
+    let project_id = approved_project(db).await;
+
+    let list = run(
+        &state,
+        &rules_command("list", vec![option("repo", json!("owner/repo"))]),
+    )
+    .await;
+    assert!(list.contains("`#1`"));
+    assert!(list.contains("issues.opened"));
+
+    // Move the lowest-priority rule to the top
+    let before = rules::list_rules(db, &project_id).await.unwrap();
+    let last = before.last().unwrap().clone();
+    run(
+        &state,
+        &rules_command(
+            "priority",
+            vec![
+                option("repo", json!("owner/repo")),
+                option("index", json!(before.len())),
+                option("priority", json!(100)),
+            ],
+        ),
+    )
+    .await;
+    let after = rules::list_rules(db, &project_id).await.unwrap();
+    assert_eq!(after[0].id, last.id);
+    assert_eq!(after[0].priority, 100);
+
+    run(
+        &state,
+        &rules_command(
+            "remove",
+            vec![
+                option("repo", json!("owner/repo")),
+                option("index", json!(1)),
+            ],
+        ),
+    )
+    .await;
+    let remaining = rules::list_rules(db, &project_id).await.unwrap();
+    assert_eq!(remaining.len(), before.len() - 1);
+    assert!(remaining.iter().all(|r| r.id != last.id));
+
+    let out_of_range = run(
+        &state,
+        &rules_command(
+            "remove",
+            vec![
+                option("repo", json!("owner/repo")),
+                option("index", json!(99)),
+            ],
+        ),
+    )
+    .await;
+    assert!(out_of_range.contains("No rule #99"));
+}
+
+#[tokio::test]
+async fn test_rules_require_moderator_and_same_guild() {
+    let state = state().await;
+    approved_project(state.db.as_ref()).await;
+    let data = rules_command("list", vec![option("repo", json!("owner/repo"))]);
+
+    let member = Member {
+        user: User { id: "2".into() },
+        roles: vec![],
+        permissions: Some("0".into()),
+    };
+    let result = handle_rules(&state, Some(&member), &data, &Some(GUILD.into())).await;
+    assert!(matches!(result, Err(Error::Unauthorized)));
+
+    let response = handle_rules(&state, Some(&moderator()), &data, &Some("999".into()))
+        .await
+        .unwrap();
+    assert!(response.contains("not found in this server"));
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_destinations() {
+    let state = state().await;
+    let db = state.db.as_ref();
+    let project_id = approved_project(db).await;
+
+    let conditions = RuleConditions {
+        labels: Some(vec!["security".into()]),
+        ..Default::default()
+    };
+    let actions = RuleActions {
+        post_activity: false,
+        post_forum: false,
+        post_announce: true,
+        template: Some("🔒 {repo} #{number}\n{title}".into()),
+        continue_matching: false,
+    };
+    rules::create_rule(db, &project_id, 100, &conditions, &actions)
+        .await
+        .unwrap();
+
+    let response = run(
+        &state,
+        &rules_command(
+            "test",
+            vec![
+                option("repo", json!("owner/repo")),
+                option("event", json!("pull_request.opened")),
+                option("labels", json!("security")),
+            ],
+        ),
+    )
+    .await;
+    assert!(response.contains("Matched rule (priority 100)"));
+    assert!(response.contains("**→ Announcements**"));
+    assert!(response.contains("🔒 owner/repo #1"));
+    assert!(!response.contains("Activity thread"));
+
+    // Without the label the default rules apply: nothing matches pull_request.opened
+    let response = run(
+        &state,
+        &rules_command(
+            "test",
+            vec![
+                option("repo", json!("owner/repo")),
+                option("event", json!("pull_request.opened")),
+            ],
+        ),
+    )
+    .await;
+    assert!(response.contains("No rule matched"));
+    assert!(response.contains("Activity thread `📦 repo Activity`"));
+
+    let response = run(
+        &state,
+        &rules_command(
+            "test",
+            vec![
+                option("repo", json!("owner/repo")),
+                option("event", json!("gollum")),
+            ],
+        ),
+    )
+    .await;
+    assert!(response.contains("No sample payload"));
+}
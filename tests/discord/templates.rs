@@ -0,0 +1,104 @@
+use bytehub::discord::templates;
+use bytehub::github::events::{
+    Label, ParsedEvent, PullRequest, PullRequestEvent, Release, ReleaseEvent, Repository, User,
+};
+use bytehub::governance::rules::{self, RuleActions, RuleConditions};
+
+fn repository() -> Repository {
+    Repository {
+        full_name: "owner/repo".into(),
+        name: "repo".into(),
+    }
+}
+
+fn pr_event() -> ParsedEvent {
+    ParsedEvent::PullRequest(PullRequestEvent {
+        action: "opened".into(),
+        pull_request: PullRequest {
+            number: 42,
+            title: "Fix login".into(),
+            html_url: "https://github.com/owner/repo/pull/42".into(),
+            merged: Some(false),
+            labels: vec![
+                Label {
+                    name: "security".into(),
+                },
+                Label { name: "bug".into() },
+            ],
+            state: None,
+            user: None,
+            merged_by: None,
+        },
+        repository: repository(),
+        sender: User {
+            login: "octocat".into(),
+        },
+    })
+}
+
+#[test]
+fn test_render_splits_title_and_description() {
+    let template = "🔒 {repo}: #{number} {title}\nLabels: {labels}\nby @{actor} - {url}";
+    let (title, description) = templates::render(template, &pr_event()).unwrap();
+
+    assert_eq!(title, "🔒 owner/repo: #42 Fix login");
+    assert_eq!(
+        description,
+        "Labels: security, bug\nby @octocat - https://github.com/owner/repo/pull/42"
+    );
+}
+
+#[test]
+fn test_render_escaped_braces_and_release_fields() {
+    let event = ParsedEvent::Release(ReleaseEvent {
+        action: "published".into(),
+        release: Release {
+            tag_name: "v1.2.0".into(),
+            name: None,
+            body: Some("Notes".into()),
+            html_url: "https://github.com/owner/repo/releases/v1.2.0".into(),
+        },
+        repository: repository(),
+        sender: User {
+            login: "octocat".into(),
+        },
+    });
+
+    let (title, description) = templates::render("{{{tag}}} {title}\n{body}", &event).unwrap();
+    assert_eq!(title, "{v1.2.0} v1.2.0");
+    assert_eq!(description, "Notes");
+}
+
+#[test]
+fn test_render_fails_for_unavailable_placeholder() {
+    // Pull requests have no branch field, so the dispatcher keeps the built-in layout
+    let err = templates::render("{branch}", &pr_event()).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("not available for pull_request.opened"));
+}
+
+#[test]
+fn test_validate_rejects_bad_templates() {
+    assert!(templates::validate("{repo} #{number}\n{url}").is_ok());
+    assert!(templates::validate("{unknown}").is_err());
+    assert!(templates::validate("{repo").is_err());
+    assert!(templates::validate("repo}").is_err());
+    assert!(templates::validate("\n{url}").is_err());
+}
+
+#[test]
+fn test_rule_validation_checks_template() {
+    let actions = RuleActions {
+        post_activity: true,
+        post_forum: false,
+        post_announce: false,
+        template: Some("{nope}".into()),
+        continue_matching: false,
+    };
+    let result = rules::validate_rule(0, &RuleConditions::default(), &actions);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Unknown placeholder"));
+}
@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::Json;
+use bytehub::error::{Error, Result};
+use bytehub::github::backfill::{backfill, BackfillRequest};
+use bytehub::github::client::{GitHubInterface, HookDelivery, Webhook};
+use bytehub::github::deliveries::DELIVERY_TTL_SECS;
+use bytehub::governance::{milestones, projects};
+use bytehub::router::admin::handle_backfill;
+use bytehub::AppState;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+struct MockGitHub {
+    hooks: Vec<Webhook>,
+    deliveries: Vec<HookDelivery>,
+    payloads: HashMap<u64, String>,
+}
+
+#[async_trait]
+impl GitHubInterface for MockGitHub {
+    async fn list_webhooks(&self, _repo: &str) -> Result<Vec<Webhook>> {
+        Ok(self.hooks.clone())
+    }
+    async fn list_deliveries(
+        &self,
+        _repo: &str,
+        _hook_id: u64,
+        _since: DateTime<Utc>,
+    ) -> Result<Vec<HookDelivery>> {
+        Ok(self.deliveries.clone())
+    }
+    async fn get_delivery_payload(&self, _repo: &str, _hook_id: u64, id: u64) -> Result<String> {
+        self.payloads
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Error::GitHub("delivery gone".into()))
+    }
+}
+
+fn hook(id: u64, url: &str) -> Webhook {
+    Webhook {
+        id,
+        url: url.into(),
+    }
+}
+
+fn milestone_payload(closed_issues: u64) -> String {
+    json!({
+        "action": "edited",
+        "milestone": {
+            "number": 1,
+            "title": "v1.0",
+            "html_url": "https://github.com/owner/repo/milestone/1",
+            "state": "open",
+            "open_issues": 10 - closed_issues,
+            "closed_issues": closed_issues,
+        },
+        "repository": { "full_name": "owner/repo", "name": "repo" },
+        "sender": { "login": "octocat" },
+    })
+    .to_string()
+}
+
+fn delivery(id: u64, guid: &str, event: &str, minutes_ago: i64) -> HookDelivery {
+    HookDelivery {
+        id,
+        guid: guid.into(),
+        delivered_at: Utc::now() - Duration::minutes(minutes_ago),
+        event: event.into(),
+        status_code: 0,
+    }
+}
+
+/// Deliveries newest first, as GitHub lists them
+fn outage() -> MockGitHub {
+    MockGitHub {
+        hooks: vec![
+            hook(1, "https://example.com/other"),
+            hook(2, "https://bytehub.example.com/webhooks/github"),
+        ],
+        deliveries: vec![
+            delivery(10, "too-old", "milestone", 300),
+            delivery(11, "ping", "ping", 50),
+            delivery(12, "first", "milestone", 40),
+            delivery(13, "seen", "milestone", 30),
+            delivery(14, "unknown", "sponsorship", 25),
+            delivery(15, "second", "milestone", 20),
+            // A redelivery of `second`, listed separately by GitHub
+            delivery(16, "second", "milestone", 10),
+            delivery(17, "gone", "milestone", 5),
+        ]
+        .into_iter()
+        .rev()
+        .collect(),
+        payloads: HashMap::from([
+            (10, milestone_payload(1)),
+            (11, "{}".to_string()),
+            (12, milestone_payload(2)),
+            (13, milestone_payload(9)),
+            (14, "{}".to_string()),
+            (15, milestone_payload(3)),
+            (16, milestone_payload(3)),
+        ]),
+    }
+}
+
+async fn approved_state() -> (AppState, String) {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+    let project_id = projects::get_project(db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap()
+        .id;
+    (state, project_id)
+}
+
+fn request(hook_id: Option<u64>) -> BackfillRequest {
+    BackfillRequest {
+        repo: "Owner/Repo".into(),
+        since: Utc::now() - Duration::hours(2),
+        until: None,
+        hook_id,
+    }
+}
+
+#[tokio::test]
+async fn test_backfill_replays_missed_deliveries_once() {
+    let (state, project_id) = approved_state().await;
+    let github = outage();
+
+    // Processed before the outage
+    state
+        .db
+        .record_delivery("seen", DELIVERY_TTL_SECS)
+        .await
+        .unwrap();
+
+    let report = backfill(state.db.as_ref(), &github, &state.queue, &request(None))
+        .await
+        .unwrap();
+    state.queue.flush().await;
+
+    assert_eq!(report.hook_id, 2);
+    assert_eq!(report.found, 6);
+    assert_eq!(report.queued, 2);
+    assert_eq!(report.duplicates, 1);
+    assert_eq!(report.skipped, 2);
+    assert_eq!(report.failed, vec!["gone".to_string()]);
+
+    // Dispatched oldest first, so the latest snapshot wins
+    let open = milestones::list_open_milestones(state.db.as_ref(), &project_id)
+        .await
+        .unwrap();
+    assert_eq!(open[0].closed_issues, 3);
+
+    // Skipped and failed deliveries stay unclaimed, so they can come again
+    for guid in ["unknown", "gone"] {
+        assert!(state
+            .db
+            .record_delivery(guid, DELIVERY_TTL_SECS)
+            .await
+            .unwrap());
+    }
+
+    // A second run finds nothing new
+    let report = backfill(state.db.as_ref(), &github, &state.queue, &request(Some(2)))
+        .await
+        .unwrap();
+    assert_eq!(report.queued, 0);
+    assert_eq!(report.duplicates, 5);
+}
+
+#[tokio::test]
+async fn test_backfill_needs_an_approved_project_and_one_webhook() {
+    let (state, _) = approved_state().await;
+    let mut github = outage();
+
+    let mut unknown_repo = request(None);
+    unknown_repo.repo = "owner/other".into();
+    let result = backfill(state.db.as_ref(), &github, &state.queue, &unknown_repo).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    let mut backwards = request(None);
+    backwards.until = Some(backwards.since - Duration::hours(1));
+    let result = backfill(state.db.as_ref(), &github, &state.queue, &backwards).await;
+    assert!(matches!(result, Err(Error::InvalidPayload(_))));
+
+    github
+        .hooks
+        .push(hook(3, "https://bytehub.example.com/webhooks/github/"));
+    let result = backfill(state.db.as_ref(), &github, &state.queue, &request(None)).await;
+    assert!(matches!(result, Err(Error::InvalidPayload(_))));
+
+    github.hooks.clear();
+    let result = backfill(state.db.as_ref(), &github, &state.queue, &request(None)).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_backfill_endpoint_needs_a_github_token() {
+    let (state, _) = approved_state().await;
+    let mut headers = HeaderMap::new();
+    headers.insert("authorization", "Bearer test_admin_token".parse().unwrap());
+
+    let result = handle_backfill(State(state.clone()), headers.clone(), Json(request(None))).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    let result = handle_backfill(State(state.clone()), HeaderMap::new(), Json(request(None))).await;
+    assert!(matches!(result, Err(Error::Unauthorized)));
+
+    let state = AppState {
+        github: Some(Arc::new(outage())),
+        ..state
+    };
+    let report = handle_backfill(State(state), headers, Json(request(None)))
+        .await
+        .unwrap()
+        .0;
+    assert_eq!(report.queued, 3);
+}
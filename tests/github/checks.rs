@@ -0,0 +1,165 @@
+use bytehub::discord::formatters::{COLOR_FAILURE, COLOR_SUCCESS};
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::rules::{RuleEvaluation, DEFAULT_RULE_ID};
+use bytehub::router::dispatch::{activity_embed, sidebar_embed, Destination, Dispatcher};
+use serde_json::json;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+const SHA: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678";
+
+fn app(slug: &str, name: &str) -> serde_json::Value {
+    json!({ "id": 1, "slug": slug, "name": name })
+}
+
+fn parse(event_type: &str, payload: serde_json::Value) -> ParsedEvent {
+    ParsedEvent::from_payload(event_type, &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn check_suite(app: serde_json::Value, branch: &str, conclusion: &str) -> ParsedEvent {
+    parse(
+        "check_suite",
+        json!({
+            "action": "completed",
+            "check_suite": {
+                "id": 5,
+                "head_branch": branch,
+                "head_sha": SHA,
+                "status": "completed",
+                "conclusion": conclusion,
+                "app": app,
+            },
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "octocat" },
+        }),
+    )
+}
+
+fn check_run(app: serde_json::Value, conclusion: &str) -> ParsedEvent {
+    parse(
+        "check_run",
+        json!({
+            "action": "completed",
+            "check_run": {
+                "id": 9,
+                "name": "build",
+                "head_sha": SHA,
+                "status": "completed",
+                "conclusion": conclusion,
+                "html_url": "https://ci.example.com/runs/9",
+                "app": app,
+                "check_suite": { "id": 5, "head_branch": "main" },
+            },
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "octocat" },
+        }),
+    )
+}
+
+#[test]
+fn test_check_events_parse() {
+    let suite = check_suite(app("circleci-checks", "CircleCI Checks"), "main", "success");
+    assert_eq!(suite.event_key().as_deref(), Some("check_suite.completed"));
+    assert_eq!(suite.repo_full_name(), Some("Owner/Repo"));
+    assert_eq!(suite.branch(), Some("main"));
+    assert!(!suite.is_actions_check());
+
+    let run = check_run(app("github-actions", "GitHub Actions"), "failure");
+    assert_eq!(run.event_key().as_deref(), Some("check_run.completed"));
+    assert_eq!(run.branch(), Some("main"));
+    assert!(run.is_actions_check());
+}
+
+#[tokio::test]
+async fn test_external_check_suite_is_reported() {
+    let state = common::create_state(Permissions::empty()).await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    let event = check_suite(app("circleci-checks", "CircleCI Checks"), "main", "failure");
+    assert!(dispatcher.should_log(&event));
+    assert!(dispatcher.should_post(&event));
+
+    let (title, description, color, _) = activity_embed(&event).unwrap();
+    assert_eq!(title, "❌ CircleCI Checks failure");
+    assert!(description.contains("Commit: `a1b2c3d`"));
+    assert!(description.contains(&format!(
+        "https://github.com/Owner/Repo/commit/{}/checks",
+        SHA
+    )));
+    assert_eq!(color, COLOR_FAILURE);
+
+    let (thread, _, _, color, _) = sidebar_embed(&event).unwrap();
+    assert_eq!(thread, "❌ CI Failed");
+    assert_eq!(color, COLOR_FAILURE);
+
+    // Feature branches are logged but stay out of the forum
+    let event = check_suite(
+        app("circleci-checks", "CircleCI Checks"),
+        "feature",
+        "success",
+    );
+    assert!(dispatcher.should_log(&event));
+    assert!(!dispatcher.should_post(&event));
+
+    // Neutral or cancelled suites are not reported
+    let event = check_suite(
+        app("circleci-checks", "CircleCI Checks"),
+        "main",
+        "cancelled",
+    );
+    assert!(!dispatcher.should_log(&event));
+}
+
+#[tokio::test]
+async fn test_actions_checks_are_not_duplicated() {
+    let state = common::create_state(Permissions::empty()).await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    // workflow_run already reports GitHub Actions
+    let event = check_suite(app("github-actions", "GitHub Actions"), "main", "success");
+    assert!(!dispatcher.should_log(&event));
+    assert!(!dispatcher.should_post(&event));
+    assert_eq!(
+        dispatcher.routing(&event, &RuleEvaluation::default()),
+        Default::default()
+    );
+}
+
+#[tokio::test]
+async fn test_check_runs_need_a_rule() {
+    let state = common::create_state(Permissions::empty()).await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    let event = check_run(app("buildkite", "Buildkite"), "success");
+    assert!(!dispatcher.should_log(&event));
+    assert!(!dispatcher.should_post(&event));
+
+    let (title, description, color, footer) = activity_embed(&event).unwrap();
+    assert_eq!(title, "✅ build success");
+    assert!(description.contains("[View Check](https://ci.example.com/runs/9)"));
+    assert_eq!(color, COLOR_SUCCESS);
+    assert_eq!(footer.as_deref(), Some("Buildkite"));
+}
+
+#[tokio::test]
+async fn test_check_suites_reach_projects_with_older_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    common::approve_with_original_rules(state.db.as_ref(), "owner/repo", "42").await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    let event = check_suite(app("circleci-checks", "CircleCI Checks"), "main", "failure");
+    let plan = dispatcher.plan(&event).await.unwrap().unwrap();
+    assert_eq!(plan.matches[0].rule_id, DEFAULT_RULE_ID);
+    assert!(plan.routing.activity);
+    assert!(plan.messages.iter().any(|m| m.destination
+        == Destination::Sidebar {
+            thread_name: "❌ CI Failed".to_string()
+        }));
+
+    // Checks from GitHub Actions stay skipped
+    let event = check_suite(app("github-actions", "GitHub Actions"), "main", "failure");
+    let plan = dispatcher.plan(&event).await.unwrap().unwrap();
+    assert!(plan.messages.is_empty());
+}
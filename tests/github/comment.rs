@@ -0,0 +1,203 @@
+use bytehub::discord::client::DiscordClient;
+use bytehub::discord::formatters::MAX_COMMENT_EXCERPT_LEN;
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::projects;
+use bytehub::governance::rules::{RuleActions, RuleEvaluation, RuleMatch};
+use bytehub::router::dispatch::{
+    activity_embed, sidebar_embed, Destination, DispatchPlan, Dispatcher,
+};
+use bytehub::storage::memory::MemoryDb;
+use serde_json::json;
+use std::sync::Arc;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn create_test_dispatcher() -> Dispatcher {
+    let _ = crabgraph::tls::try_install_default();
+    let discord = Arc::new(DiscordClient::new("token", 123));
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
+}
+
+fn issue_comment(action: &str, item_url: &str, body: &str, sender: &str) -> ParsedEvent {
+    let payload = json!({
+        "action": action,
+        "issue": {
+            "number": 5,
+            "title": "Crash on startup",
+            "html_url": item_url,
+            "labels": [{ "name": "bug" }],
+        },
+        "comment": {
+            "id": 99,
+            "body": body,
+            "html_url": format!("{}#issuecomment-99", item_url),
+            "user": { "login": sender },
+        },
+        "repository": { "full_name": "test/repo", "name": "repo" },
+        "sender": { "login": sender },
+    });
+    ParsedEvent::from_payload("issue_comment", &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn review_comment(action: &str) -> ParsedEvent {
+    let payload = json!({
+        "action": action,
+        "comment": {
+            "id": 7,
+            "body": "Nit: rename this",
+            "html_url": "https://github.com/test/repo/pull/8#discussion_r7",
+            "user": { "login": "reviewer" },
+        },
+        "pull_request": {
+            "number": 8,
+            "title": "Add feature",
+            "html_url": "https://github.com/test/repo/pull/8",
+            "labels": [{ "name": "bounty" }],
+        },
+        "repository": { "full_name": "test/repo", "name": "repo" },
+        "sender": { "login": "reviewer" },
+    });
+    ParsedEvent::from_payload(
+        "pull_request_review_comment",
+        &serde_json::to_vec(&payload).unwrap(),
+    )
+    .unwrap()
+}
+
+const ISSUE_URL: &str = "https://github.com/test/repo/issues/5";
+const PR_URL: &str = "https://github.com/test/repo/pull/5";
+
+#[test]
+fn test_comment_parsing() {
+    let event = issue_comment("created", ISSUE_URL, "Looks good", "test-user");
+    assert_eq!(event.event_key().as_deref(), Some("issue_comment.created"));
+    assert_eq!(event.labels(), vec!["bug".to_string()]);
+    assert!(event.targets_item_thread());
+
+    let event = review_comment("created");
+    assert_eq!(
+        event.event_key().as_deref(),
+        Some("pull_request_review_comment.created")
+    );
+    assert_eq!(event.actor(), Some("reviewer"));
+}
+
+#[test]
+fn test_comment_embeds_target_item_thread() {
+    let (_, title, _, _, footer) =
+        sidebar_embed(&issue_comment("created", ISSUE_URL, "Hi", "alice")).unwrap();
+    assert_eq!(title, "💬 #5 Crash on startup");
+    assert_eq!(footer.as_deref(), Some("by @alice"));
+
+    let (thread, ..) = sidebar_embed(&issue_comment("created", ISSUE_URL, "Hi", "alice")).unwrap();
+    assert_eq!(thread, "📋 Other issues");
+    let (thread, ..) = sidebar_embed(&issue_comment("created", PR_URL, "Hi", "alice")).unwrap();
+    assert_eq!(thread, "🧩 PR Opened");
+    let (thread, ..) = sidebar_embed(&review_comment("created")).unwrap();
+    assert_eq!(thread, "🪙 PR with bounty");
+}
+
+#[test]
+fn test_comment_excerpt_is_truncated() {
+    let body = "a".repeat(MAX_COMMENT_EXCERPT_LEN * 2);
+    let (title, description, _, _) =
+        activity_embed(&issue_comment("created", PR_URL, &body, "alice")).unwrap();
+
+    assert_eq!(title, "💬 Comment on PR #5");
+    assert!(description.contains(&format!("> {}…", "a".repeat(MAX_COMMENT_EXCERPT_LEN - 1))));
+    assert!(!description.contains(&"a".repeat(MAX_COMMENT_EXCERPT_LEN)));
+    assert!(
+        description.contains("[View Comment](https://github.com/test/repo/pull/5#issuecomment-99)")
+    );
+}
+
+#[tokio::test]
+async fn test_comments_follow_default_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "test/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "test/repo", "300", "42")
+        .await
+        .unwrap();
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    let destinations = |plan: &DispatchPlan| -> Vec<Destination> {
+        plan.messages
+            .iter()
+            .map(|m| m.destination.clone())
+            .collect()
+    };
+
+    let plan = dispatcher
+        .plan(&issue_comment("created", ISSUE_URL, "Hi", "alice"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(plan.matches.len(), 1);
+    assert_eq!(
+        destinations(&plan),
+        vec![
+            Destination::Activity {
+                thread_name: "📦 repo Activity".into()
+            },
+            Destination::ItemThread {
+                thread_name: "📋 Other issues".into()
+            },
+        ]
+    );
+
+    let plan = dispatcher
+        .plan(&review_comment("created"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan
+        .messages
+        .iter()
+        .any(|m| matches!(m.destination, Destination::ItemThread { .. })));
+
+    // Edits and bot comments are not posted
+    let plan = dispatcher
+        .plan(&issue_comment("edited", ISSUE_URL, "Hi", "alice"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.messages.is_empty());
+    let plan = dispatcher
+        .plan(&issue_comment(
+            "created",
+            ISSUE_URL,
+            "Coverage report",
+            "github-actions[bot]",
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.messages.is_empty());
+}
+
+#[tokio::test]
+async fn test_rule_can_silence_comments() {
+    let dispatcher = create_test_dispatcher();
+    let evaluation = RuleEvaluation {
+        rule_count: 1,
+        actor_whitelisted: false,
+        matches: vec![RuleMatch {
+            actions: RuleActions {
+                post_activity: false,
+                post_forum: false,
+                post_announce: false,
+                template: None,
+                continue_matching: false,
+            },
+            rule_id: "rule".into(),
+            priority: 0,
+            reason: "event is pull_request_review_comment.created".into(),
+        }],
+    };
+
+    let routing = dispatcher.routing(&review_comment("created"), &evaluation);
+    assert!(!routing.activity && !routing.sidebar && !routing.announce);
+}
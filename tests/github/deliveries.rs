@@ -0,0 +1,128 @@
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use bytehub::error::Error;
+use bytehub::github::deliveries::{self, DELIVERY_TTL_SECS};
+use bytehub::github::webhook::handle_webhook;
+use bytehub::governance::projects;
+use serde_json::json;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn signed_headers(event: &str, delivery_id: Option<&str>, body: &[u8]) -> HeaderMap {
+    let mac = crabgraph::mac::hmac_sha256(b"test_secret", body).unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-hub-signature-256",
+        format!("sha256={}", hex::encode(mac)).parse().unwrap(),
+    );
+    headers.insert("x-github-event", event.parse().unwrap());
+    if let Some(id) = delivery_id {
+        headers.insert("x-github-delivery", id.parse().unwrap());
+    }
+    headers
+}
+
+fn ping_payload() -> Bytes {
+    Bytes::from(
+        json!({
+            "zen": "Design for failure.",
+            "hook_id": 42,
+            "repository": { "full_name": "owner/repo", "name": "repo" },
+            "sender": { "login": "octocat" },
+        })
+        .to_string(),
+    )
+}
+
+async fn approved_state() -> bytehub::AppState {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+    state
+}
+
+#[tokio::test]
+async fn test_redelivery_is_acknowledged_but_dropped() {
+    let state = approved_state().await;
+    let body = ping_payload();
+    let before = deliveries::duplicates_dropped();
+
+    for _ in 0..2 {
+        let response = handle_webhook(
+            State(state.clone()),
+            signed_headers("ping", Some("72d3162e-cc78-11e3-81ab-4c9367dc0958"), &body),
+            body.clone(),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        assert!(response.status().is_success());
+    }
+
+    // Other tests in this binary may drop duplicates concurrently
+    assert!(deliveries::duplicates_dropped() > before);
+    assert!(!state
+        .db
+        .record_delivery("72d3162e-cc78-11e3-81ab-4c9367dc0958", DELIVERY_TTL_SECS)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_deliveries_without_guid_are_processed() {
+    let state = approved_state().await;
+    let body = ping_payload();
+
+    for _ in 0..2 {
+        let response = handle_webhook(
+            State(state.clone()),
+            signed_headers("ping", None, &body),
+            body.clone(),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        assert!(response.status().is_success());
+    }
+}
+
+#[tokio::test]
+async fn test_rejected_ping_can_be_redelivered() {
+    let state = common::create_state(Permissions::empty()).await;
+    let body = ping_payload();
+    let delivery_id = "0b6e4c52-6e1a-4d43-9a53-5f0e3c1f6a01";
+
+    let result = handle_webhook(
+        State(state.clone()),
+        signed_headers("ping", Some(delivery_id), &body),
+        body.clone(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+
+    // The rejected delivery was never claimed, so redelivering it works
+    handle_webhook(
+        State(state.clone()),
+        signed_headers("ping", Some(delivery_id), &body),
+        body,
+    )
+    .await
+    .unwrap();
+    assert!(!db
+        .record_delivery(delivery_id, DELIVERY_TTL_SECS)
+        .await
+        .unwrap());
+}
@@ -0,0 +1,223 @@
+use bytehub::discord::formatters::{COLOR_DEPLOYMENT, COLOR_FAILURE, COLOR_SUCCESS};
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::projects;
+use bytehub::governance::rules::{
+    self, RuleActions, RuleConditions, RuleEvaluation, DEFAULT_RULE_ID,
+};
+use bytehub::router::dispatch::{
+    activity_embed, sidebar_embed, Destination, Dispatcher, DEPLOYMENTS_THREAD,
+};
+use bytehub::router::messages;
+use serde_json::json;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn deployment() -> serde_json::Value {
+    json!({
+        "id": 42,
+        "sha": "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678",
+        "ref": "main",
+        "task": "deploy",
+        "environment": "production",
+        "description": "Deploy request from hubot",
+        "creator": { "login": "github-actions[bot]" },
+    })
+}
+
+fn parse(event_type: &str, payload: serde_json::Value) -> ParsedEvent {
+    ParsedEvent::from_payload(event_type, &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn deployment_created() -> ParsedEvent {
+    parse(
+        "deployment",
+        json!({
+            "action": "created",
+            "deployment": deployment(),
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "github-actions[bot]" },
+        }),
+    )
+}
+
+fn deployment_status(state: &str) -> ParsedEvent {
+    parse(
+        "deployment_status",
+        json!({
+            "action": "created",
+            "deployment_status": {
+                "id": 7,
+                "state": state,
+                "description": null,
+                "environment_url": "https://app.example.com",
+                "target_url": "https://app.example.com",
+                "log_url": "https://github.com/owner/repo/actions/runs/1",
+                "creator": { "login": "github-actions[bot]" },
+            },
+            "deployment": deployment(),
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "github-actions[bot]" },
+        }),
+    )
+}
+
+#[test]
+fn test_deployment_events_share_an_item_key() {
+    let created = deployment_created();
+    let status = deployment_status("in_progress");
+
+    assert_eq!(created.event_key().as_deref(), Some("deployment.created"));
+    assert_eq!(
+        status.event_key().as_deref(),
+        Some("deployment_status.created")
+    );
+    assert_eq!(created.branch(), Some("main"));
+    assert_eq!(
+        created.item_key().as_deref(),
+        Some("owner/repo/deployments/42")
+    );
+    assert_eq!(created.item_key(), status.item_key());
+}
+
+#[test]
+fn test_deployment_embed_follows_status() {
+    let (thread, title, description, color, footer) = sidebar_embed(&deployment_created()).unwrap();
+    assert_eq!(thread, DEPLOYMENTS_THREAD);
+    assert_eq!(title, "⏳ Deploy to production pending");
+    assert!(description.contains("`a1b2c3d` on `main` by @github-actions[bot]"));
+    assert!(description.contains("Deploy request from hubot"));
+    assert_eq!(color, COLOR_DEPLOYMENT);
+    assert_eq!(footer.as_deref(), Some("Deployment #42"));
+
+    for (state, expected_title, expected_color) in [
+        ("queued", "⏳ Deploy to production queued", COLOR_DEPLOYMENT),
+        (
+            "in_progress",
+            "🚀 Deploy to production in progress",
+            COLOR_DEPLOYMENT,
+        ),
+        (
+            "success",
+            "✅ Deploy to production succeeded",
+            COLOR_SUCCESS,
+        ),
+        ("failure", "❌ Deploy to production failed", COLOR_FAILURE),
+    ] {
+        let (thread, title, description, color, _) =
+            sidebar_embed(&deployment_status(state)).unwrap();
+        assert_eq!(thread, DEPLOYMENTS_THREAD);
+        assert_eq!(title, expected_title);
+        assert_eq!(color, expected_color);
+        assert!(description.contains("[Open production](https://app.example.com)"));
+        assert!(description.contains("[Logs](https://github.com/owner/repo/actions/runs/1)"));
+    }
+}
+
+#[tokio::test]
+async fn test_deployment_routing() {
+    let state = common::create_state(Permissions::empty()).await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    // Bot-made deployments still reach the Deployments thread
+    let routing = dispatcher.routing(&deployment_created(), &RuleEvaluation::default());
+    assert!(routing.sidebar);
+    assert!(!routing.activity);
+
+    let routing = dispatcher.routing(
+        &deployment_status("in_progress"),
+        &RuleEvaluation::default(),
+    );
+    assert!(routing.sidebar);
+    assert!(!routing.activity);
+
+    // Only the outcome is logged to the activity thread
+    let event = deployment_status("success");
+    assert!(dispatcher.should_log(&event));
+    assert!(activity_embed(&event).is_some());
+}
+
+#[tokio::test]
+async fn test_deployment_message_is_recorded_for_editing() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let plan = dispatcher
+        .plan(&deployment_created())
+        .await
+        .unwrap()
+        .unwrap();
+    let sidebar = plan
+        .messages
+        .iter()
+        .find(|m| matches!(m.destination, Destination::Sidebar { .. }))
+        .expect("default rules post deployments to the forum");
+    assert_eq!(
+        sidebar.edit_key.as_deref(),
+        Some("owner/repo/deployments/42")
+    );
+
+    dispatcher.dispatch(deployment_created()).await.unwrap();
+
+    // The mock creates the thread, whose starter message shares its ID
+    let posted = messages::get_posted_message(db, "owner/repo/deployments/42")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(posted.channel_id, "700");
+    assert_eq!(posted.message_id, "700");
+}
+
+#[tokio::test]
+async fn test_deployments_reach_projects_with_older_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    common::approve_with_original_rules(db, "owner/repo", "42").await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    for event in [deployment_created(), deployment_status("success")] {
+        let plan = dispatcher.plan(&event).await.unwrap().unwrap();
+        assert_eq!(plan.matches.len(), 1);
+        assert_eq!(plan.matches[0].rule_id, DEFAULT_RULE_ID);
+        assert!(plan.messages.iter().any(|m| m.destination
+            == Destination::Sidebar {
+                thread_name: DEPLOYMENTS_THREAD.to_string()
+            }));
+    }
+
+    // A rule naming the event type replaces the default
+    let project = projects::get_project(db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+    let conditions = RuleConditions {
+        event_type: Some("deployment.created".into()),
+        ..Default::default()
+    };
+    let actions = RuleActions {
+        post_activity: true,
+        post_forum: false,
+        post_announce: false,
+        template: None,
+        continue_matching: false,
+    };
+    rules::create_rule(db, &project.id, 0, &conditions, &actions)
+        .await
+        .unwrap();
+    let plan = dispatcher
+        .plan(&deployment_created())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(plan.matches[0].rule_id, DEFAULT_RULE_ID);
+    assert!(!plan
+        .messages
+        .iter()
+        .any(|m| matches!(m.destination, Destination::Sidebar { .. })));
+}
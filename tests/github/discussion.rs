@@ -0,0 +1,156 @@
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::projects;
+use bytehub::governance::rules::{RuleConditions, RuleContext};
+use bytehub::router::dispatch::{
+    activity_embed, sidebar_embed, Destination, Dispatcher, DISCUSSIONS_THREAD,
+};
+use bytehub::router::samples::{sample_event, SampleOptions};
+use serde_json::json;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn discussion(action: &str, answered: bool) -> ParsedEvent {
+    let answer_url = "https://github.com/test/repo/discussions/4#discussioncomment-9";
+    let payload = json!({
+        "action": action,
+        "discussion": {
+            "number": 4,
+            "title": "How do I configure rules?",
+            "html_url": "https://github.com/test/repo/discussions/4",
+            "category": { "name": "Q&A" },
+            "answer_html_url": if answered { Some(answer_url) } else { None },
+            "user": { "login": "asker" },
+        },
+        "answer": if answered {
+            json!({
+                "id": 9,
+                "body": "Use /rules add",
+                "html_url": answer_url,
+                "user": { "login": "helper" },
+            })
+        } else {
+            json!(null)
+        },
+        "repository": { "full_name": "test/repo", "name": "repo" },
+        "sender": { "login": "asker" },
+    });
+    ParsedEvent::from_payload("discussion", &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+#[test]
+fn test_discussion_parsing() {
+    let event = discussion("created", false);
+    assert_eq!(event.event_key().as_deref(), Some("discussion.created"));
+    assert_eq!(event.category(), Some("Q&A"));
+
+    let ParsedEvent::Discussion(answered) = discussion("answered", true) else {
+        panic!("expected a discussion event");
+    };
+    assert!(answered.discussion.is_answered());
+    assert_eq!(answered.answer.unwrap().user.login, "helper");
+}
+
+#[test]
+fn test_discussion_comment_parsing() {
+    let event = sample_event(
+        "test/repo",
+        "discussion_comment.created",
+        &SampleOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        event.event_key().as_deref(),
+        Some("discussion_comment.created")
+    );
+    assert_eq!(event.category(), Some("Q&A"));
+
+    let (title, description, _, _) = activity_embed(&event).unwrap();
+    assert_eq!(title, "💬 Comment on discussion #1");
+    assert!(description.contains("> Sample comment"));
+    assert!(sidebar_embed(&event).is_none());
+}
+
+#[test]
+fn test_discussions_share_a_sidebar_thread() {
+    let (thread, title, description, _, footer) =
+        sidebar_embed(&discussion("created", false)).unwrap();
+    assert_eq!(thread, DISCUSSIONS_THREAD);
+    assert_eq!(title, "How do I configure rules?");
+    assert!(description.starts_with("New in **Q&A**"));
+    assert_eq!(footer.as_deref(), Some("by @asker"));
+
+    let (thread, title, description, _, footer) =
+        sidebar_embed(&discussion("answered", true)).unwrap();
+    assert_eq!(thread, DISCUSSIONS_THREAD);
+    assert_eq!(title, "✅ How do I configure rules?");
+    assert!(description.contains("> Use /rules add"));
+    assert_eq!(footer.as_deref(), Some("answer by @helper"));
+}
+
+#[tokio::test]
+async fn test_discussions_follow_default_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "test/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "test/repo", "300", "42")
+        .await
+        .unwrap();
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let discussions_thread = Destination::Sidebar {
+        thread_name: DISCUSSIONS_THREAD.to_string(),
+    };
+
+    for event in [discussion("created", false), discussion("answered", true)] {
+        let plan = dispatcher.plan(&event).await.unwrap().unwrap();
+        assert_eq!(plan.matches.len(), 1);
+        assert!(plan.routing.activity);
+        assert!(!plan.routing.announce);
+        assert!(plan
+            .messages
+            .iter()
+            .any(|m| m.destination == discussions_thread));
+    }
+
+    let plan = dispatcher
+        .plan(&discussion("edited", false))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.messages.is_empty());
+
+    // Comments are only logged to the activity thread
+    let comment = sample_event(
+        "test/repo",
+        "discussion_comment.created",
+        &SampleOptions::default(),
+    )
+    .unwrap();
+    let plan = dispatcher.plan(&comment).await.unwrap().unwrap();
+    assert_eq!(plan.messages.len(), 1);
+    assert!(matches!(
+        plan.messages[0].destination,
+        Destination::Activity { .. }
+    ));
+}
+
+#[test]
+fn test_rule_matches_category() {
+    let conditions = RuleConditions {
+        event_type: Some("discussion.answered".into()),
+        category: Some("q&a".into()),
+        ..Default::default()
+    };
+    let ctx = RuleContext {
+        event_key: Some("discussion.answered".into()),
+        category: Some("Q&A".into()),
+        ..Default::default()
+    };
+
+    assert!(conditions.matches(&ctx));
+    assert!(!conditions.matches(&RuleContext {
+        category: Some("Ideas".into()),
+        ..ctx.clone()
+    }));
+}
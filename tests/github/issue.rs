@@ -1,20 +1,15 @@
 use bytehub::discord::client::DiscordClient;
 use bytehub::github::events::{Issue, IssueEvent, Label, ParsedEvent, Repository, User};
 use bytehub::router::dispatch::Dispatcher;
-use bytehub::storage::convex::ConvexDb;
+use bytehub::storage::memory::MemoryDb;
 use std::sync::Arc;
 
 async fn create_test_dispatcher() -> Dispatcher {
     // Install crabgraph TLS provider for tests
     let _ = crabgraph::tls::try_install_default();
 
-    dotenvy::dotenv().ok();
-    let convex_url = std::env::var("CONVEX_URL").expect("CONVEX_URL required for tests");
-    let db = ConvexDb::new(&convex_url)
-        .await
-        .expect("Failed to connect to Convex");
     let discord = Arc::new(DiscordClient::new("token", 123));
-    Dispatcher::new(db, discord)
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
 }
 
 #[tokio::test]
@@ -28,7 +23,11 @@ async fn test_issue_opened_triage() {
             title: "Test Issue".into(),
             html_url: "http://github.com".into(),
             labels: vec![],
+            milestone: None,
+            state: None,
+            user: None,
         },
+        milestone: None,
         repository: Repository {
             full_name: "test/repo".into(),
             name: "repo".into(),
@@ -57,7 +56,11 @@ async fn test_issue_with_bounty_announcement() {
             labels: vec![Label {
                 name: "bounty".into(),
             }],
+            milestone: None,
+            state: None,
+            user: None,
         },
+        milestone: None,
         repository: Repository {
             full_name: "test/repo".into(),
             name: "repo".into(),
@@ -82,7 +85,11 @@ async fn test_issue_labeled_triage() {
             title: "Labeled Issue".into(),
             html_url: "http://github.com".into(),
             labels: vec![Label { name: "bug".into() }],
+            milestone: None,
+            state: None,
+            user: None,
         },
+        milestone: None,
         repository: Repository {
             full_name: "test/repo".into(),
             name: "repo".into(),
@@ -0,0 +1,308 @@
+use bytehub::discord::formatters::{COLOR_PR, COLOR_SKIPPED, COLOR_SUCCESS};
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::projects;
+use bytehub::governance::rules::{self, RuleActions, RuleConditions};
+use bytehub::router::dispatch::{sidebar_embed, Destination, Dispatcher};
+use bytehub::router::messages;
+use bytehub::AppState;
+use serde_json::{json, Value};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+async fn recording_state() -> (AppState, Arc<Mutex<Vec<common::EditedEmbed>>>) {
+    let edits = Arc::new(Mutex::new(Vec::new()));
+    let state = common::create_state_with(common::MockDiscord {
+        permissions: Permissions::empty(),
+        fail_all: false,
+        fail_posts: Arc::new(AtomicBool::new(false)),
+        edits: edits.clone(),
+    })
+    .await;
+
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+    (state, edits)
+}
+
+fn parse(event_type: &str, payload: Value) -> ParsedEvent {
+    ParsedEvent::from_payload(event_type, &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn pull_request(
+    action: &str,
+    state: &str,
+    labels: &[&str],
+    merged_by: Option<&str>,
+) -> ParsedEvent {
+    let labels: Vec<Value> = labels.iter().map(|l| json!({ "name": l })).collect();
+    parse(
+        "pull_request",
+        json!({
+            "action": action,
+            "pull_request": {
+                "number": 7,
+                "title": "Add caching",
+                "html_url": "https://github.com/owner/repo/pull/7",
+                "merged": merged_by.is_some(),
+                "labels": labels,
+                "state": state,
+                "user": { "login": "alice" },
+                "merged_by": merged_by.map(|login| json!({ "login": login })),
+            },
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": merged_by.unwrap_or("alice") },
+        }),
+    )
+}
+
+fn issue(action: &str, number: u64, state: &str, sender: &str) -> ParsedEvent {
+    parse(
+        "issues",
+        json!({
+            "action": action,
+            "issue": {
+                "number": number,
+                "title": "Crash on startup",
+                "html_url": format!("https://github.com/owner/repo/issues/{}", number),
+                "labels": [],
+                "state": state,
+                "user": { "login": "alice" },
+            },
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": sender },
+        }),
+    )
+}
+
+fn workflow_run(action: &str, status: &str, conclusion: Option<&str>) -> ParsedEvent {
+    parse(
+        "workflow_run",
+        json!({
+            "action": action,
+            "workflow_run": {
+                "id": 99,
+                "name": "CI",
+                "status": status,
+                "conclusion": conclusion,
+                "html_url": "https://github.com/owner/repo/actions/runs/99",
+                "head_branch": "main",
+            },
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "alice" },
+        }),
+    )
+}
+
+#[test]
+fn test_item_embeds_follow_state() {
+    let (thread, _, description, color, footer) =
+        sidebar_embed(&pull_request("opened", "open", &[], None)).unwrap();
+    assert_eq!(thread, "🧩 PR Opened");
+    assert!(description.starts_with("Opened by @alice\n"));
+    assert!(!description.contains("Labels:"));
+    assert_eq!(color, COLOR_PR);
+    assert_eq!(footer.as_deref(), Some("by @alice"));
+
+    let (thread, _, description, color, footer) =
+        sidebar_embed(&pull_request("closed", "closed", &["perf"], Some("bob"))).unwrap();
+    assert_eq!(thread, "🧩 PR Merged");
+    assert!(description.starts_with("Merged by @bob\nLabels: `perf`\n"));
+    assert_eq!(color, COLOR_PR);
+    assert_eq!(footer.as_deref(), Some("by @alice"));
+
+    let (_, _, description, color, _) =
+        sidebar_embed(&issue("closed", 3, "closed", "bob")).unwrap();
+    assert!(description.starts_with("Closed by @bob\n"));
+    assert_eq!(color, COLOR_SKIPPED);
+
+    let (_, _, description, color, _) =
+        sidebar_embed(&workflow_run("in_progress", "in_progress", None)).unwrap();
+    assert!(description.contains("**CI** - in progress"));
+    assert_eq!(color, COLOR_SKIPPED);
+}
+
+#[tokio::test]
+async fn test_pr_message_is_edited_as_it_changes() {
+    let (state, edits) = recording_state().await;
+    let db = state.db.as_ref();
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    // Default rules only post merged pull requests to the forum
+    let project = projects::get_project(db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+    let conditions = RuleConditions {
+        event_type: Some("pull_request.opened".into()),
+        ..Default::default()
+    };
+    let actions = RuleActions {
+        post_activity: true,
+        post_forum: true,
+        post_announce: false,
+        template: None,
+        continue_matching: false,
+    };
+    rules::create_rule(db, &project.id, 100, &conditions, &actions)
+        .await
+        .unwrap();
+
+    dispatcher
+        .dispatch(pull_request("opened", "open", &[], None))
+        .await
+        .unwrap();
+    let posted = messages::get_posted_message(db, "owner/repo/pulls/7")
+        .await
+        .unwrap()
+        .expect("opened PRs are posted to the forum");
+    assert!(edits.lock().unwrap().is_empty());
+
+    dispatcher
+        .dispatch(pull_request("labeled", "open", &["perf"], None))
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(pull_request("closed", "closed", &["perf"], Some("bob")))
+        .await
+        .unwrap();
+
+    let edits = edits.lock().unwrap();
+    assert_eq!(edits.len(), 2);
+    for edit in edits.iter() {
+        assert_eq!(edit.channel_id.to_string(), posted.channel_id);
+        assert_eq!(edit.message_id.to_string(), posted.message_id);
+        assert_eq!(edit.title, "Add caching");
+    }
+    assert!(edits[0].description.contains("Labels: `perf`"));
+    assert!(edits[1].description.starts_with("Merged by @bob\n"));
+    assert_eq!(edits[1].color, COLOR_PR);
+}
+
+#[tokio::test]
+async fn test_closing_only_edits_posted_issues() {
+    let (state, edits) = recording_state().await;
+    let db = state.db.as_ref();
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    // Closing is not posted on its own, it only updates the issue's message
+    let plan = dispatcher
+        .plan(&issue("closed", 3, "closed", "bob"))
+        .await
+        .unwrap()
+        .unwrap();
+    let sidebar = plan
+        .messages
+        .iter()
+        .find(|m| matches!(m.destination, Destination::Sidebar { .. }))
+        .unwrap();
+    assert!(sidebar.edit_only);
+    assert_eq!(sidebar.edit_key.as_deref(), Some("owner/repo/issues/3"));
+
+    dispatcher
+        .dispatch(issue("closed", 3, "closed", "bob"))
+        .await
+        .unwrap();
+    assert!(edits.lock().unwrap().is_empty());
+    assert!(messages::get_posted_message(db, "owner/repo/issues/3")
+        .await
+        .unwrap()
+        .is_none());
+
+    dispatcher
+        .dispatch(issue("opened", 4, "open", "alice"))
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(issue("closed", 4, "closed", "bob"))
+        .await
+        .unwrap();
+
+    let edits = edits.lock().unwrap();
+    assert_eq!(edits.len(), 1);
+    assert!(edits[0].description.starts_with("Closed by @bob\n"));
+    assert_eq!(edits[0].color, COLOR_SKIPPED);
+}
+
+#[tokio::test]
+async fn test_rerun_workflow_updates_its_message() {
+    let (state, edits) = recording_state().await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    dispatcher
+        .dispatch(workflow_run("completed", "completed", Some("failure")))
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(workflow_run("in_progress", "in_progress", None))
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(workflow_run("completed", "completed", Some("success")))
+        .await
+        .unwrap();
+
+    let edits = edits.lock().unwrap();
+    let colors: Vec<u32> = edits.iter().map(|e| e.color).collect();
+    assert_eq!(colors, vec![COLOR_SKIPPED, COLOR_SUCCESS]);
+    assert!(edits[1].description.contains("**CI** - success"));
+}
+
+#[tokio::test]
+async fn test_bots_and_silencing_rules_do_not_edit() {
+    let (state, edits) = recording_state().await;
+    let db = state.db.as_ref();
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    // Merged pull requests are posted by the default rules
+    dispatcher
+        .dispatch(pull_request("closed", "closed", &[], Some("bob")))
+        .await
+        .unwrap();
+    assert!(messages::get_posted_message(db, "owner/repo/pulls/7")
+        .await
+        .unwrap()
+        .is_some());
+    let mut labeled = pull_request("labeled", "closed", &["dependencies"], Some("bob"));
+    if let ParsedEvent::PullRequest(e) = &mut labeled {
+        e.sender.login = "dependabot[bot]".into();
+    }
+    let plan = dispatcher.plan(&labeled).await.unwrap().unwrap();
+    assert!(plan.messages.is_empty());
+
+    // A rule keeping closed issues out of the forum also stops the edit
+    let project = projects::get_project(db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+    let conditions = RuleConditions {
+        event_type: Some("issues.closed".into()),
+        ..Default::default()
+    };
+    let actions = RuleActions {
+        post_activity: true,
+        post_forum: false,
+        post_announce: false,
+        template: None,
+        continue_matching: false,
+    };
+    rules::create_rule(db, &project.id, 100, &conditions, &actions)
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(issue("opened", 4, "open", "alice"))
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(issue("closed", 4, "closed", "bob"))
+        .await
+        .unwrap();
+
+    assert!(edits.lock().unwrap().is_empty());
+}
@@ -0,0 +1,260 @@
+use bytehub::discord::client::DiscordClient;
+use bytehub::discord::templates;
+use bytehub::github::events::{is_star_milestone, star_milestone_key, ParsedEvent};
+use bytehub::governance::rules::{RuleActions, RuleEvaluation, RuleMatch};
+use bytehub::governance::{projects, server_config};
+use bytehub::router::dispatch::{
+    activity_embed, announcement_embed, Destination, Dispatcher, Routing,
+};
+use bytehub::router::messages;
+use bytehub::storage::memory::MemoryDb;
+use serde_json::json;
+use std::sync::Arc;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn create_test_dispatcher() -> Dispatcher {
+    let _ = crabgraph::tls::try_install_default();
+    let discord = Arc::new(DiscordClient::new("token", 123));
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
+}
+
+fn parse(event_type: &str, payload: serde_json::Value) -> ParsedEvent {
+    ParsedEvent::from_payload(event_type, &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn repository_stats(stars: u64) -> serde_json::Value {
+    json!({
+        "full_name": "test/repo",
+        "name": "repo",
+        "html_url": "https://github.com/test/repo",
+        "stargazers_count": stars,
+        "forks_count": 12,
+    })
+}
+
+fn star_event(action: &str, stars: u64) -> ParsedEvent {
+    parse(
+        "star",
+        json!({
+            "action": action,
+            "starred_at": "2024-01-01T00:00:00Z",
+            "repository": repository_stats(stars),
+            "sender": { "login": "stargazer" },
+        }),
+    )
+}
+
+fn ref_event(event_type: &str, ref_type: &str, ref_name: &str) -> ParsedEvent {
+    parse(
+        event_type,
+        json!({
+            "ref": ref_name,
+            "ref_type": ref_type,
+            "master_branch": "main",
+            "pusher_type": "user",
+            "repository": { "full_name": "test/repo", "name": "repo" },
+            "sender": { "login": "maintainer" },
+        }),
+    )
+}
+
+fn matched(post_activity: bool) -> RuleEvaluation {
+    RuleEvaluation {
+        rule_count: 1,
+        actor_whitelisted: false,
+        matches: vec![RuleMatch {
+            actions: RuleActions {
+                post_activity,
+                post_forum: false,
+                post_announce: false,
+                template: None,
+                continue_matching: false,
+            },
+            rule_id: "1".into(),
+            priority: 0,
+            reason: "test".into(),
+        }],
+    }
+}
+
+#[test]
+fn test_star_milestones() {
+    for count in [100, 500, 1000, 5000, 10_000, 50_000, 100_000] {
+        assert!(is_star_milestone(count), "{} should be a milestone", count);
+    }
+    for count in [0, 1, 10, 50, 99, 101, 250, 999, 2000, 10_001] {
+        assert!(
+            !is_star_milestone(count),
+            "{} should not be a milestone",
+            count
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_star_is_only_announced_at_milestones() {
+    let dispatcher = create_test_dispatcher();
+
+    let event = star_event("created", 1000);
+    assert_eq!(event.event_key().as_deref(), Some("star.created"));
+    assert_eq!(event.repo_full_name(), Some("test/repo"));
+    assert_eq!(
+        dispatcher.routing(&event, &RuleEvaluation::default()),
+        Routing {
+            activity: true,
+            sidebar: false,
+            announce: true,
+            moderators: false,
+        }
+    );
+    let (title, description, _) = announcement_embed(&event).unwrap();
+    assert!(title.contains("1000 stars"));
+    assert!(description.contains("https://github.com/test/repo/stargazers"));
+
+    // Ordinary stars and un-stars stay silent
+    for event in [star_event("created", 1001), star_event("deleted", 1000)] {
+        assert_eq!(
+            dispatcher.routing(&event, &RuleEvaluation::default()),
+            Routing::default()
+        );
+        assert!(activity_embed(&event).is_none());
+        assert!(announcement_embed(&event).is_none());
+    }
+
+    // Rules cannot turn every star into a message either
+    let event = star_event("created", 1001);
+    assert!(!dispatcher.routing(&event, &matched(true)).activity);
+}
+
+#[tokio::test]
+async fn test_star_milestone_is_announced_once() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "test/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "test/repo", "300", "42")
+        .await
+        .unwrap();
+    server_config::save_config(db, "42", "100", "200")
+        .await
+        .unwrap();
+
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let announces = |plan: &bytehub::router::dispatch::DispatchPlan| {
+        plan.messages
+            .iter()
+            .any(|m| m.destination == Destination::Announcements)
+    };
+
+    let plan = dispatcher
+        .plan(&star_event("created", 1000))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(announces(&plan));
+    dispatcher
+        .dispatch(star_event("created", 1000))
+        .await
+        .unwrap();
+    let announced = messages::get_posted_message(db, &star_milestone_key("test/repo", 1000))
+        .await
+        .unwrap()
+        .expect("the announcement is recorded");
+    assert_eq!(announced.message_id, "900");
+
+    // Un-starring and starring again reaches 1000 once more
+    dispatcher
+        .dispatch(star_event("deleted", 999))
+        .await
+        .unwrap();
+    let plan = dispatcher
+        .plan(&star_event("created", 1000))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!announces(&plan));
+    assert!(plan.routing.announce);
+
+    // The next milestone is still announced
+    let plan = dispatcher
+        .plan(&star_event("created", 5000))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(announces(&plan));
+}
+
+#[tokio::test]
+async fn test_fork_is_logged() {
+    let dispatcher = create_test_dispatcher();
+    let event = parse(
+        "fork",
+        json!({
+            "forkee": {
+                "full_name": "someone/repo",
+                "html_url": "https://github.com/someone/repo",
+                "owner": { "login": "someone" },
+            },
+            "repository": repository_stats(42),
+            "sender": { "login": "someone" },
+        }),
+    );
+
+    assert_eq!(event.event_key().as_deref(), Some("fork"));
+    assert_eq!(event.actor(), Some("someone"));
+    let routing = dispatcher.routing(&event, &RuleEvaluation::default());
+    assert!(routing.activity);
+    assert!(!routing.sidebar && !routing.announce);
+
+    let (title, description, _, _) = activity_embed(&event).unwrap();
+    assert_eq!(title, "🍴 Forked by @someone");
+    assert!(description.contains("[someone/repo](https://github.com/someone/repo)"));
+    assert!(description.contains("12 forks"));
+}
+
+#[tokio::test]
+async fn test_tag_creation_is_distinct_from_release() {
+    let dispatcher = create_test_dispatcher();
+    let event = ref_event("create", "tag", "v1.2.0");
+
+    assert_eq!(event.event_key().as_deref(), Some("create.tag"));
+    assert_eq!(event.branch(), None);
+    let routing = dispatcher.routing(&event, &RuleEvaluation::default());
+    assert!(routing.activity);
+    assert!(!routing.sidebar && !routing.announce);
+
+    let (title, description, _, _) = activity_embed(&event).unwrap();
+    assert_eq!(title, "🏷️ Tag `v1.2.0` created");
+    assert!(description.contains("https://github.com/test/repo/tree/v1.2.0"));
+    assert!(announcement_embed(&event).is_none());
+
+    let fields = templates::fields(&event);
+    assert_eq!(fields.get("tag").map(String::as_str), Some("v1.2.0"));
+    assert_eq!(fields.get("action").map(String::as_str), Some("created"));
+}
+
+#[tokio::test]
+async fn test_branch_refs_need_a_rule() {
+    let dispatcher = create_test_dispatcher();
+
+    for event in [
+        ref_event("create", "branch", "feature/x"),
+        ref_event("delete", "branch", "feature/x"),
+        ref_event("delete", "tag", "v0.1.0"),
+    ] {
+        assert!(!dispatcher.should_log(&event));
+        assert!(dispatcher.routing(&event, &matched(true)).activity);
+        assert!(!dispatcher.routing(&event, &matched(false)).activity);
+    }
+
+    let event = ref_event("create", "branch", "feature/x");
+    assert_eq!(event.event_key().as_deref(), Some("create.branch"));
+    assert_eq!(event.branch(), Some("feature/x"));
+
+    let event = ref_event("delete", "branch", "feature/x");
+    assert_eq!(event.event_key().as_deref(), Some("delete.branch"));
+    let (title, _, _, _) = activity_embed(&event).unwrap();
+    assert_eq!(title, "🗑️ Branch `feature/x` deleted");
+}
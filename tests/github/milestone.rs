@@ -0,0 +1,269 @@
+use bytehub::discord::commands::{handle_milestone, CommandOption, InteractionData};
+use bytehub::discord::formatters::{progress_bar, COLOR_MILESTONE, COLOR_SUCCESS};
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::rules::{RuleEvaluation, DEFAULT_RULE_ID};
+use bytehub::governance::{milestones, projects};
+use bytehub::router::dispatch::{
+    activity_embed, sidebar_embed, Destination, Dispatcher, MILESTONES_THREAD,
+};
+use bytehub::router::messages;
+use serde_json::json;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn milestone(state: &str, open_issues: u64, closed_issues: u64) -> serde_json::Value {
+    json!({
+        "number": 3,
+        "title": "v1.0",
+        "description": "First stable release",
+        "html_url": "https://github.com/Owner/Repo/milestone/3",
+        "state": state,
+        "open_issues": open_issues,
+        "closed_issues": closed_issues,
+        "due_on": "2030-01-31T08:00:00Z",
+    })
+}
+
+fn parse(event_type: &str, payload: serde_json::Value) -> ParsedEvent {
+    ParsedEvent::from_payload(event_type, &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn milestone_event(
+    action: &str,
+    milestone: serde_json::Value,
+    changes: serde_json::Value,
+) -> ParsedEvent {
+    parse(
+        "milestone",
+        json!({
+            "action": action,
+            "milestone": milestone,
+            "changes": changes,
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "octocat" },
+        }),
+    )
+}
+
+fn issue_closed(milestone: serde_json::Value) -> ParsedEvent {
+    parse(
+        "issues",
+        json!({
+            "action": "closed",
+            "issue": {
+                "number": 7,
+                "title": "Ship it",
+                "html_url": "https://github.com/Owner/Repo/issues/7",
+                "labels": [],
+                "milestone": milestone,
+            },
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "octocat" },
+        }),
+    )
+}
+
+fn milestone_command(repo: &str) -> InteractionData {
+    InteractionData {
+        name: "milestone".into(),
+        options: Some(vec![CommandOption {
+            name: "repo".into(),
+            value: repo.into(),
+            options: None,
+        }]),
+    }
+}
+
+#[test]
+fn test_milestone_parsing() {
+    let event = milestone_event("created", milestone("open", 4, 6), json!(null));
+    assert_eq!(event.event_key().as_deref(), Some("milestone.created"));
+    assert_eq!(event.item_key().as_deref(), Some("owner/repo/milestones/3"));
+
+    let m = event.milestone().unwrap();
+    assert!(m.is_open());
+    assert_eq!(m.progress(), 60);
+    assert_eq!(m.due_date(), Some("2030-01-31"));
+
+    // Issues carry their milestone, including its current counts
+    let issue = issue_closed(milestone("open", 3, 7));
+    assert_eq!(issue.milestone().unwrap().closed_issues, 7);
+
+    // `demilestoned` sends the milestone next to the issue
+    let demilestoned = parse(
+        "issues",
+        json!({
+            "action": "demilestoned",
+            "issue": { "number": 7, "title": "Ship it", "html_url": "", "labels": [], "milestone": null },
+            "milestone": milestone("open", 3, 6),
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "octocat" },
+        }),
+    );
+    assert_eq!(demilestoned.milestone().unwrap().closed_issues, 6);
+
+    assert_eq!(progress_bar(60), "▰▰▰▰▰▰▱▱▱▱");
+    assert_eq!(progress_bar(0), "▱▱▱▱▱▱▱▱▱▱");
+}
+
+#[tokio::test]
+async fn test_milestone_routing_and_embeds() {
+    let state = common::create_state(Permissions::empty()).await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    let created = milestone_event("created", milestone("open", 4, 6), json!(null));
+    let routing = dispatcher.routing(&created, &RuleEvaluation::default());
+    assert!(routing.activity);
+    assert!(routing.sidebar);
+
+    let (title, description, color, _) = activity_embed(&created).unwrap();
+    assert_eq!(title, "🎯 Milestone created: v1.0");
+    assert!(description.contains("**60%** · 6 of 10 issues closed"));
+    assert!(description.contains("Due 2030-01-31"));
+    assert_eq!(color, COLOR_MILESTONE);
+
+    let (thread, title, description, _, footer) = sidebar_embed(&created).unwrap();
+    assert_eq!(thread, MILESTONES_THREAD);
+    assert_eq!(title, "🎯 v1.0");
+    assert!(description.contains("First stable release"));
+    assert_eq!(footer.as_deref(), Some("Milestone #3"));
+
+    // Later changes edit the posted message instead of posting again
+    let closed = milestone_event("closed", milestone("closed", 0, 10), json!(null));
+    assert!(dispatcher.should_log(&closed));
+    assert!(!dispatcher.should_post(&closed));
+    let (title, _, color, _) = activity_embed(&closed).unwrap();
+    assert_eq!(title, "🏁 Milestone closed: v1.0");
+    assert_eq!(color, COLOR_SUCCESS);
+
+    let rescheduled = milestone_event(
+        "edited",
+        milestone("open", 4, 6),
+        json!({ "due_on": { "from": null } }),
+    );
+    assert!(dispatcher.should_log(&rescheduled));
+    assert_eq!(
+        activity_embed(&rescheduled).unwrap().0,
+        "📅 Milestone rescheduled: v1.0"
+    );
+
+    let renamed = milestone_event(
+        "edited",
+        milestone("open", 4, 6),
+        json!({ "title": { "from": "v0.9" } }),
+    );
+    assert!(!dispatcher.should_log(&renamed));
+}
+
+#[tokio::test]
+async fn test_milestone_progress_follows_issues() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+    let project = projects::get_project(db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let created = milestone_event("created", milestone("open", 4, 6), json!(null));
+    let plan = dispatcher.plan(&created).await.unwrap().unwrap();
+    let sidebar = plan
+        .messages
+        .iter()
+        .find(|m| matches!(m.destination, Destination::Sidebar { .. }))
+        .expect("default rules post new milestones to the forum");
+    assert_eq!(sidebar.edit_key.as_deref(), Some("owner/repo/milestones/3"));
+
+    dispatcher.dispatch(created).await.unwrap();
+    assert!(messages::get_posted_message(db, "owner/repo/milestones/3")
+        .await
+        .unwrap()
+        .is_some());
+
+    dispatcher
+        .dispatch(issue_closed(milestone("open", 3, 7)))
+        .await
+        .unwrap();
+    let open = milestones::list_open_milestones(db, &project.id)
+        .await
+        .unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].progress(), 70);
+
+    let guild = Some("42".to_string());
+    let response = handle_milestone(db, &milestone_command("Owner/Repo"), &guild)
+        .await
+        .unwrap();
+    assert!(response.contains("[v1.0](https://github.com/Owner/Repo/milestone/3)"));
+    assert!(response.contains("**70%** · 7 of 10 issues closed"));
+
+    dispatcher
+        .dispatch(milestone_event(
+            "deleted",
+            milestone("open", 3, 7),
+            json!(null),
+        ))
+        .await
+        .unwrap();
+    let response = handle_milestone(db, &milestone_command("owner/repo"), &guild)
+        .await
+        .unwrap();
+    assert!(response.starts_with("No open milestones known"));
+}
+
+#[tokio::test]
+async fn test_milestone_command_is_scoped_to_the_server() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+
+    let response = handle_milestone(db, &milestone_command("owner/repo"), &Some("7".into()))
+        .await
+        .unwrap();
+    assert_eq!(response, "❌ Project `owner/repo` not found in this server");
+
+    let response = handle_milestone(db, &milestone_command("owner/other"), &Some("42".into()))
+        .await
+        .unwrap();
+    assert!(response.starts_with("❌"));
+}
+
+#[tokio::test]
+async fn test_milestones_reach_projects_with_older_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    common::approve_with_original_rules(db, "owner/repo", "42").await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    let created = milestone_event("created", milestone("open", 4, 6), json!(null));
+    let plan = dispatcher.plan(&created).await.unwrap().unwrap();
+    assert_eq!(plan.matches[0].rule_id, DEFAULT_RULE_ID);
+    assert!(plan.messages.iter().any(|m| m.destination
+        == Destination::Sidebar {
+            thread_name: MILESTONES_THREAD.to_string()
+        }));
+
+    dispatcher.dispatch(created).await.unwrap();
+    assert!(messages::get_posted_message(db, "owner/repo/milestones/3")
+        .await
+        .unwrap()
+        .is_some());
+
+    dispatcher
+        .dispatch(issue_closed(milestone("open", 3, 7)))
+        .await
+        .unwrap();
+    let response = handle_milestone(db, &milestone_command("owner/repo"), &Some("42".into()))
+        .await
+        .unwrap();
+    assert!(response.contains("**70%** · 7 of 10 issues closed"));
+}
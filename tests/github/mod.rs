@@ -1,4 +1,17 @@
+pub mod backfill;
+pub mod checks;
+pub mod comment;
+pub mod deliveries;
+pub mod deployment;
+pub mod discussion;
 pub mod issue;
+pub mod item_messages;
+pub mod lifecycle;
+pub mod milestone;
 pub mod pull_request;
+pub mod push;
 pub mod release;
+pub mod repository;
+pub mod review;
+pub mod security;
 pub mod workflow_run;
@@ -3,18 +3,13 @@ use bytehub::github::events::{
     Label, ParsedEvent, PullRequest, PullRequestEvent, Repository, User,
 };
 use bytehub::router::dispatch::Dispatcher;
-use bytehub::storage::convex::ConvexDb;
+use bytehub::storage::memory::MemoryDb;
 use std::sync::Arc;
 
 async fn create_test_dispatcher() -> Dispatcher {
     let _ = crabgraph::tls::try_install_default();
-    dotenvy::dotenv().ok();
-    let convex_url = std::env::var("CONVEX_URL").expect("CONVEX_URL required for tests");
-    let db = ConvexDb::new(&convex_url)
-        .await
-        .expect("Failed to connect to Convex");
     let discord = Arc::new(DiscordClient::new("token", 123));
-    Dispatcher::new(db, discord)
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
 }
 
 #[tokio::test]
@@ -29,6 +24,9 @@ async fn test_pr_opened_triage() {
             html_url: "http://github.com".into(),
             merged: Some(false),
             labels: vec![],
+            state: None,
+            user: None,
+            merged_by: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
@@ -57,6 +55,9 @@ async fn test_pr_merged_triage() {
             html_url: "http://github.com".into(),
             merged: Some(true),
             labels: vec![],
+            state: None,
+            user: None,
+            merged_by: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
@@ -86,6 +87,9 @@ async fn test_pr_with_bounty_announcement() {
             labels: vec![Label {
                 name: "bounty".into(),
             }],
+            state: None,
+            user: None,
+            merged_by: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
@@ -112,6 +116,9 @@ async fn test_bot_actor_exclusion() {
             html_url: "http://github.com".into(),
             merged: Some(false),
             labels: vec![],
+            state: None,
+            user: None,
+            merged_by: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
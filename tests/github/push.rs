@@ -0,0 +1,156 @@
+use bytehub::discord::client::DiscordClient;
+use bytehub::discord::formatters::{format_push, MAX_PUSH_COMMITS};
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::rules::{
+    RuleActions, RuleConditions, RuleContext, RuleEvaluation, RuleMatch,
+};
+use bytehub::router::dispatch::{activity_embed, Dispatcher};
+use bytehub::storage::memory::MemoryDb;
+use serde_json::json;
+use std::sync::Arc;
+
+fn create_test_dispatcher() -> Dispatcher {
+    let _ = crabgraph::tls::try_install_default();
+    let discord = Arc::new(DiscordClient::new("token", 123));
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
+}
+
+fn push_payload(git_ref: &str, commit_count: usize) -> serde_json::Value {
+    let commits: Vec<_> = (0..commit_count)
+        .map(|i| {
+            json!({
+                "id": format!("{:040x}", i + 1),
+                "message": format!("Commit number {}\n\nLonger description", i + 1),
+                "url": format!("https://github.com/test/repo/commit/{:040x}", i + 1),
+                "author": { "name": "Test User", "username": "test-user" },
+            })
+        })
+        .collect();
+
+    json!({
+        "ref": git_ref,
+        "before": "a".repeat(40),
+        "after": "b".repeat(40),
+        "compare": "https://github.com/test/repo/compare/aaaaaaa...bbbbbbb",
+        "forced": false,
+        "deleted": false,
+        "commits": commits,
+        "repository": { "full_name": "test/repo", "name": "repo" },
+        "sender": { "login": "test-user" },
+    })
+}
+
+fn parse(payload: &serde_json::Value) -> ParsedEvent {
+    ParsedEvent::from_payload("push", &serde_json::to_vec(payload).unwrap()).unwrap()
+}
+
+#[test]
+fn test_push_parsing() {
+    let event = parse(&push_payload("refs/heads/main", 2));
+
+    let ParsedEvent::Push(push) = &event else {
+        panic!("expected a push event");
+    };
+    assert_eq!(push.commits.len(), 2);
+    assert_eq!(push.commits[0].summary(), "Commit number 1");
+    assert_eq!(event.event_key().as_deref(), Some("push"));
+    assert_eq!(event.branch(), Some("main"));
+    assert_eq!(event.actor(), Some("test-user"));
+}
+
+#[test]
+fn test_tag_push_has_no_branch() {
+    let event = parse(&push_payload("refs/tags/v1.0.0", 1));
+    assert_eq!(event.branch(), None);
+}
+
+#[test]
+fn test_push_embed_lists_commits() {
+    let event = parse(&push_payload("refs/heads/main", 2));
+    let (title, description, _, _) = activity_embed(&event).unwrap();
+
+    assert_eq!(title, "⬆️ 2 commits pushed to `main`");
+    assert!(description.contains("[`0000000`](https://github.com/test/repo/commit/"));
+    assert!(description.contains("Commit number 2 - @test-user"));
+    assert!(!description.contains("Longer description"));
+    assert!(description.contains("[Compare changes](https://github.com/test/repo/compare/"));
+}
+
+#[test]
+fn test_large_push_is_truncated() {
+    let mut payload = push_payload("refs/heads/main", MAX_PUSH_COMMITS + 5);
+    payload["forced"] = json!(true);
+    payload["commits"][0]["message"] = json!("x".repeat(200));
+
+    let ParsedEvent::Push(push) = parse(&payload) else {
+        panic!("expected a push event");
+    };
+    let (title, description) = format_push(&push);
+
+    assert!(title.ends_with("(force-push)"));
+    assert!(description.contains("… and 5 more"));
+    assert!(!description.contains(&format!("Commit number {}", MAX_PUSH_COMMITS + 1)));
+    assert!(!description.contains(&"x".repeat(72)));
+    assert!(description.contains(&format!("{}…", "x".repeat(71))));
+}
+
+#[tokio::test]
+async fn test_push_routing_defaults_to_main_branch() {
+    let dispatcher = create_test_dispatcher();
+
+    assert!(dispatcher.should_log(&parse(&push_payload("refs/heads/main", 1))));
+    assert!(!dispatcher.should_log(&parse(&push_payload("refs/heads/feature/x", 1))));
+    assert!(!dispatcher.should_post(&parse(&push_payload("refs/heads/main", 1))));
+    assert!(!dispatcher.should_announce(&parse(&push_payload("refs/heads/main", 1))));
+
+    let mut deleted = push_payload("refs/heads/main", 0);
+    deleted["deleted"] = json!(true);
+    assert!(!dispatcher.should_log(&parse(&deleted)));
+}
+
+#[tokio::test]
+async fn test_branch_rule_selects_other_branches() {
+    let dispatcher = create_test_dispatcher();
+    let event = parse(&push_payload("refs/heads/release/1.2", 1));
+
+    let conditions = RuleConditions {
+        event_type: Some("push".into()),
+        branches: Some(vec!["release/*".into()]),
+        ..Default::default()
+    };
+    let ctx = RuleContext {
+        event_key: event.event_key(),
+        branch: event.branch().map(str::to_string),
+        ..Default::default()
+    };
+    assert!(conditions.matches(&ctx));
+    assert!(!conditions.matches(&RuleContext {
+        branch: Some("main".into()),
+        ..ctx.clone()
+    }));
+
+    let evaluation = RuleEvaluation {
+        rule_count: 1,
+        actor_whitelisted: false,
+        matches: vec![RuleMatch {
+            actions: RuleActions {
+                post_activity: true,
+                post_forum: false,
+                post_announce: false,
+                template: None,
+                continue_matching: false,
+            },
+            rule_id: "rule".into(),
+            priority: 0,
+            reason: conditions.describe(),
+        }],
+    };
+    assert!(dispatcher.routing(&event, &evaluation).activity);
+
+    // Without a matching rule only the default branch is logged
+    let unmatched = RuleEvaluation {
+        rule_count: 1,
+        ..Default::default()
+    };
+    assert!(!dispatcher.routing(&event, &unmatched).activity);
+}
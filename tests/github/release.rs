@@ -1,18 +1,13 @@
 use bytehub::discord::client::DiscordClient;
 use bytehub::github::events::{ParsedEvent, Release, ReleaseEvent, Repository, User};
 use bytehub::router::dispatch::Dispatcher;
-use bytehub::storage::convex::ConvexDb;
+use bytehub::storage::memory::MemoryDb;
 use std::sync::Arc;
 
 async fn create_test_dispatcher() -> Dispatcher {
     let _ = crabgraph::tls::try_install_default();
-    dotenvy::dotenv().ok();
-    let convex_url = std::env::var("CONVEX_URL").expect("CONVEX_URL required for tests");
-    let db = ConvexDb::new(&convex_url)
-        .await
-        .expect("Failed to connect to Convex");
     let discord = Arc::new(DiscordClient::new("token", 123));
-    Dispatcher::new(db, discord)
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
 }
 
 #[tokio::test]
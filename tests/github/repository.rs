@@ -0,0 +1,262 @@
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use bytehub::error::Error;
+use bytehub::github::events::ParsedEvent;
+use bytehub::github::webhook::handle_webhook;
+use bytehub::governance::rules::RuleEvaluation;
+use bytehub::governance::{projects, rules};
+use bytehub::router::dispatch::{activity_embed, Dispatcher, Routing};
+use bytehub::router::messages;
+use serde_json::json;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn signed_headers(event: &str, body: &[u8]) -> HeaderMap {
+    let mac = crabgraph::mac::hmac_sha256(b"test_secret", body).unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-hub-signature-256",
+        format!("sha256={}", hex::encode(mac)).parse().unwrap(),
+    );
+    headers.insert("x-github-event", event.parse().unwrap());
+    headers
+}
+
+fn ping_payload(repo: &str) -> Bytes {
+    Bytes::from(
+        json!({
+            "zen": "Keep it logically awesome.",
+            "hook_id": 42,
+            "hook": { "type": "Repository", "events": ["*"] },
+            "repository": { "full_name": repo, "name": repo.rsplit('/').next().unwrap() },
+            "sender": { "login": "octocat" },
+        })
+        .to_string(),
+    )
+}
+
+fn issue_event(action: &str, full_name: &str) -> ParsedEvent {
+    let payload = json!({
+        "action": action,
+        "issue": {
+            "number": 1,
+            "title": "Crash on startup",
+            "html_url": format!("https://github.com/{}/issues/1", full_name),
+            "labels": [],
+        },
+        "repository": {
+            "full_name": full_name,
+            "name": full_name.rsplit('/').next().unwrap(),
+        },
+        "sender": { "login": "octocat" },
+    });
+    ParsedEvent::from_payload("issues", &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn repository_event(action: &str, full_name: &str, changes: serde_json::Value) -> ParsedEvent {
+    let payload = json!({
+        "action": action,
+        "changes": changes,
+        "repository": {
+            "full_name": full_name,
+            "name": full_name.rsplit('/').next().unwrap(),
+        },
+        "sender": { "login": "octocat" },
+    });
+    ParsedEvent::from_payload("repository", &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+#[test]
+fn test_previous_full_name() {
+    let ParsedEvent::Repository(renamed) = repository_event(
+        "renamed",
+        "owner/new-name",
+        json!({ "repository": { "name": { "from": "old-name" } } }),
+    ) else {
+        panic!("expected a repository event");
+    };
+    assert_eq!(
+        renamed.previous_full_name().as_deref(),
+        Some("owner/old-name")
+    );
+
+    let ParsedEvent::Repository(transferred) = repository_event(
+        "transferred",
+        "new-org/repo",
+        json!({ "owner": { "from": { "user": { "login": "someone" } } } }),
+    ) else {
+        panic!("expected a repository event");
+    };
+    assert_eq!(
+        transferred.previous_full_name().as_deref(),
+        Some("someone/repo")
+    );
+
+    let ParsedEvent::Repository(archived) = repository_event("archived", "owner/repo", json!(null))
+    else {
+        panic!("expected a repository event");
+    };
+    assert_eq!(archived.previous_full_name(), None);
+}
+
+#[tokio::test]
+async fn test_ping_requires_approved_project() {
+    let state = common::create_state(Permissions::empty()).await;
+    let body = ping_payload("Owner/Repo");
+
+    let result = handle_webhook(
+        State(state.clone()),
+        signed_headers("ping", &body),
+        body.clone(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+
+    let response = handle_webhook(State(state), signed_headers("ping", &body), body)
+        .await
+        .unwrap()
+        .into_response();
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn test_ping_always_reaches_activity_thread() {
+    let state = common::create_state(Permissions::empty()).await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let event = ParsedEvent::from_payload("ping", &ping_payload("owner/repo")).unwrap();
+
+    assert_eq!(event.event_key().as_deref(), Some("ping"));
+    assert_eq!(
+        dispatcher.routing(&event, &RuleEvaluation::default()),
+        Routing {
+            activity: true,
+            ..Default::default()
+        }
+    );
+    let (title, description, _, footer) = activity_embed(&event).unwrap();
+    assert_eq!(title, "🔌 Webhook connected");
+    assert!(description.contains("**owner/repo**"));
+    assert_eq!(footer.as_deref(), Some("Hook ID: 42"));
+}
+
+#[tokio::test]
+async fn test_rename_moves_project_and_keeps_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/old-name")
+        .await
+        .unwrap();
+    projects::approve_project_with_forum(db, "owner/old-name", "300", "42")
+        .await
+        .unwrap();
+    let before = projects::get_project(db, "owner/old-name")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let event = repository_event(
+        "renamed",
+        "Owner/New-Name",
+        json!({ "repository": { "name": { "from": "Old-Name" } } }),
+    );
+    let (title, description, _, _) = activity_embed(&event).unwrap();
+    assert_eq!(title, "✏️ Repository renamed");
+    assert!(description.contains("`Owner/Old-Name` → `Owner/New-Name`"));
+
+    dispatcher.dispatch(event).await.unwrap();
+
+    assert!(projects::get_project(db, "owner/old-name")
+        .await
+        .unwrap()
+        .is_none());
+    let after = projects::get_project(db, "owner/new-name")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(after.id, before.id);
+    assert_eq!(after.name, "new-name");
+    assert!(after.is_approved);
+    assert_eq!(
+        rules::list_rules(db, &after.id).await.unwrap().len(),
+        rules::default_rules().len()
+    );
+}
+
+#[tokio::test]
+async fn test_items_are_edited_after_rename() {
+    let edits = Arc::new(Mutex::new(Vec::new()));
+    let state = common::create_state_with(common::MockDiscord {
+        permissions: Permissions::empty(),
+        fail_all: false,
+        fail_posts: Arc::new(AtomicBool::new(false)),
+        edits: edits.clone(),
+    })
+    .await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/old-name")
+        .await
+        .unwrap();
+    projects::approve_project_with_forum(db, "owner/old-name", "300", "42")
+        .await
+        .unwrap();
+
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    dispatcher
+        .dispatch(issue_event("opened", "owner/old-name"))
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(repository_event(
+            "renamed",
+            "owner/new-name",
+            json!({ "repository": { "name": { "from": "old-name" } } }),
+        ))
+        .await
+        .unwrap();
+    dispatcher
+        .dispatch(issue_event("closed", "owner/new-name"))
+        .await
+        .unwrap();
+
+    let posted = messages::get_posted_message(db, "owner/new-name/issues/1")
+        .await
+        .unwrap()
+        .expect("the issue's message follows the rename");
+    let edits = edits.lock().unwrap();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].message_id.to_string(), posted.message_id);
+    assert!(edits[0].description.starts_with("Closed by @octocat"));
+}
+
+#[tokio::test]
+async fn test_lifecycle_notices() {
+    let state = common::create_state(Permissions::empty()).await;
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    for (action, title) in [
+        ("archived", "🗄️ Repository archived"),
+        ("unarchived", "📂 Repository unarchived"),
+        ("deleted", "🗑️ Repository deleted"),
+    ] {
+        let event = repository_event(action, "owner/repo", json!(null));
+        assert!(dispatcher.should_log(&event));
+        assert_eq!(activity_embed(&event).unwrap().0, title);
+    }
+
+    let event = repository_event("publicized", "owner/repo", json!(null));
+    assert!(!dispatcher.should_log(&event));
+    assert!(activity_embed(&event).is_none());
+}
@@ -0,0 +1,162 @@
+use bytehub::discord::client::DiscordClient;
+use bytehub::discord::formatters::{
+    COLOR_REVIEW_APPROVED, COLOR_REVIEW_CHANGES, COLOR_REVIEW_COMMENTED,
+};
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::projects;
+use bytehub::governance::rules::{self, RuleActions, RuleConditions, RuleContext};
+use bytehub::router::dispatch::{activity_embed, sidebar_embed, Destination, Dispatcher};
+use bytehub::storage::memory::MemoryDb;
+use serde_json::json;
+use std::sync::Arc;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn create_test_dispatcher() -> Dispatcher {
+    let _ = crabgraph::tls::try_install_default();
+    let discord = Arc::new(DiscordClient::new("token", 123));
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
+}
+
+fn review_event(state: &str, body: Option<&str>) -> ParsedEvent {
+    let payload = json!({
+        "action": "submitted",
+        "review": {
+            "id": 11,
+            "state": state,
+            "body": body,
+            "html_url": "https://github.com/test/repo/pull/3#pullrequestreview-11",
+            "user": { "login": "maintainer" },
+        },
+        "pull_request": {
+            "number": 3,
+            "title": "Speed up parser",
+            "html_url": "https://github.com/test/repo/pull/3",
+            "labels": [],
+        },
+        "repository": { "full_name": "test/repo", "name": "repo" },
+        "sender": { "login": "maintainer" },
+    });
+    ParsedEvent::from_payload(
+        "pull_request_review",
+        &serde_json::to_vec(&payload).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_review_parsing() {
+    let event = review_event("approved", None);
+    assert_eq!(
+        event.event_key().as_deref(),
+        Some("pull_request_review.submitted")
+    );
+    assert_eq!(event.review_state(), Some("approved"));
+    assert_eq!(event.actor(), Some("maintainer"));
+    assert!(event.targets_item_thread());
+}
+
+#[test]
+fn test_review_embeds_by_state() {
+    let (title, description, color, _) = activity_embed(&review_event("approved", None)).unwrap();
+    assert_eq!(title, "✅ PR #3 approved");
+    assert!(description.starts_with("**Speed up parser**\nby @maintainer"));
+    assert_eq!(color, COLOR_REVIEW_APPROVED);
+
+    let (title, description, color, _) =
+        activity_embed(&review_event("changes_requested", Some("Please add tests"))).unwrap();
+    assert_eq!(title, "🔁 PR #3 changes requested");
+    assert!(description.contains("> Please add tests"));
+    assert_eq!(color, COLOR_REVIEW_CHANGES);
+
+    let (thread, title, description, color, _) =
+        sidebar_embed(&review_event("commented", None)).unwrap();
+    assert_eq!(thread, "🧩 PR Opened");
+    assert_eq!(title, "👀 #3 Speed up parser");
+    assert!(description.starts_with("Reviewed by @maintainer"));
+    assert_eq!(color, COLOR_REVIEW_COMMENTED);
+}
+
+#[tokio::test]
+async fn test_review_built_in_routing() {
+    let dispatcher = create_test_dispatcher();
+
+    assert!(dispatcher.should_log(&review_event("approved", None)));
+    assert!(dispatcher.should_post(&review_event("changes_requested", None)));
+    // Plain comment reviews are left to their review comments
+    assert!(!dispatcher.should_log(&review_event("commented", None)));
+    assert!(!dispatcher.should_announce(&review_event("approved", None)));
+}
+
+#[tokio::test]
+async fn test_reviews_follow_default_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "test/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "test/repo", "300", "42")
+        .await
+        .unwrap();
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+
+    for review_state in ["approved", "changes_requested"] {
+        let plan = dispatcher
+            .plan(&review_event(review_state, None))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(plan.matches.len(), 1);
+        assert!(plan.routing.activity);
+        assert!(plan.messages.iter().any(|m| m.destination
+            == Destination::ItemThread {
+                thread_name: "🧩 PR Opened".into()
+            }));
+    }
+
+    // Plain comment reviews are left to their review comments
+    let plan = dispatcher
+        .plan(&review_event("commented", None))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.matches.is_empty());
+    assert!(plan.messages.is_empty());
+}
+
+#[test]
+fn test_rule_matches_review_state() {
+    let conditions = RuleConditions {
+        event_type: Some("pull_request_review.submitted".into()),
+        review_state: Some("changes_requested".into()),
+        ..Default::default()
+    };
+    let ctx = |state: &str| RuleContext {
+        event_key: Some("pull_request_review.submitted".into()),
+        review_state: Some(state.into()),
+        ..Default::default()
+    };
+
+    assert!(conditions.matches(&ctx("changes_requested")));
+    assert!(!conditions.matches(&ctx("approved")));
+    assert_eq!(
+        conditions.describe(),
+        "event is pull_request_review.submitted, review is changes_requested"
+    );
+}
+
+#[test]
+fn test_invalid_review_state_rejected() {
+    let conditions = RuleConditions {
+        review_state: Some("APPROVED".into()),
+        ..Default::default()
+    };
+    let actions = RuleActions {
+        post_activity: true,
+        post_forum: false,
+        post_announce: false,
+        template: None,
+        continue_matching: false,
+    };
+    assert!(rules::validate_rule(0, &conditions, &actions).is_err());
+}
@@ -0,0 +1,208 @@
+use bytehub::discord::client::DiscordClient;
+use bytehub::discord::formatters::{
+    COLOR_SEVERITY_CRITICAL, COLOR_SEVERITY_HIGH, COLOR_SEVERITY_MEDIUM,
+};
+use bytehub::github::events::ParsedEvent;
+use bytehub::governance::rules::{RuleActions, RuleEvaluation, RuleMatch};
+use bytehub::router::dispatch::{
+    activity_embed, announcement_embed, moderator_embed, sidebar_embed, Dispatcher, Routing,
+};
+use bytehub::storage::memory::MemoryDb;
+use serde_json::json;
+use std::sync::Arc;
+
+fn create_test_dispatcher() -> Dispatcher {
+    let _ = crabgraph::tls::try_install_default();
+    let discord = Arc::new(DiscordClient::new("token", 123));
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
+}
+
+fn parse(event_type: &str, payload: serde_json::Value) -> ParsedEvent {
+    ParsedEvent::from_payload(event_type, &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn repository() -> serde_json::Value {
+    json!({ "full_name": "test/repo", "name": "repo" })
+}
+
+fn dependabot_alert(severity: &str) -> ParsedEvent {
+    parse(
+        "dependabot_alert",
+        json!({
+            "action": "created",
+            "alert": {
+                "number": 2,
+                "state": "open",
+                "html_url": "https://github.com/test/repo/security/dependabot/2",
+                "security_advisory": {
+                    "ghsa_id": "GHSA-xxxx-yyyy-zzzz",
+                    "summary": "Prototype pollution in lodash",
+                    "severity": severity,
+                },
+                "dependency": {
+                    "package": { "name": "lodash", "ecosystem": "npm" },
+                    "manifest_path": "package-lock.json",
+                },
+            },
+            "repository": repository(),
+            "sender": { "login": "dependabot[bot]" },
+        }),
+    )
+}
+
+fn code_scanning_alert(level: Option<&str>, severity: &str) -> ParsedEvent {
+    parse(
+        "code_scanning_alert",
+        json!({
+            "action": "created",
+            "ref": "refs/heads/main",
+            "alert": {
+                "number": 5,
+                "state": "open",
+                "html_url": "https://github.com/test/repo/security/code-scanning/5",
+                "rule": {
+                    "id": "js/sql-injection",
+                    "description": "Database query built from user-controlled sources",
+                    "severity": severity,
+                    "security_severity_level": level,
+                },
+                "tool": { "name": "CodeQL" },
+            },
+            "repository": repository(),
+            "sender": { "login": "github" },
+        }),
+    )
+}
+
+fn secret_scanning_alert() -> ParsedEvent {
+    parse(
+        "secret_scanning_alert",
+        json!({
+            "action": "created",
+            "alert": {
+                "number": 1,
+                "html_url": "https://github.com/test/repo/security/secret-scanning/1",
+                "secret_type": "github_personal_access_token",
+                "secret_type_display_name": "GitHub Personal Access Token",
+                "state": "open",
+            },
+            "repository": repository(),
+            "sender": { "login": "github" },
+        }),
+    )
+}
+
+#[test]
+fn test_security_events_parse() {
+    let event = dependabot_alert("high");
+    assert_eq!(
+        event.event_key().as_deref(),
+        Some("dependabot_alert.created")
+    );
+    assert!(event.is_security_alert());
+    assert_eq!(event.security_severity(), Some("high"));
+
+    let advisory = parse(
+        "security_advisory",
+        json!({
+            "action": "published",
+            "security_advisory": {
+                "ghsa_id": "GHSA-aaaa-bbbb-cccc",
+                "summary": "Remote code execution",
+                "severity": "critical",
+                "html_url": "https://github.com/advisories/GHSA-aaaa-bbbb-cccc",
+            },
+        }),
+    );
+    assert!(advisory.is_security_alert());
+    // Global advisories are not tied to a project
+    assert_eq!(advisory.repo_full_name(), None);
+
+    assert_eq!(
+        secret_scanning_alert().security_severity(),
+        Some("critical")
+    );
+}
+
+#[test]
+fn test_code_scanning_severity_fallback() {
+    assert_eq!(
+        code_scanning_alert(Some("medium"), "error").security_severity(),
+        Some("medium")
+    );
+    assert_eq!(
+        code_scanning_alert(None, "error").security_severity(),
+        Some("high")
+    );
+    assert_eq!(
+        code_scanning_alert(None, "note").security_severity(),
+        Some("low")
+    );
+}
+
+#[test]
+fn test_moderator_embeds_use_severity_colours() {
+    let (title, description, color) = moderator_embed(&dependabot_alert("high")).unwrap();
+    assert_eq!(title, "🛡️ Dependabot alert #2 created");
+    assert!(description.contains("Package: `lodash` (npm) in `package-lock.json`"));
+    assert!(description.contains("Severity: **high**"));
+    assert_eq!(color, COLOR_SEVERITY_HIGH);
+
+    let (_, description, color) =
+        moderator_embed(&code_scanning_alert(Some("medium"), "warning")).unwrap();
+    assert!(description.contains("Tool: CodeQL · Rule: `js/sql-injection`"));
+    assert_eq!(color, COLOR_SEVERITY_MEDIUM);
+
+    let (title, description, color) = moderator_embed(&secret_scanning_alert()).unwrap();
+    assert_eq!(title, "🔑 Secret scanning alert #1 created");
+    assert!(description.starts_with("**GitHub Personal Access Token** detected"));
+    assert_eq!(color, COLOR_SEVERITY_CRITICAL);
+}
+
+#[test]
+fn test_security_alerts_have_no_public_layout() {
+    let event = dependabot_alert("low");
+    assert!(activity_embed(&event).is_none());
+    assert!(sidebar_embed(&event).is_none());
+    assert!(announcement_embed(&event).is_none());
+}
+
+#[tokio::test]
+async fn test_security_alerts_only_reach_moderators() {
+    let dispatcher = create_test_dispatcher();
+    let only_moderators = Routing {
+        activity: false,
+        sidebar: false,
+        announce: false,
+        moderators: true,
+    };
+
+    // Dependabot is a bot actor, but its alerts are not filtered
+    let event = dependabot_alert("critical");
+    assert_eq!(
+        dispatcher.routing(&event, &RuleEvaluation::default()),
+        only_moderators
+    );
+
+    // Rules cannot publish security alerts
+    let publish_everything = RuleEvaluation {
+        rule_count: 1,
+        actor_whitelisted: false,
+        matches: vec![RuleMatch {
+            actions: RuleActions {
+                post_activity: true,
+                post_forum: true,
+                post_announce: true,
+                template: None,
+                continue_matching: false,
+            },
+            rule_id: "rule".into(),
+            priority: 0,
+            reason: "matches every event".into(),
+        }],
+    };
+    assert_eq!(
+        dispatcher.routing(&secret_scanning_alert(), &publish_everything),
+        only_moderators
+    );
+}
@@ -1,18 +1,13 @@
 use bytehub::discord::client::DiscordClient;
 use bytehub::github::events::{ParsedEvent, Repository, User, WorkflowRun, WorkflowRunEvent};
 use bytehub::router::dispatch::Dispatcher;
-use bytehub::storage::convex::ConvexDb;
+use bytehub::storage::memory::MemoryDb;
 use std::sync::Arc;
 
 async fn create_test_dispatcher() -> Dispatcher {
     let _ = crabgraph::tls::try_install_default();
-    dotenvy::dotenv().ok();
-    let convex_url = std::env::var("CONVEX_URL").expect("CONVEX_URL required for tests");
-    let db = ConvexDb::new(&convex_url)
-        .await
-        .expect("Failed to connect to Convex");
     let discord = Arc::new(DiscordClient::new("token", 123));
-    Dispatcher::new(db, discord)
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
 }
 
 #[tokio::test]
@@ -27,6 +22,7 @@ async fn test_workflow_success_triage() {
             conclusion: Some("success".into()),
             html_url: "http://github.com".into(),
             head_branch: Some("main".into()),
+            status: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
@@ -54,6 +50,7 @@ async fn test_workflow_failure_triage() {
             conclusion: Some("failure".into()),
             html_url: "http://github.com".into(),
             head_branch: Some("main".into()),
+            status: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
@@ -81,6 +78,7 @@ async fn test_workflow_in_progress_ignored() {
             conclusion: None,
             html_url: "http://github.com".into(),
             head_branch: Some("main".into()),
+            status: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
@@ -108,6 +106,7 @@ async fn test_workflow_feature_branch_ignored() {
             conclusion: Some("success".into()),
             html_url: "http://github.com".into(),
             head_branch: Some("feature/cool-stuff".into()),
+            status: None,
         },
         repository: Repository {
             full_name: "test/repo".into(),
@@ -0,0 +1,193 @@
+use bytehub::github::events::{Issue, IssueEvent, Label, ParsedEvent, Repository, User};
+use bytehub::governance::rules::{Rule, RuleActions, RuleConditions, RuleContext, RuleEvaluator};
+use bytehub::governance::{rules, whitelist};
+use bytehub::storage::memory::MemoryDb;
+
+fn issue_event(labels: &[&str], sender: &str) -> ParsedEvent {
+    ParsedEvent::Issue(IssueEvent {
+        action: "opened".into(),
+        issue: Issue {
+            number: 1,
+            title: "Test Issue".into(),
+            html_url: "http://github.com".into(),
+            labels: labels
+                .iter()
+                .map(|l| Label {
+                    name: l.to_string(),
+                })
+                .collect(),
+            milestone: None,
+            state: None,
+            user: None,
+        },
+        milestone: None,
+        repository: Repository {
+            full_name: "test/repo".into(),
+            name: "repo".into(),
+        },
+        sender: User {
+            login: sender.into(),
+        },
+    })
+}
+
+fn ctx_with_labels(labels: &[&str]) -> RuleContext {
+    RuleContext {
+        event_key: Some("issues.opened".into()),
+        labels: labels.iter().map(|l| l.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_labels_any_of() {
+    let conditions = RuleConditions {
+        labels: Some(vec!["security".into(), "bug".into()]),
+        ..Default::default()
+    };
+
+    assert!(conditions.matches(&ctx_with_labels(&["Security"])));
+    assert!(conditions.matches(&ctx_with_labels(&["bug", "docs"])));
+    assert!(!conditions.matches(&ctx_with_labels(&["docs"])));
+    assert!(!conditions.matches(&ctx_with_labels(&[])));
+}
+
+#[test]
+fn test_labels_all_of() {
+    let conditions = RuleConditions {
+        labels_all: Some(vec!["bounty".into(), "good first issue".into()]),
+        ..Default::default()
+    };
+
+    assert!(conditions.matches(&ctx_with_labels(&["bounty", "good first issue"])));
+    assert!(!conditions.matches(&ctx_with_labels(&["bounty"])));
+}
+
+#[test]
+fn test_labels_none_of() {
+    let conditions = RuleConditions {
+        event_type: Some("issues.opened".into()),
+        labels_none: Some(vec!["security".into()]),
+        ..Default::default()
+    };
+
+    assert!(conditions.matches(&ctx_with_labels(&["bug"])));
+    assert!(!conditions.matches(&ctx_with_labels(&["bug", "SECURITY"])));
+}
+
+#[test]
+fn test_actor_whitelisted_condition() {
+    let conditions = RuleConditions {
+        actor_whitelisted: Some(true),
+        ..Default::default()
+    };
+
+    let mut ctx = ctx_with_labels(&[]);
+    assert!(!conditions.matches(&ctx));
+    ctx.actor_whitelisted = true;
+    assert!(conditions.matches(&ctx));
+}
+
+#[tokio::test]
+async fn test_context_for_event_uses_labels_and_whitelist() {
+    let db = MemoryDb::new();
+    whitelist::add_user(&db, "42", "trusted-dev").await.unwrap();
+
+    let ctx = RuleContext::for_event(&db, "42", &issue_event(&["security"], "Trusted-Dev"))
+        .await
+        .unwrap();
+    assert_eq!(ctx.event_key.as_deref(), Some("issues.opened"));
+    assert_eq!(ctx.labels, vec!["security".to_string()]);
+    assert!(ctx.actor_whitelisted);
+
+    // The whitelist is per guild
+    let ctx = RuleContext::for_event(&db, "7", &issue_event(&[], "trusted-dev"))
+        .await
+        .unwrap();
+    assert!(!ctx.actor_whitelisted);
+
+    let ctx = RuleContext::for_event(&db, "42", &issue_event(&[], "stranger"))
+        .await
+        .unwrap();
+    assert!(!ctx.actor_whitelisted);
+
+    // Default rules ignore labels entirely
+    let (conditions, _) = &rules::default_rules()[3];
+    assert!(conditions.matches(&ctx));
+}
+
+fn rule(id: &str, priority: i64, conditions: RuleConditions, continue_matching: bool) -> Rule {
+    Rule {
+        id: id.into(),
+        project_id: "p1".into(),
+        priority,
+        conditions,
+        actions: RuleActions {
+            post_activity: true,
+            post_forum: true,
+            post_announce: false,
+            template: None,
+            continue_matching,
+        },
+    }
+}
+
+#[test]
+fn test_evaluator_stops_at_first_match_by_default() {
+    let evaluator = RuleEvaluator::new(vec![
+        rule("low", 1, RuleConditions::default(), false),
+        rule(
+            "high",
+            10,
+            RuleConditions {
+                event_type: Some("issues.opened".into()),
+                ..Default::default()
+            },
+            false,
+        ),
+    ]);
+
+    let evaluation = evaluator.evaluate(&ctx_with_labels(&[]));
+    assert_eq!(evaluation.rule_count, 2);
+    assert_eq!(evaluation.matches.len(), 1);
+    assert_eq!(evaluation.matches[0].rule_id, "high");
+    assert_eq!(evaluation.matches[0].reason, "event is issues.opened");
+}
+
+#[test]
+fn test_evaluator_continue_collects_multiple_matches() {
+    let evaluator = RuleEvaluator::new(vec![
+        rule("catch-all", 0, RuleConditions::default(), false),
+        rule(
+            "security",
+            5,
+            RuleConditions {
+                labels: Some(vec!["security".into()]),
+                ..Default::default()
+            },
+            true,
+        ),
+        rule(
+            "never",
+            3,
+            RuleConditions {
+                event_type: Some("release.published".into()),
+                ..Default::default()
+            },
+            true,
+        ),
+    ]);
+
+    let evaluation = evaluator.evaluate(&ctx_with_labels(&["security"]));
+    let ids: Vec<&str> = evaluation
+        .matches
+        .iter()
+        .map(|m| m.rule_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["security", "catch-all"]);
+    assert_eq!(evaluation.matches[1].reason, "matches every event");
+
+    // Without the label only the catch-all fires
+    let evaluation = evaluator.evaluate(&ctx_with_labels(&[]));
+    assert_eq!(evaluation.matches.len(), 1);
+}
@@ -0,0 +1,123 @@
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use bytehub::error::Error;
+use bytehub::governance::projects;
+use bytehub::router::dispatch::Destination;
+use bytehub::router::dry_run::handle_dry_run;
+use serde_json::json;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn headers(token: Option<&str>, event: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = token {
+        headers.insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+    }
+    headers.insert("x-github-event", event.parse().unwrap());
+    headers
+}
+
+fn release_payload() -> Bytes {
+    Bytes::from(
+        json!({
+            "action": "published",
+            "release": {
+                "tag_name": "v1.0.0",
+                "name": null,
+                "body": "Notes",
+                "html_url": "https://github.com/owner/repo/releases/v1.0.0"
+            },
+            "repository": { "full_name": "Owner/Repo", "name": "Repo" },
+            "sender": { "login": "octocat" }
+        })
+        .to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_dry_run_requires_admin_token() {
+    let state = common::create_state(Permissions::empty()).await;
+
+    let result = handle_dry_run(
+        State(state.clone()),
+        headers(None, "release"),
+        release_payload(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Unauthorized)));
+
+    let result = handle_dry_run(
+        State(state.clone()),
+        headers(Some("wrong"), "release"),
+        release_payload(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Unauthorized)));
+
+    // Disabled entirely without ADMIN_TOKEN
+    let mut disabled = state;
+    disabled.config.admin_token = None;
+    let result = handle_dry_run(
+        State(disabled),
+        headers(Some("test_admin_token"), "release"),
+        release_payload(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_dry_run_plans_release() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+
+    let plan = handle_dry_run(
+        State(state.clone()),
+        headers(Some("test_admin_token"), "release"),
+        release_payload(),
+    )
+    .await
+    .unwrap()
+    .0;
+
+    // Default rules: release.published goes everywhere
+    assert_eq!(plan.repo, "owner/repo");
+    assert_eq!(plan.matches.len(), 1);
+    let destinations: Vec<&Destination> = plan.messages.iter().map(|m| &m.destination).collect();
+    assert_eq!(
+        destinations,
+        vec![
+            &Destination::Activity {
+                thread_name: "📦 repo Activity".into()
+            },
+            &Destination::Sidebar {
+                thread_name: "🚀 Release Published".into()
+            },
+            &Destination::Announcements,
+        ]
+    );
+    assert_eq!(plan.messages[2].title, "🚀 New Release: v1.0.0");
+}
+
+#[tokio::test]
+async fn test_dry_run_unknown_project() {
+    let state = common::create_state(Permissions::empty()).await;
+
+    let result = handle_dry_run(
+        State(state),
+        headers(Some("test_admin_token"), "release"),
+        release_payload(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
@@ -0,0 +1,131 @@
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use bytehub::error::Error;
+use bytehub::github::events::ParsedEvent;
+use bytehub::github::webhook::handle_webhook;
+use bytehub::governance::{milestones, projects};
+use bytehub::router::queue::{Delivery, DispatchQueue};
+use serde_json::json;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn milestone_payload(repo: &str, closed_issues: u64) -> serde_json::Value {
+    json!({
+        "action": "edited",
+        "milestone": {
+            "number": 1,
+            "title": "v1.0",
+            "html_url": format!("https://github.com/{}/milestone/1", repo),
+            "state": "open",
+            "open_issues": 100 - closed_issues,
+            "closed_issues": closed_issues,
+        },
+        "repository": { "full_name": repo, "name": repo.rsplit('/').next().unwrap() },
+        "sender": { "login": "octocat" },
+    })
+}
+
+fn milestone_event(repo: &str, closed_issues: u64) -> ParsedEvent {
+    let payload = milestone_payload(repo, closed_issues);
+    ParsedEvent::from_payload("milestone", &serde_json::to_vec(&payload).unwrap()).unwrap()
+}
+
+fn delivery() -> Delivery {
+    Delivery {
+        delivery_id: None,
+        event_type: "milestone".into(),
+        payload: String::new(),
+    }
+}
+
+async fn approve(state: &bytehub::AppState, repo: &str) -> String {
+    let db = state.db.as_ref();
+    projects::submit_project(db, repo).await.unwrap();
+    projects::approve_project_with_forum(db, repo, "300", "42")
+        .await
+        .unwrap();
+    projects::get_project(db, repo).await.unwrap().unwrap().id
+}
+
+async fn progress(state: &bytehub::AppState, project_id: &str) -> u64 {
+    milestones::list_open_milestones(state.db.as_ref(), project_id)
+        .await
+        .unwrap()[0]
+        .closed_issues
+}
+
+#[tokio::test]
+async fn test_webhook_is_acknowledged_before_dispatch() {
+    let state = common::create_state(Permissions::empty()).await;
+    let project_id = approve(&state, "owner/repo").await;
+
+    let body = Bytes::from(milestone_payload("owner/repo", 40).to_string());
+    let mac = crabgraph::mac::hmac_sha256(b"test_secret", &body).unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-hub-signature-256",
+        format!("sha256={}", hex::encode(mac)).parse().unwrap(),
+    );
+    headers.insert("x-github-event", "milestone".parse().unwrap());
+
+    let response = handle_webhook(State(state.clone()), headers, body)
+        .await
+        .unwrap()
+        .into_response();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    state.queue.flush().await;
+    assert_eq!(progress(&state, &project_id).await, 40);
+}
+
+#[tokio::test]
+async fn test_events_of_a_repository_keep_their_order() {
+    let state = common::create_state(Permissions::empty()).await;
+    let first = approve(&state, "owner/first").await;
+    let second = approve(&state, "owner/second").await;
+
+    for closed_issues in 1..=10 {
+        state
+            .queue
+            .enqueue(milestone_event("owner/first", closed_issues), delivery())
+            .unwrap();
+        state
+            .queue
+            .enqueue(
+                milestone_event("Owner/Second", 20 + closed_issues),
+                delivery(),
+            )
+            .unwrap();
+    }
+    state.queue.flush().await;
+
+    // The last event of each repository is the one that sticks
+    assert_eq!(progress(&state, &first).await, 10);
+    assert_eq!(progress(&state, &second).await, 30);
+}
+
+#[tokio::test]
+async fn test_full_queue_rejects_events() {
+    let state = common::create_state(Permissions::empty()).await;
+    let queue = DispatchQueue::start(state.db.clone(), state.discord.clone(), 1, 1);
+
+    // The single-threaded test runtime does not run the worker until we yield
+    queue
+        .enqueue(milestone_event("owner/repo", 1), delivery())
+        .unwrap();
+    let result = queue.enqueue(milestone_event("owner/repo", 2), delivery());
+    assert!(matches!(result, Err(Error::Unavailable(_))));
+    assert_eq!(
+        Error::Unavailable("busy".into()).into_response().status(),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    queue.flush().await;
+    queue
+        .enqueue(milestone_event("owner/repo", 3), delivery())
+        .unwrap();
+}
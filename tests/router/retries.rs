@@ -0,0 +1,339 @@
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use bytehub::discord::commands::{handle_deliveries, CommandOption, InteractionData, Member, User};
+use bytehub::error::Error;
+use bytehub::github::deliveries::DELIVERY_TTL_SECS;
+use bytehub::github::webhook::handle_webhook;
+use bytehub::governance::projects;
+use bytehub::router::admin::{handle_get_failed, handle_list_failed, handle_replay_failed};
+use bytehub::router::dispatch::Destination;
+use bytehub::router::retries::{self, backoff_secs, AttemptFailure, FailedDelivery, MAX_ATTEMPTS};
+use bytehub::AppState;
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+const GUILD: &str = "42";
+
+async fn flaky_state() -> (AppState, Arc<AtomicBool>) {
+    let fail_posts = Arc::new(AtomicBool::new(true));
+    let state = common::create_state_with(common::MockDiscord {
+        permissions: Permissions::empty(),
+        fail_all: false,
+        fail_posts: fail_posts.clone(),
+        edits: Default::default(),
+    })
+    .await;
+
+    let db = state.db.as_ref();
+    projects::submit_project(db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "owner/repo", "300", GUILD)
+        .await
+        .unwrap();
+
+    (state, fail_posts)
+}
+
+fn issue_body() -> Bytes {
+    Bytes::from(
+        json!({
+            "action": "opened",
+            "issue": {
+                "number": 7,
+                "title": "Crash on start",
+                "html_url": "https://github.com/owner/repo/issues/7",
+                "labels": [],
+            },
+            "repository": { "full_name": "owner/repo", "name": "repo" },
+            "sender": { "login": "octocat" },
+        })
+        .to_string(),
+    )
+}
+
+async fn deliver_webhook(state: &AppState, body: Bytes, delivery_id: &str) -> StatusCode {
+    let mac = crabgraph::mac::hmac_sha256(b"test_secret", &body).unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-hub-signature-256",
+        format!("sha256={}", hex::encode(mac)).parse().unwrap(),
+    );
+    headers.insert("x-github-event", "issues".parse().unwrap());
+    headers.insert("x-github-delivery", delivery_id.parse().unwrap());
+
+    let status = handle_webhook(State(state.clone()), headers, body)
+        .await
+        .unwrap()
+        .into_response()
+        .status();
+    state.queue.flush().await;
+    status
+}
+
+async fn failed(state: &AppState) -> Vec<FailedDelivery> {
+    state.db.list_failed_deliveries().await.unwrap()
+}
+
+/// Make every stored delivery due and run one round of retries
+async fn retry_now(state: &AppState) -> usize {
+    for mut f in failed(state).await {
+        f.next_attempt_at = 0;
+        state.db.update_failed_delivery(&f).await.unwrap();
+    }
+    let queued = retries::retry_due(state.db.as_ref(), &state.queue)
+        .await
+        .unwrap();
+    state.queue.flush().await;
+    queued
+}
+
+fn admin_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("authorization", "Bearer test_admin_token".parse().unwrap());
+    headers
+}
+
+fn deliveries_command(subcommand: &str, options: Vec<CommandOption>) -> InteractionData {
+    InteractionData {
+        name: "deliveries".into(),
+        options: Some(vec![CommandOption {
+            name: subcommand.into(),
+            value: serde_json::Value::Null,
+            options: Some(options),
+        }]),
+    }
+}
+
+fn moderator() -> Member {
+    Member {
+        user: User { id: "1".into() },
+        roles: vec![],
+        permissions: Some("32".into()), // MANAGE_GUILD
+    }
+}
+
+#[test]
+fn test_backoff_doubles_up_to_an_hour() {
+    assert_eq!(backoff_secs(1), 60);
+    assert_eq!(backoff_secs(2), 120);
+    assert_eq!(backoff_secs(3), 240);
+    assert_eq!(backoff_secs(7), 3600);
+    assert_eq!(backoff_secs(u32::MAX), 3600);
+}
+
+#[tokio::test]
+async fn test_failed_dispatch_is_stored_and_retried() {
+    let (state, fail_posts) = flaky_state().await;
+    let body = issue_body();
+
+    assert_eq!(
+        deliver_webhook(&state, body.clone(), "guid-1").await,
+        StatusCode::ACCEPTED
+    );
+
+    let stored = failed(&state).await;
+    assert_eq!(stored.len(), 1);
+    let f = &stored[0];
+    assert_eq!(f.delivery_id.as_deref(), Some("guid-1"));
+    assert_eq!(f.event_type, "issues");
+    assert_eq!(f.repo, "owner/repo");
+    assert_eq!(f.payload.as_bytes(), &body[..]);
+    assert_eq!(f.attempts, 1);
+    assert!(!f.dead_lettered);
+    assert!(f.error.contains("Mock outage"));
+    assert!(!f.destinations.as_ref().unwrap().is_empty());
+
+    // Not due yet
+    assert_eq!(
+        retries::retry_due(state.db.as_ref(), &state.queue)
+            .await
+            .unwrap(),
+        0
+    );
+
+    // The claim is kept while retrying, so a redelivery is not posted twice
+    assert_eq!(
+        deliver_webhook(&state, body, "guid-1").await,
+        StatusCode::OK
+    );
+    assert_eq!(failed(&state).await.len(), 1);
+
+    fail_posts.store(false, Ordering::SeqCst);
+    assert_eq!(retry_now(&state).await, 1);
+    assert!(failed(&state).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_retry_destinations_round_trip() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    let mut failed = FailedDelivery {
+        id: String::new(),
+        delivery_id: None,
+        event_type: "issues".into(),
+        repo: "owner/repo".into(),
+        payload: "{}".into(),
+        destinations: Some(vec![Destination::Announcements]),
+        error: "Announcements: Discord error".into(),
+        attempts: 1,
+        next_attempt_at: 0,
+        dead_lettered: false,
+        created_at: 0,
+    };
+    failed.id = db.insert_failed_delivery(&failed).await.unwrap();
+
+    // A retry that fails before posting leaves the whole event to retry
+    let failure = AttemptFailure {
+        error: "storage unavailable".into(),
+        destinations: None,
+    };
+    retries::record_retry(db, failed.clone(), Some(failure)).await;
+    let stored = db.get_failed_delivery(&failed.id).await.unwrap().unwrap();
+    assert_eq!(stored.destinations, None);
+    assert_eq!(stored.attempts, 2);
+
+    let failure = AttemptFailure {
+        error: "Moderators: Discord error".into(),
+        destinations: Some(vec![Destination::Moderators]),
+    };
+    retries::record_retry(db, stored, Some(failure)).await;
+    let stored = db.get_failed_delivery(&failed.id).await.unwrap().unwrap();
+    assert_eq!(stored.destinations, Some(vec![Destination::Moderators]));
+}
+
+#[tokio::test]
+async fn test_delivery_is_dead_lettered_after_max_attempts() {
+    let (state, fail_posts) = flaky_state().await;
+    deliver_webhook(&state, issue_body(), "guid-2").await;
+
+    for _ in 1..MAX_ATTEMPTS {
+        assert_eq!(retry_now(&state).await, 1);
+    }
+
+    let f = failed(&state).await.remove(0);
+    assert_eq!(f.attempts, MAX_ATTEMPTS);
+    assert!(f.dead_lettered);
+
+    // Dead letters are left alone, and GitHub may redeliver them
+    assert_eq!(retry_now(&state).await, 0);
+    assert!(state
+        .db
+        .record_delivery("guid-2", DELIVERY_TTL_SECS)
+        .await
+        .unwrap());
+
+    fail_posts.store(false, Ordering::SeqCst);
+    retries::replay(state.db.as_ref(), &state.queue, &f.id)
+        .await
+        .unwrap();
+    state.queue.flush().await;
+    assert!(failed(&state).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_replayed_dead_letter_is_not_redelivered() {
+    let (state, fail_posts) = flaky_state().await;
+    deliver_webhook(&state, issue_body(), "guid-3").await;
+    for _ in 1..MAX_ATTEMPTS {
+        retry_now(&state).await;
+    }
+    let f = failed(&state).await.remove(0);
+    assert!(f.dead_lettered);
+
+    fail_posts.store(false, Ordering::SeqCst);
+    retries::replay(state.db.as_ref(), &state.queue, &f.id)
+        .await
+        .unwrap();
+    state.queue.flush().await;
+    assert!(failed(&state).await.is_empty());
+
+    // The replay posted it, so a redelivery from GitHub is dropped
+    assert_eq!(
+        deliver_webhook(&state, issue_body(), "guid-3").await,
+        StatusCode::OK
+    );
+    assert!(!state
+        .db
+        .record_delivery("guid-3", DELIVERY_TTL_SECS)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_admin_endpoints_inspect_and_replay() {
+    let (state, fail_posts) = flaky_state().await;
+    deliver_webhook(&state, issue_body(), "guid-3").await;
+
+    let result = handle_list_failed(State(state.clone()), HeaderMap::new()).await;
+    assert!(matches!(result, Err(Error::Unauthorized)));
+
+    let listed = handle_list_failed(State(state.clone()), admin_headers())
+        .await
+        .unwrap()
+        .0;
+    assert_eq!(listed.len(), 1);
+    let id = listed[0].id.clone();
+
+    let fetched = handle_get_failed(State(state.clone()), admin_headers(), Path(id.clone()))
+        .await
+        .unwrap()
+        .0;
+    assert_eq!(fetched, listed[0]);
+
+    let result = handle_get_failed(State(state.clone()), admin_headers(), Path("404".into())).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    fail_posts.store(false, Ordering::SeqCst);
+    let (status, _) = handle_replay_failed(State(state.clone()), admin_headers(), Path(id))
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::ACCEPTED);
+    state.queue.flush().await;
+    assert!(failed(&state).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_deliveries_command_is_scoped_to_the_server() {
+    let (state, fail_posts) = flaky_state().await;
+    deliver_webhook(&state, issue_body(), "guid-4").await;
+    let id = failed(&state).await[0].id.clone();
+
+    let data = deliveries_command("failed", vec![]);
+    let response = handle_deliveries(&state, Some(&moderator()), &data, &Some(GUILD.into()))
+        .await
+        .unwrap();
+    assert!(response.contains(&format!("`{}`", id)));
+    assert!(response.contains("`issues` on `owner/repo`"));
+
+    let response = handle_deliveries(&state, Some(&moderator()), &data, &Some("7".into()))
+        .await
+        .unwrap();
+    assert_eq!(response, "✅ No failed deliveries.");
+
+    let replay = deliveries_command(
+        "replay",
+        vec![CommandOption {
+            name: "id".into(),
+            value: id.clone().into(),
+            options: None,
+        }],
+    );
+    let response = handle_deliveries(&state, Some(&moderator()), &replay, &Some("7".into()))
+        .await
+        .unwrap();
+    assert!(response.starts_with("❌"));
+
+    fail_posts.store(false, Ordering::SeqCst);
+    let response = handle_deliveries(&state, Some(&moderator()), &replay, &Some(GUILD.into()))
+        .await
+        .unwrap();
+    assert!(response.starts_with("🔁"));
+    state.queue.flush().await;
+    assert!(failed(&state).await.is_empty());
+}
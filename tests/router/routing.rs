@@ -0,0 +1,265 @@
+use bytehub::discord::client::DiscordClient;
+use bytehub::github::events::{
+    Label, ParsedEvent, PullRequest, PullRequestEvent, Release, ReleaseEvent, Repository, User,
+};
+use bytehub::governance::rules::{RuleActions, RuleEvaluation, RuleMatch};
+use bytehub::governance::{projects, whitelist};
+use bytehub::router::dispatch::{Destination, Dispatcher, Routing};
+use bytehub::storage::memory::MemoryDb;
+use std::sync::Arc;
+use twilight_model::guild::Permissions;
+
+#[path = "../common/common.rs"]
+mod common;
+
+fn create_test_dispatcher() -> Dispatcher {
+    let _ = crabgraph::tls::try_install_default();
+    let discord = Arc::new(DiscordClient::new("token", 123));
+    Dispatcher::new(Arc::new(MemoryDb::new()), discord)
+}
+
+fn pr_event(action: &str, merged: bool, labels: &[&str], sender: &str) -> ParsedEvent {
+    ParsedEvent::PullRequest(PullRequestEvent {
+        action: action.into(),
+        pull_request: PullRequest {
+            number: 7,
+            title: "Test PR".into(),
+            html_url: "http://github.com".into(),
+            merged: Some(merged),
+            labels: labels
+                .iter()
+                .map(|l| Label {
+                    name: l.to_string(),
+                })
+                .collect(),
+            state: None,
+            user: None,
+            merged_by: None,
+        },
+        repository: Repository {
+            full_name: "test/repo".into(),
+            name: "repo".into(),
+        },
+        sender: User {
+            login: sender.into(),
+        },
+    })
+}
+
+fn actions(post_activity: bool, post_forum: bool, post_announce: bool) -> RuleActions {
+    RuleActions {
+        post_activity,
+        post_forum,
+        post_announce,
+        template: None,
+        continue_matching: false,
+    }
+}
+
+/// Evaluation of a project with rules where the given actions matched
+fn matched(actions: Vec<RuleActions>) -> RuleEvaluation {
+    RuleEvaluation {
+        rule_count: actions.len().max(1),
+        actor_whitelisted: false,
+        matches: actions
+            .into_iter()
+            .enumerate()
+            .map(|(i, actions)| RuleMatch {
+                actions,
+                rule_id: i.to_string(),
+                priority: 0,
+                reason: "test".into(),
+            })
+            .collect(),
+    }
+}
+
+#[tokio::test]
+async fn test_no_rules_falls_back_to_builtin_routing() {
+    let dispatcher = create_test_dispatcher();
+
+    let event = pr_event("opened", false, &["bounty"], "test-user");
+    let routing = dispatcher.routing(&event, &RuleEvaluation::default());
+
+    assert_eq!(
+        routing,
+        Routing {
+            activity: true,
+            sidebar: true,
+            announce: true,
+            moderators: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_matching_rule_drives_destinations() {
+    let dispatcher = create_test_dispatcher();
+
+    // A bounty PR would be announced by default, but the rule says otherwise
+    let event = pr_event("opened", false, &["bounty"], "test-user");
+    let routing = dispatcher.routing(&event, &matched(vec![actions(false, true, false)]));
+
+    assert_eq!(
+        routing,
+        Routing {
+            activity: false,
+            sidebar: true,
+            announce: false,
+            moderators: false,
+        }
+    );
+
+    // Rules can announce events the built-in logic would not
+    let event = pr_event("closed", true, &[], "test-user");
+    let routing = dispatcher.routing(&event, &matched(vec![actions(true, true, true)]));
+    assert!(routing.announce);
+}
+
+#[tokio::test]
+async fn test_unmatched_event_is_only_logged() {
+    let dispatcher = create_test_dispatcher();
+
+    let event = ParsedEvent::Release(ReleaseEvent {
+        action: "published".into(),
+        release: Release {
+            tag_name: "v1.0.0".into(),
+            name: None,
+            body: None,
+            html_url: "http://github.com".into(),
+        },
+        repository: Repository {
+            full_name: "test/repo".into(),
+            name: "repo".into(),
+        },
+        sender: User {
+            login: "test-user".into(),
+        },
+    });
+
+    let routing = dispatcher.routing(&event, &matched(vec![]));
+    assert_eq!(
+        routing,
+        Routing {
+            activity: true,
+            sidebar: false,
+            announce: false,
+            moderators: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_rules_keep_bot_and_eligibility_filters() {
+    let dispatcher = create_test_dispatcher();
+
+    // Bots never reach the activity thread or PR sidebar threads
+    let event = pr_event("opened", false, &[], "dependabot[bot]");
+    let routing = dispatcher.routing(&event, &matched(vec![actions(true, true, false)]));
+    assert!(!routing.activity);
+    assert!(!routing.sidebar);
+
+    // Sidebar threads only exist for opened/merged/labeled PRs
+    let event = pr_event("closed", false, &[], "test-user");
+    let routing = dispatcher.routing(&event, &matched(vec![actions(true, true, false)]));
+    assert!(routing.activity);
+    assert!(!routing.sidebar);
+}
+
+#[tokio::test]
+async fn test_multiple_matches_combine_destinations() {
+    let dispatcher = create_test_dispatcher();
+
+    // e.g. a "security" rule that announces and continues, then a generic PR rule
+    let event = pr_event("opened", false, &["security"], "test-user");
+    let routing = dispatcher.routing(
+        &event,
+        &matched(vec![
+            actions(false, false, true),
+            actions(true, true, false),
+        ]),
+    );
+
+    assert_eq!(
+        routing,
+        Routing {
+            activity: true,
+            sidebar: true,
+            announce: true,
+            moderators: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_whitelisted_actor_bypasses_bot_filter_and_is_announced() {
+    let dispatcher = create_test_dispatcher();
+    let trusted = RuleEvaluation {
+        actor_whitelisted: true,
+        ..Default::default()
+    };
+
+    // A whitelisted bot is treated like a person
+    let event = pr_event("opened", false, &[], "renovate[bot]");
+    assert_eq!(
+        dispatcher.routing(&event, &RuleEvaluation::default()),
+        Routing::default()
+    );
+    assert_eq!(
+        dispatcher.routing(&event, &trusted),
+        Routing {
+            activity: true,
+            sidebar: true,
+            announce: true,
+            moderators: false,
+        }
+    );
+
+    // Events that never get a sidebar post are not announced either
+    let event = pr_event("closed", false, &[], "octocat");
+    assert!(!dispatcher.routing(&event, &trusted).announce);
+}
+
+#[tokio::test]
+async fn test_whitelisted_actor_is_announced_under_default_rules() {
+    let state = common::create_state(Permissions::empty()).await;
+    let db = state.db.as_ref();
+    projects::submit_project(db, "test/repo").await.unwrap();
+    projects::approve_project_with_forum(db, "test/repo", "300", "42")
+        .await
+        .unwrap();
+    whitelist::add_user(db, "42", "renovate[bot]")
+        .await
+        .unwrap();
+    let dispatcher = Dispatcher::new(state.db.clone(), state.discord.clone());
+    let announces = |plan: &bytehub::router::dispatch::DispatchPlan| {
+        plan.messages
+            .iter()
+            .any(|m| m.destination == Destination::Announcements)
+    };
+
+    let plan = dispatcher
+        .plan(&pr_event("closed", true, &[], "renovate[bot]"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.routing.sidebar);
+    assert!(announces(&plan));
+
+    // Others still need a rule that announces
+    let plan = dispatcher
+        .plan(&pr_event("closed", true, &[], "octocat"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.routing.sidebar);
+    assert!(!announces(&plan));
+
+    // Nothing is posted for unmerged pull requests, so nothing is announced
+    let plan = dispatcher
+        .plan(&pr_event("closed", false, &[], "renovate[bot]"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!announces(&plan));
+}
@@ -0,0 +1,107 @@
+use bytehub::error::Error;
+use bytehub::governance::rules::{RuleContext, RuleEvaluator};
+use bytehub::governance::{projects, server_config, whitelist};
+use bytehub::storage::memory::MemoryDb;
+use bytehub::storage::Storage;
+
+fn ctx(event_key: &str, is_merged: bool) -> RuleContext {
+    RuleContext {
+        event_key: Some(event_key.into()),
+        is_merged,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_submit_lowercases_and_rejects_duplicates() {
+    let db = MemoryDb::new();
+
+    projects::submit_project(&db, "Owner/Repo").await.unwrap();
+    let project = projects::get_project(&db, "OWNER/repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(project.github_repo, "owner/repo");
+    assert_eq!(project.name, "repo");
+
+    let result = projects::submit_project(&db, "owner/repo").await;
+    assert!(matches!(result, Err(Error::ProjectAlreadyExists(_))));
+}
+
+#[tokio::test]
+async fn test_approve_with_forum_and_list_by_guild() {
+    let db = MemoryDb::new();
+
+    projects::submit_project(&db, "owner/a").await.unwrap();
+    projects::submit_project(&db, "owner/b").await.unwrap();
+    projects::approve_project_with_forum(&db, "owner/a", "300", "42")
+        .await
+        .unwrap();
+
+    let listed = projects::list_projects_by_guild(&db, "42").await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].github_repo, "owner/a");
+    assert!(projects::get_approved_project(&db, "owner/b")
+        .await
+        .unwrap()
+        .is_none());
+
+    // Default rules are created on approval
+    let project = &listed[0];
+    let project_rules = db.list_rules(&project.id).await.unwrap();
+    let issue = RuleEvaluator::new(project_rules).evaluate(&ctx("issues.opened", false));
+    assert_eq!(issue.matches.len(), 1);
+
+    let result = projects::approve_project(&db, "owner/missing").await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_deny_and_config_upsert() {
+    let db = MemoryDb::new();
+
+    projects::submit_project(&db, "owner/repo").await.unwrap();
+    projects::deny_project(&db, "owner/repo").await.unwrap();
+    assert!(matches!(
+        projects::deny_project(&db, "owner/repo").await,
+        Err(Error::NotFound(_))
+    ));
+
+    server_config::save_config(&db, "42", "100", "200")
+        .await
+        .unwrap();
+    server_config::save_config(&db, "42", "100", "201")
+        .await
+        .unwrap();
+    let config = server_config::get_config(&db, "42").await.unwrap().unwrap();
+    assert_eq!(config.github_forum_id, "201");
+}
+
+#[tokio::test]
+async fn test_whitelist_is_case_insensitive() {
+    let db = MemoryDb::new();
+
+    assert!(!whitelist::is_whitelisted(&db, "42", "octocat")
+        .await
+        .unwrap());
+    whitelist::add_user(&db, "42", "OctoCat").await.unwrap();
+    assert!(whitelist::is_whitelisted(&db, "42", "octocat")
+        .await
+        .unwrap());
+    assert!(!whitelist::is_whitelisted(&db, "7", "octocat")
+        .await
+        .unwrap());
+
+    whitelist::add_user(&db, "42", "hubot").await.unwrap();
+    assert_eq!(
+        whitelist::list_users(&db, "42").await.unwrap(),
+        vec!["hubot".to_string(), "octocat".to_string()]
+    );
+
+    whitelist::remove_user(&db, "42", "OCTOCAT").await.unwrap();
+    assert!(matches!(
+        whitelist::remove_user(&db, "42", "octocat").await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(whitelist::add_user(&db, "42", "not a user").await.is_err());
+}
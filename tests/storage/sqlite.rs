@@ -0,0 +1,512 @@
+use bytehub::error::Error;
+use bytehub::github::events::Milestone;
+use bytehub::governance::rules::{RuleContext, RuleEvaluator};
+use bytehub::governance::{milestones, projects, rules, server_config, whitelist};
+use bytehub::router::dispatch::Destination;
+use bytehub::router::messages;
+use bytehub::router::retries::FailedDelivery;
+use bytehub::storage::sqlite::SqliteDb;
+use bytehub::storage::Storage;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn ctx(event_key: &str, is_merged: bool) -> RuleContext {
+    RuleContext {
+        event_key: Some(event_key.into()),
+        is_merged,
+        ..Default::default()
+    }
+}
+
+fn open_db() -> SqliteDb {
+    SqliteDb::open(":memory:").expect("Failed to open in-memory SQLite")
+}
+
+#[tokio::test]
+async fn test_migrations_are_idempotent() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("bytehub-test-{}.db", nanos));
+    let path_str = path.to_str().unwrap();
+
+    let db = SqliteDb::open(path_str).unwrap();
+    let version = db.schema_version().await.unwrap();
+    projects::submit_project(&db, "owner/repo").await.unwrap();
+    drop(db);
+
+    // Re-opening must not re-apply migrations or lose data
+    let db = SqliteDb::open(path_str).unwrap();
+    assert_eq!(db.schema_version().await.unwrap(), version);
+    assert!(projects::get_project(&db, "owner/repo")
+        .await
+        .unwrap()
+        .is_some());
+
+    drop(db);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path_str, suffix));
+    }
+}
+
+#[tokio::test]
+async fn test_unscoped_whitelist_is_copied_to_each_server() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("bytehub-test-{}.db", nanos));
+    let path_str = path.to_str().unwrap();
+
+    // A database from before the whitelist was scoped per server
+    let conn = rusqlite::Connection::open(path_str).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            github_repo TEXT NOT NULL UNIQUE,
+            forum_channel_id TEXT NOT NULL DEFAULT '',
+            thread_id TEXT,
+            guild_id TEXT NOT NULL DEFAULT '',
+            is_approved INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE TABLE whitelist (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            github_username TEXT NOT NULL UNIQUE
+        );
+        CREATE TABLE server_config (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            guild_id TEXT NOT NULL UNIQUE,
+            announcements_id TEXT NOT NULL,
+            github_forum_id TEXT NOT NULL
+        );
+        INSERT INTO projects (name, github_repo, guild_id, is_approved)
+            VALUES ('repo', 'owner/repo', '7', 1);
+        INSERT INTO server_config (guild_id, announcements_id, github_forum_id)
+            VALUES ('42', '100', '200');
+        INSERT INTO whitelist (github_username) VALUES ('alice'), ('bob');
+        PRAGMA user_version = 1;",
+    )
+    .unwrap();
+    drop(conn);
+
+    let db = SqliteDb::open(path_str).unwrap();
+    for guild in ["7", "42"] {
+        assert_eq!(
+            whitelist::list_users(&db, guild).await.unwrap(),
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+    }
+    assert!(whitelist::list_users(&db, "9").await.unwrap().is_empty());
+
+    drop(db);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path_str, suffix));
+    }
+}
+
+#[tokio::test]
+async fn test_submit_lowercases_and_rejects_duplicates() {
+    let db = open_db();
+
+    projects::submit_project(&db, "Owner/Repo").await.unwrap();
+    let project = projects::get_project(&db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(project.github_repo, "owner/repo");
+    assert_eq!(project.name, "repo");
+    assert!(!project.is_approved);
+
+    let result = projects::submit_project(&db, "OWNER/REPO").await;
+    assert!(matches!(result, Err(Error::ProjectAlreadyExists(_))));
+}
+
+#[tokio::test]
+async fn test_approve_with_forum_creates_default_rules() {
+    let db = open_db();
+
+    projects::submit_project(&db, "owner/repo").await.unwrap();
+    assert!(projects::get_approved_project(&db, "owner/repo")
+        .await
+        .unwrap()
+        .is_none());
+
+    projects::approve_project_with_forum(&db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+
+    let project = projects::get_approved_project(&db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(project.forum_channel_id, "300");
+    assert_eq!(project.guild_id, "42");
+
+    let listed = projects::list_projects_by_guild(&db, "42").await.unwrap();
+    assert_eq!(listed.len(), 1);
+
+    // Default rules: merged PRs go to the forum, releases are announced
+    let project_rules = db.list_rules(&project.id).await.unwrap();
+    assert_eq!(project_rules.len(), rules::default_rules().len());
+    let evaluator = RuleEvaluator::new(project_rules);
+
+    let merged = evaluator.evaluate(&ctx("pull_request.closed", true));
+    assert_eq!(merged.matches.len(), 1);
+    assert!(merged.matches[0].actions.post_forum);
+    assert!(!merged.matches[0].actions.post_announce);
+
+    let release = evaluator.evaluate(&ctx("release.published", false));
+    assert!(release.matches[0].actions.post_announce);
+
+    let unmatched = evaluator.evaluate(&ctx("pull_request.closed", false));
+    assert!(unmatched.has_rules());
+    assert!(unmatched.matches.is_empty());
+}
+
+#[tokio::test]
+async fn test_deny_removes_project() {
+    let db = open_db();
+
+    projects::submit_project(&db, "owner/repo").await.unwrap();
+    projects::approve_project_with_forum(&db, "owner/repo", "300", "42")
+        .await
+        .unwrap();
+    projects::deny_project(&db, "owner/repo").await.unwrap();
+
+    assert!(projects::get_project(&db, "owner/repo")
+        .await
+        .unwrap()
+        .is_none());
+
+    let result = projects::deny_project(&db, "owner/repo").await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_rename_project() {
+    let db = open_db();
+
+    projects::submit_project(&db, "owner/old").await.unwrap();
+    projects::submit_project(&db, "owner/taken").await.unwrap();
+    let id = projects::get_project(&db, "owner/old")
+        .await
+        .unwrap()
+        .unwrap()
+        .id;
+
+    assert!(matches!(
+        projects::rename_project(&db, "owner/old", "owner/taken").await,
+        Err(Error::ProjectAlreadyExists(_))
+    ));
+    assert!(matches!(
+        projects::rename_project(&db, "owner/missing", "owner/other").await,
+        Err(Error::NotFound(_))
+    ));
+
+    projects::rename_project(&db, "Owner/Old", "NewOwner/New")
+        .await
+        .unwrap();
+    let project = projects::get_project(&db, "newowner/new")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(project.id, id);
+    assert_eq!(project.name, "new");
+}
+
+#[tokio::test]
+async fn test_server_config_upsert() {
+    let db = open_db();
+
+    assert!(server_config::get_config(&db, "42")
+        .await
+        .unwrap()
+        .is_none());
+
+    server_config::save_config(&db, "42", "100", "200")
+        .await
+        .unwrap();
+    server_config::save_config(&db, "42", "101", "200")
+        .await
+        .unwrap();
+
+    let config = server_config::get_config(&db, "42").await.unwrap().unwrap();
+    assert_eq!(config.announcements_id, "101");
+    assert_eq!(config.github_forum_id, "200");
+    assert_eq!(config.moderator_channel_id, None);
+}
+
+#[tokio::test]
+async fn test_moderator_channel_survives_config_save() {
+    let db = open_db();
+
+    assert!(matches!(
+        server_config::set_moderator_channel(&db, "42", "300").await,
+        Err(Error::NotFound(_))
+    ));
+
+    server_config::save_config(&db, "42", "100", "200")
+        .await
+        .unwrap();
+    server_config::set_moderator_channel(&db, "42", "300")
+        .await
+        .unwrap();
+    // Re-saving the other channels must not drop the moderator channel
+    server_config::save_config(&db, "42", "101", "200")
+        .await
+        .unwrap();
+
+    let config = server_config::get_config(&db, "42").await.unwrap().unwrap();
+    assert_eq!(config.moderator_channel_id.as_deref(), Some("300"));
+}
+
+#[tokio::test]
+async fn test_posted_message_upsert() {
+    let db = open_db();
+    let key = "owner/repo/deployments/1";
+
+    assert!(messages::get_posted_message(&db, key)
+        .await
+        .unwrap()
+        .is_none());
+
+    messages::save_posted_message(&db, key, "10", "20")
+        .await
+        .unwrap();
+    messages::save_posted_message(&db, key, "10", "21")
+        .await
+        .unwrap();
+
+    let posted = messages::get_posted_message(&db, key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(posted.channel_id, "10");
+    assert_eq!(posted.message_id, "21");
+}
+
+#[tokio::test]
+async fn test_posted_messages_follow_repo_rename() {
+    let db = open_db();
+    for (key, message_id) in [
+        ("owner/repo/issues/1", "1"),
+        ("owner/repo/milestones/2", "2"),
+        ("owner/repo-tools/issues/1", "3"),
+        ("owner/new/issues/1", "4"),
+    ] {
+        messages::save_posted_message(&db, key, "10", message_id)
+            .await
+            .unwrap();
+    }
+
+    messages::rename_repo(&db, "owner/repo", "owner/new")
+        .await
+        .unwrap();
+
+    let get = |key: &'static str| {
+        let db = &db;
+        async move { messages::get_posted_message(db, key).await.unwrap() }
+    };
+    assert!(get("owner/repo/issues/1").await.is_none());
+    assert!(get("owner/repo/milestones/2").await.is_none());
+    assert_eq!(get("owner/new/issues/1").await.unwrap().message_id, "1");
+    assert_eq!(get("owner/new/milestones/2").await.unwrap().message_id, "2");
+    // Repositories sharing the name as a prefix are left alone
+    assert_eq!(
+        get("owner/repo-tools/issues/1").await.unwrap().message_id,
+        "3"
+    );
+}
+
+#[tokio::test]
+async fn test_milestone_snapshots() {
+    let db = open_db();
+    projects::submit_project(&db, "owner/repo").await.unwrap();
+    let project = projects::get_project(&db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let milestone =
+        |number: u64, state: &str, closed_issues: u64, due_on: Option<&str>| Milestone {
+            number,
+            title: format!("v{}", number),
+            description: None,
+            html_url: format!("https://github.com/owner/repo/milestone/{}", number),
+            state: state.into(),
+            open_issues: 2,
+            closed_issues,
+            due_on: due_on.map(String::from),
+        };
+
+    for m in [
+        milestone(1, "open", 0, None),
+        milestone(2, "open", 1, Some("2030-06-01T00:00:00Z")),
+        milestone(3, "closed", 4, None),
+        // Replaces the first snapshot of #2
+        milestone(2, "open", 2, Some("2030-06-01T00:00:00Z")),
+    ] {
+        milestones::save_milestone(&db, &project.id, &m)
+            .await
+            .unwrap();
+    }
+
+    let open = milestones::list_open_milestones(&db, &project.id)
+        .await
+        .unwrap();
+    assert_eq!(
+        open.iter().map(|m| m.number).collect::<Vec<_>>(),
+        vec![2, 1],
+        "dated milestones come first, closed ones are left out"
+    );
+    assert_eq!(open[0].progress(), 50);
+    assert_eq!(open[0].due_date(), Some("2030-06-01"));
+
+    milestones::delete_milestone(&db, &project.id, 1)
+        .await
+        .unwrap();
+    milestones::delete_milestone(&db, &project.id, 99)
+        .await
+        .unwrap();
+    assert_eq!(db.list_milestones(&project.id).await.unwrap().len(), 2);
+
+    // Removed together with the project
+    projects::deny_project(&db, "owner/repo").await.unwrap();
+    assert!(db.list_milestones(&project.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_delivery_records_expire() {
+    let db = open_db();
+
+    assert!(db.record_delivery("a", 3600).await.unwrap());
+    assert!(!db.record_delivery("a", 3600).await.unwrap());
+
+    db.forget_delivery("a").await.unwrap();
+    assert!(db.record_delivery("a", 3600).await.unwrap());
+
+    // Expired right away, so the next delivery with the GUID is new again
+    assert!(db.record_delivery("b", 0).await.unwrap());
+    assert!(db.record_delivery("b", 3600).await.unwrap());
+    assert!(!db.record_delivery("b", 3600).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_failed_delivery_crud() {
+    let db = open_db();
+
+    let mut failed = FailedDelivery {
+        id: String::new(),
+        delivery_id: Some("guid".into()),
+        event_type: "issues".into(),
+        repo: "owner/repo".into(),
+        payload: "{}".into(),
+        destinations: Some(vec![Destination::Announcements]),
+        error: "Announcements: Discord error".into(),
+        attempts: 1,
+        next_attempt_at: 100,
+        dead_lettered: false,
+        created_at: 40,
+    };
+    failed.id = db.insert_failed_delivery(&failed).await.unwrap();
+    assert_eq!(
+        db.get_failed_delivery(&failed.id).await.unwrap().as_ref(),
+        Some(&failed)
+    );
+
+    // Retrying everything clears the destinations
+    failed.destinations = None;
+    failed.attempts = 5;
+    failed.dead_lettered = true;
+    db.update_failed_delivery(&failed).await.unwrap();
+
+    let second = db
+        .insert_failed_delivery(&FailedDelivery {
+            delivery_id: None,
+            created_at: 50,
+            ..failed.clone()
+        })
+        .await
+        .unwrap();
+    let listed = db.list_failed_deliveries().await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0], failed);
+    assert_eq!(listed[1].id, second);
+    assert_eq!(listed[1].delivery_id, None);
+
+    db.delete_failed_delivery(&failed.id).await.unwrap();
+    assert!(db.get_failed_delivery(&failed.id).await.unwrap().is_none());
+    assert!(matches!(
+        db.delete_failed_delivery(&failed.id).await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        db.update_failed_delivery(&failed).await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(db
+        .get_failed_delivery("not-a-number")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_rule_crud() {
+    let db = open_db();
+
+    projects::submit_project(&db, "owner/repo").await.unwrap();
+    let project = projects::get_project(&db, "owner/repo")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let (conditions, actions) = rules::default_rules().remove(0);
+    let id = rules::create_rule(&db, &project.id, 5, &conditions, &actions)
+        .await
+        .unwrap();
+    rules::set_priority(&db, &id, 7).await.unwrap();
+
+    let listed = rules::list_rules(&db, &project.id).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, id);
+    assert_eq!(listed[0].priority, 7);
+
+    rules::delete_rule(&db, &id).await.unwrap();
+    assert!(matches!(
+        rules::delete_rule(&db, &id).await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        rules::set_priority(&db, &id, 1).await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_whitelist_is_scoped_per_guild() {
+    let db = open_db();
+    assert!(db.schema_version().await.unwrap() >= 2);
+
+    whitelist::add_user(&db, "42", "OctoCat").await.unwrap();
+    whitelist::add_user(&db, "42", "octocat").await.unwrap();
+    whitelist::add_user(&db, "7", "hubot").await.unwrap();
+
+    assert!(whitelist::is_whitelisted(&db, "42", "OCTOCAT")
+        .await
+        .unwrap());
+    assert!(!whitelist::is_whitelisted(&db, "7", "octocat")
+        .await
+        .unwrap());
+    assert_eq!(
+        whitelist::list_users(&db, "42").await.unwrap(),
+        vec!["octocat".to_string()]
+    );
+
+    whitelist::remove_user(&db, "42", "octocat").await.unwrap();
+    assert!(matches!(
+        whitelist::remove_user(&db, "42", "octocat").await,
+        Err(Error::NotFound(_))
+    ));
+}